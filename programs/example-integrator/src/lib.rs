@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use svm_spoke::{
+    cpi_helpers::{approve_and_deposit, Deposit, DepositParams},
+    program::SvmSpoke,
+};
+
+declare_id!("3Qw4yJmpsLPfSrAYMmEiQQJZY9smahfZDLePvskTb5MS");
+
+// This program is an example of a third-party program depositing into the svm_spoke via CPI. Deposited tokens are held
+// in a program controlled token account that is not an ATA and the depositor is the vault_authority PDA, so the deposit
+// is signed with the PDA seeds. Access control is intentionally omitted as the program is only used in tests.
+
+#[program]
+pub mod example_integrator {
+    use super::*;
+
+    pub fn initialize_vault(_ctx: Context<InitializeVault>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn deposit_from_vault(ctx: Context<DepositFromVault>, params: VaultDepositParams) -> Result<()> {
+        let deposit_accounts = Deposit {
+            signer: ctx.accounts.vault_authority.to_account_info(),
+            state: ctx.accounts.spoke_state.to_account_info(),
+            route: ctx.accounts.spoke_route.to_account_info(),
+            depositor_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            vault: ctx.accounts.spoke_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            event_authority: ctx.accounts.spoke_event_authority.to_account_info(),
+            program: ctx.accounts.spoke_program.to_account_info(),
+        };
+        let deposit_params = DepositParams {
+            depositor: ctx.accounts.vault_authority.key(),
+            recipient: params.recipient,
+            input_token: ctx.accounts.mint.key(),
+            output_token: params.output_token,
+            input_amount: params.input_amount,
+            output_amount: params.output_amount,
+            destination_chain_id: params.destination_chain_id,
+            exclusive_relayer: params.exclusive_relayer,
+            quote_timestamp: params.quote_timestamp,
+            fill_deadline: params.fill_deadline,
+            exclusivity_parameter: params.exclusivity_parameter,
+            message: params.message,
+        };
+
+        let seeds = &[b"vault_authority".as_ref(), &[ctx.bumps.vault_authority]];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_context =
+            CpiContext::new_with_signer(ctx.accounts.spoke_program.to_account_info(), deposit_accounts, signer_seeds);
+
+        approve_and_deposit(cpi_context, deposit_params)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VaultDepositParams {
+    pub recipient: Pubkey,
+    pub output_token: Pubkey,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    pub destination_chain_id: u64,
+    pub exclusive_relayer: Pubkey,
+    pub quote_timestamp: u32,
+    pub fill_deadline: u32,
+    pub exclusivity_parameter: u32,
+    pub message: Vec<u8>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Only used as the depositor and the authority of the vault token account.
+    #[account(seeds = [b"vault_authority"], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"vault_token_account", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+        token::token_program = token_program
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositFromVault<'info> {
+    // Writable as the svm_spoke deposit signer is writable.
+    /// CHECK: Only used as the depositor and the authority of the vault token account.
+    #[account(mut, seeds = [b"vault_authority"], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, seeds = [b"vault_token_account", mint.key().as_ref()], bump)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Validated by the svm_spoke on deposit.
+    #[account(mut)]
+    pub spoke_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the svm_spoke on deposit.
+    pub spoke_route: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the svm_spoke on deposit.
+    #[account(mut)]
+    pub spoke_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the svm_spoke on deposit.
+    pub spoke_event_authority: UncheckedAccount<'info>,
+
+    pub spoke_program: Program<'info, SvmSpoke>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
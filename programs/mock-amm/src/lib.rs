@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("AQ8ncDvYumHf7cXpQ6cLr5k5tqauSCdCKHrbCShMWM7C");
+
+// This program is a minimal stand-in for a DEX that is used to test swap integrations (e.g. spoke_periphery). It swaps
+// at caller provided amounts against the pool token accounts owned by the pool_authority PDA, so tests can simulate
+// any exchange rate, partial fills of the input amount and slippage.
+
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>, amount_in: u64, amount_out: u64) -> Result<()> {
+        // Pull input tokens from the user.
+        let transfer_in_accounts = TransferChecked {
+            from: ctx.accounts.user_input_token_account.to_account_info(),
+            mint: ctx.accounts.input_mint.to_account_info(),
+            to: ctx.accounts.pool_input_token_account.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_context = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_in_accounts);
+        transfer_checked(cpi_context, amount_in, ctx.accounts.input_mint.decimals)?;
+
+        // Send output tokens from the pool signing with the pool authority PDA.
+        let seeds = &[b"pool_authority".as_ref(), &[ctx.bumps.pool_authority]];
+        let signer_seeds = &[&seeds[..]];
+        let transfer_out_accounts = TransferChecked {
+            from: ctx.accounts.pool_output_token_account.to_account_info(),
+            mint: ctx.accounts.output_mint.to_account_info(),
+            to: ctx.accounts.user_output_token_account.to_account_info(),
+            authority: ctx.accounts.pool_authority.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_out_accounts,
+            signer_seeds,
+        );
+        transfer_checked(cpi_context, amount_out, ctx.accounts.output_mint.decimals)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: Only used as the signing authority of the pool token accounts.
+    #[account(seeds = [b"pool_authority"], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = input_mint, token::authority = authority, token::token_program = token_program)]
+    pub user_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = output_mint, token::token_program = token_program)]
+    pub user_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = input_mint,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program
+    )]
+    pub pool_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = output_mint,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program
+    )]
+    pub pool_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -3,7 +3,13 @@ use anchor_lang::{
     solana_program::{
         instruction::Instruction,
         program::{invoke, invoke_signed},
+        system_instruction,
     },
+    Ids,
+};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -24,19 +30,56 @@ security_txt! {
 // to minimize the compute cost when finding the PDA.
 declare_id!("Fk1RpqsfeWt8KnFCTW9NQVdVxYvxuqjGn6iPB9wrmM8h");
 
+pub const MAX_ALLOWED_PROGRAMS: usize = 32;
+
 #[program]
 pub mod multicall_handler {
     use super::*;
 
     // Handler to receive Across message formatted as serialized message compiled instructions. When deserialized,
-    // these are matched with the passed accounts and executed as CPIs.
+    // these are matched with the passed accounts and executed as CPIs. The compiled instructions can optionally be
+    // followed by serialized balance injections that update the instruction data with token balances before the CPI,
+    // by the fallback recipient that receives any leftover handler_signer balances after all instructions, by the
+    // signer scope salt that selects the handler_signer PDA and by assertions checked after the CPIs. The invoked
+    // programs are restricted by the program allowlist PDA when passed among the accounts, see load_program_allowlist.
     pub fn handle_v3_across_message(ctx: Context<HandleV3AcrossMessage>, message: Vec<u8>) -> Result<()> {
+        let mut message_data = &message[..];
+        let compiled_ixs: Vec<CompiledIx> = AnchorDeserialize::deserialize(&mut message_data)?;
+        let balance_injections: Vec<BalanceInjection> = deserialize_optional(&mut message_data)?;
+        let fallback_recipient: Option<Pubkey> = deserialize_optional(&mut message_data)?;
+        let signer_scope: Option<[u8; 32]> = deserialize_optional(&mut message_data)?;
+        let assertions: Vec<Assertion> = deserialize_optional(&mut message_data)?;
+
         // Some instructions might require being signed by handler PDA.
-        let (handler_signer, bump) = Pubkey::find_program_address(&[b"handler_signer"], &crate::ID);
+        let mut signer_seeds = handler_signer_seeds(&signer_scope);
+        let (handler_signer, bump) = Pubkey::find_program_address(&signer_seeds, &crate::ID);
+        let bump_seed = [bump];
+        signer_seeds.push(&bump_seed);
+
+        let program_allowlist = load_program_allowlist(ctx.remaining_accounts)?;
 
-        let compiled_ixs: Vec<CompiledIx> = AnchorDeserialize::deserialize(&mut &message[..])?;
+        if balance_injections
+            .iter()
+            .any(|injection| injection.ix_index as usize >= compiled_ixs.len())
+        {
+            return err!(MulticallHandlerError::InvalidBalanceInjectionIndex);
+        }
+        if assertions
+            .iter()
+            .any(|assertion| assertion.after_ix_index as usize >= compiled_ixs.len())
+        {
+            return err!(MulticallHandlerError::InvalidAssertionIndex);
+        }
+
+        for (ix_index, mut compiled_ix) in compiled_ixs.into_iter().enumerate() {
+            // Balances are read just before the CPI so that they reflect the changes made by the previous instructions.
+            for injection in balance_injections
+                .iter()
+                .filter(|injection| injection.ix_index as usize == ix_index)
+            {
+                injection.inject(ctx.remaining_accounts, &mut compiled_ix.data)?;
+            }
 
-        for compiled_ix in compiled_ixs {
             // Will only sign with handler PDA if it is included in this instruction's accounts (checked below).
             let mut use_handler_signer = false;
 
@@ -47,6 +90,9 @@ pub mod multicall_handler {
                 .remaining_accounts
                 .get(compiled_ix.program_id_index as usize)
                 .ok_or(ErrorCode::AccountNotEnoughKeys)?;
+            if let Some(program_allowlist) = &program_allowlist {
+                program_allowlist.check(target_program.key)?;
+            }
 
             // Resolve CPI accounts from indexed references to the remaining accounts.
             for index in compiled_ix.account_key_indexes {
@@ -67,21 +113,413 @@ pub mod multicall_handler {
             let cpi_instruction = Instruction { program_id: target_program.key(), accounts, data: compiled_ix.data };
 
             match use_handler_signer {
-                true => invoke_signed(&cpi_instruction, &account_infos, &[&[b"handler_signer", &[bump]]])?,
+                true => invoke_signed(&cpi_instruction, &account_infos, &[&signer_seeds])?,
                 false => invoke(&cpi_instruction, &account_infos)?,
             }
+
+            for assertion in assertions
+                .iter()
+                .filter(|assertion| assertion.after_ix_index as usize == ix_index)
+            {
+                assertion.check(ctx.remaining_accounts)?;
+            }
+        }
+
+        // Otherwise leftover balances would be claimable by anyone crafting the next message.
+        if let Some(fallback_recipient) = fallback_recipient {
+            drain_leftover_balances(ctx.remaining_accounts, &handler_signer, &fallback_recipient, &signer_seeds)?;
+        }
+
+        Ok(())
+    }
+
+    // Sweeps all token balances held by the handler_signer in the passed token accounts and its lamports to the
+    // recipient. Only the passed accounts are drained, and token accounts are skipped when the recipient's associated
+    // token account is not passed or not initialized. This can only be invoked by this program as a compiled
+    // instruction step within the message, as the handler_signer has to sign it. The signer_scope must match the one
+    // in the message.
+    pub fn drain_leftover_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, DrainLeftoverTokens<'info>>,
+        signer_scope: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let mut signer_seeds = handler_signer_seeds(&signer_scope);
+        let (handler_signer, bump) = Pubkey::find_program_address(&signer_seeds, &crate::ID);
+        if ctx.accounts.handler_signer.key() != handler_signer {
+            return err!(MulticallHandlerError::InvalidHandlerSigner);
+        }
+        let bump_seed = [bump];
+        signer_seeds.push(&bump_seed);
+
+        let mut accounts = vec![
+            ctx.accounts.handler_signer.to_account_info(),
+            ctx.accounts.recipient.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ];
+        accounts.extend_from_slice(ctx.remaining_accounts);
+
+        drain_leftover_balances(&accounts, &handler_signer, ctx.accounts.recipient.key, &signer_seeds)
+    }
+
+    // Creates the program allowlist restricting the programs that can be invoked from messages. Only the upgrade
+    // authority of this program can initialize it and becomes its owner.
+    pub fn initialize_program_allowlist(ctx: Context<InitializeProgramAllowlist>) -> Result<()> {
+        let program_allowlist = &mut ctx.accounts.program_allowlist;
+        program_allowlist.owner = ctx.accounts.signer.key();
+        program_allowlist.bump = ctx.bumps.program_allowlist;
+
+        Ok(())
+    }
+
+    // Adds or removes the program from the allowlist. Only callable by the allowlist owner.
+    pub fn set_allowed_program(ctx: Context<SetAllowedProgram>, program: Pubkey, allowed: bool) -> Result<()> {
+        let programs = &mut ctx.accounts.program_allowlist.programs;
+        let position = programs.iter().position(|allowed_program| *allowed_program == program);
+
+        match (allowed, position) {
+            (true, None) => {
+                if programs.len() >= MAX_ALLOWED_PROGRAMS {
+                    return err!(MulticallHandlerError::ProgramAllowlistFull);
+                }
+                programs.push(program);
+            }
+            (false, Some(position)) => {
+                programs.swap_remove(position);
+            }
+            _ => {}
         }
 
         Ok(())
     }
 }
 
-#[derive(AnchorDeserialize)]
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramAllowlist {
+    pub owner: Pubkey,
+    #[max_len(MAX_ALLOWED_PROGRAMS)]
+    pub programs: Vec<Pubkey>, // Programs that can be invoked from messages, in addition to this program.
+    pub bump: u8,
+}
+
+impl ProgramAllowlist {
+    fn check(&self, program: &Pubkey) -> Result<()> {
+        // Self invocations are always allowed, as they only execute message steps like drain_leftover_tokens.
+        if *program != crate::ID && !self.programs.contains(program) {
+            return Err(error!(MulticallHandlerError::ProgramNotAllowed).with_account_name(program.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+// Loads the program allowlist if it is passed among the message accounts, not restricting the invoked programs until it
+// is initialized. Deployments built with the program-allowlist feature require it in every message, so that messages
+// cannot bypass the initialized allowlist by omitting it. Otherwise it is only enforced when passed in the message.
+fn load_program_allowlist(remaining_accounts: &[AccountInfo]) -> Result<Option<ProgramAllowlist>> {
+    let program_allowlist_key = program_allowlist_address();
+    match remaining_accounts
+        .iter()
+        .find(|account_info| *account_info.key == program_allowlist_key)
+    {
+        Some(account_info) if account_info.owner == &crate::ID => {
+            Ok(Some(ProgramAllowlist::try_deserialize(&mut &account_info.try_borrow_data()?[..])?))
+        }
+        Some(_) => Ok(None),
+        None if cfg!(feature = "program-allowlist") => err!(MulticallHandlerError::MissingProgramAllowlist),
+        None => Ok(None),
+    }
+}
+
+// Address of the program allowlist PDA that messages pass among their accounts.
+pub fn program_allowlist_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"program_allowlist"], &crate::ID).0
+}
+
+// Handler signer seeds without the bump. The optional scope is a salt chosen by the depositor, so that balances of its
+// handler_signer and token accounts are isolated from the ones of other integrations. The salt is not an authorization:
+// anyone can use the same salt in their messages, so scoped messages should not leave any balances behind.
+pub fn handler_signer_seeds(signer_scope: &Option<[u8; 32]>) -> Vec<&[u8]> {
+    match signer_scope {
+        Some(signer_scope) => vec![b"handler_signer", signer_scope],
+        None => vec![b"handler_signer"],
+    }
+}
+
+// Optional message fields are only deserialized when present, so that messages encoded before they were introduced
+// remain valid.
+fn deserialize_optional<T: AnchorDeserialize + Default>(data: &mut &[u8]) -> Result<T> {
+    match data.is_empty() {
+        true => Ok(T::default()),
+        false => Ok(T::deserialize(data)?),
+    }
+}
+
+// Transfers all handler_signer token balances found in the accounts to the recipient's associated token accounts and
+// handler_signer lamports to the recipient. Mints and token programs must also be passed. Balances whose recipient
+// token account is not passed or not initialized are left with the handler_signer and reported in the
+// LeftoverTokensNotDrained event, so that a missing token account does not revert the whole fill.
+fn drain_leftover_balances<'info>(
+    accounts: &[AccountInfo<'info>],
+    handler_signer: &Pubkey,
+    recipient: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let find_account = |key: &Pubkey| -> Result<&AccountInfo<'info>> {
+        Ok(accounts
+            .iter()
+            .find(|account_info| account_info.key == key)
+            .ok_or(ErrorCode::AccountNotEnoughKeys)?)
+    };
+    let signer_seeds = &[signer_seeds];
+
+    for account_info in accounts
+        .iter()
+        .filter(|account_info| TokenInterface::ids().contains(account_info.owner))
+    {
+        // Mints are also owned by the token program, but would not deserialize as token accounts.
+        let token_account = match TokenAccount::try_deserialize(&mut &account_info.try_borrow_data()?[..]) {
+            Ok(token_account) => token_account,
+            Err(_) => continue,
+        };
+        if token_account.owner != *handler_signer || token_account.amount == 0 {
+            continue;
+        }
+
+        let recipient_token_account =
+            get_associated_token_address_with_program_id(recipient, &token_account.mint, account_info.owner);
+        let recipient_token_account_info = match accounts
+            .iter()
+            .find(|account_info| *account_info.key == recipient_token_account)
+            .filter(|recipient_token_account_info| recipient_token_account_info.owner == account_info.owner)
+        {
+            Some(recipient_token_account_info) => recipient_token_account_info,
+            None => {
+                emit!(LeftoverTokensNotDrained {
+                    token_account: account_info.key(),
+                    recipient_token_account,
+                    amount: token_account.amount,
+                });
+                continue;
+            }
+        };
+        let mint = find_account(&token_account.mint)?;
+        let cpi_accounts = TransferChecked {
+            from: account_info.to_owned(),
+            mint: mint.to_owned(),
+            to: recipient_token_account_info.to_owned(),
+            authority: find_account(handler_signer)?.to_owned(),
+        };
+        let decimals = Mint::try_deserialize(&mut &mint.try_borrow_data()?[..])?.decimals;
+        let cpi_context =
+            CpiContext::new_with_signer(find_account(account_info.owner)?.to_owned(), cpi_accounts, signer_seeds);
+        transfer_checked(cpi_context, token_account.amount, decimals)?;
+    }
+
+    // Lamports can only be drained when the handler_signer was passed as writable.
+    let handler_signer_info = accounts.iter().find(|account_info| account_info.key == handler_signer);
+    if let Some(handler_signer_info) = handler_signer_info.filter(|account_info| account_info.is_writable) {
+        let lamports = handler_signer_info.lamports();
+        if lamports > 0 {
+            let transfer_ix = system_instruction::transfer(handler_signer, recipient, lamports);
+            let recipient_info = find_account(recipient)?;
+            let system_program = find_account(&anchor_lang::system_program::ID)?;
+            invoke_signed(
+                &transfer_ix,
+                &[
+                    handler_signer_info.to_owned(),
+                    recipient_info.to_owned(),
+                    system_program.to_owned(),
+                ],
+                signer_seeds,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct CompiledIx {
     pub program_id_index: u8,
     pub account_key_indexes: Vec<u8>,
     pub data: Vec<u8>,
 }
 
+// Writes the current balance of a token account into the data of a compiled instruction, so that instructions can use
+// the actual received amount. This is the equivalent of makeCallWithBalance in the EVM MulticallHandler.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BalanceInjection {
+    pub ix_index: u8,            // Index of the compiled instruction to update.
+    pub token_account_index: u8, // Index of the token account in the remaining accounts.
+    pub offset: u16,             // Offset in the instruction data where the balance is written as little-endian u64.
+}
+
+impl BalanceInjection {
+    fn inject(&self, remaining_accounts: &[AccountInfo], data: &mut [u8]) -> Result<()> {
+        let balance = token_balance(remaining_accounts, self.token_account_index)?;
+
+        let offset = self.offset as usize;
+        let balance_data = data
+            .get_mut(offset..offset + 8)
+            .ok_or(MulticallHandlerError::InvalidBalanceInjectionOffset)?;
+        balance_data.copy_from_slice(&balance.to_le_bytes());
+
+        Ok(())
+    }
+}
+
+// Post-condition checked after the compiled instruction at after_ix_index, failing the whole message if not met. This
+// protects flows like swaps on arrival even when the invoked program lacks slippage protection.
+#[derive(AnchorDeserialize)]
+pub struct Assertion {
+    pub after_ix_index: u8,
+    pub kind: AssertionKind,
+}
+
+#[derive(AnchorDeserialize)]
+pub enum AssertionKind {
+    MinTokenBalance { token_account_index: u8, min_amount: u64 },
+    AccountOwner { account_index: u8, owner: Pubkey },
+    MinLamports { account_index: u8, min_lamports: u64 },
+}
+
+impl Assertion {
+    fn check(&self, remaining_accounts: &[AccountInfo]) -> Result<()> {
+        match self.kind {
+            AssertionKind::MinTokenBalance { token_account_index, min_amount } => {
+                let balance = token_balance(remaining_accounts, token_account_index)?;
+                if balance < min_amount {
+                    return Err(error!(MulticallHandlerError::TokenBalanceBelowMinimum)
+                        .with_account_name(remaining_accounts[token_account_index as usize].key.to_string())
+                        .with_values((balance, min_amount)));
+                }
+            }
+            AssertionKind::AccountOwner { account_index, owner } => {
+                let account_info = get_account(remaining_accounts, account_index)?;
+                if *account_info.owner != owner {
+                    return Err(error!(MulticallHandlerError::AccountOwnerMismatch)
+                        .with_account_name(account_info.key.to_string())
+                        .with_pubkeys((*account_info.owner, owner)));
+                }
+            }
+            AssertionKind::MinLamports { account_index, min_lamports } => {
+                let account_info = get_account(remaining_accounts, account_index)?;
+                if account_info.lamports() < min_lamports {
+                    return Err(error!(MulticallHandlerError::LamportsBelowMinimum)
+                        .with_account_name(account_info.key.to_string())
+                        .with_values((account_info.lamports(), min_lamports)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn get_account<'a, 'info>(remaining_accounts: &'a [AccountInfo<'info>], index: u8) -> Result<&'a AccountInfo<'info>> {
+    Ok(remaining_accounts
+        .get(index as usize)
+        .ok_or(ErrorCode::AccountNotEnoughKeys)?)
+}
+
+fn token_balance(remaining_accounts: &[AccountInfo], token_account_index: u8) -> Result<u64> {
+    let token_account_info = get_account(remaining_accounts, token_account_index)?;
+    if !TokenInterface::ids().contains(token_account_info.owner) {
+        return err!(MulticallHandlerError::InvalidTokenAccount);
+    }
+    let token_account = TokenAccount::try_deserialize(&mut &token_account_info.try_borrow_data()?[..])?;
+
+    Ok(token_account.amount)
+}
+
+#[event]
+pub struct LeftoverTokensNotDrained {
+    pub token_account: Pubkey,
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum MulticallHandlerError {
+    #[msg("Balance injection references a missing compiled instruction")]
+    InvalidBalanceInjectionIndex,
+    #[msg("Account is not a token account")]
+    InvalidTokenAccount,
+    #[msg("Balance injection offset exceeds the instruction data")]
+    InvalidBalanceInjectionOffset,
+    #[msg("Handler signer does not match the signer scope")]
+    InvalidHandlerSigner,
+    #[msg("Assertion references a missing compiled instruction")]
+    InvalidAssertionIndex,
+    #[msg("Token balance is below the asserted minimum")]
+    TokenBalanceBelowMinimum,
+    #[msg("Account owner does not match the asserted owner")]
+    AccountOwnerMismatch,
+    #[msg("Lamports are below the asserted minimum")]
+    LamportsBelowMinimum,
+    #[msg("Program is not in the program allowlist")]
+    ProgramNotAllowed,
+    #[msg("Program allowlist is required in the message")]
+    MissingProgramAllowlist,
+    #[msg("Program allowlist is full")]
+    ProgramAllowlistFull,
+    #[msg("Only the program allowlist owner can call this function")]
+    NotProgramAllowlistOwner,
+    #[msg("Only the program upgrade authority can call this function")]
+    NotUpgradeAuthority,
+}
+
 #[derive(Accounts)]
 pub struct HandleV3AcrossMessage {}
+
+#[derive(Accounts)]
+pub struct DrainLeftoverTokens<'info> {
+    // Derivation depends on the signer scope, so it is checked within the instruction.
+    #[account(mut)]
+    pub handler_signer: Signer<'info>,
+
+    /// CHECK: Receives the handler_signer lamports and owns the associated token accounts receiving the tokens.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProgramAllowlist<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + ProgramAllowlist::INIT_SPACE,
+        seeds = [b"program_allowlist"],
+        bump
+    )]
+    pub program_allowlist: Account<'info, ProgramAllowlist>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::MulticallHandler>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(signer.key())
+            @ MulticallHandlerError::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowedProgram<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"program_allowlist"],
+        bump = program_allowlist.bump,
+        constraint = program_allowlist.owner == signer.key() @ MulticallHandlerError::NotProgramAllowlistOwner
+    )]
+    pub program_allowlist: Account<'info, ProgramAllowlist>,
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+// Periphery specific errors.
+#[error_code]
+pub enum PeripheryError {
+    #[msg("Invalid exchange program!")]
+    InvalidExchange,
+    #[msg("Exchange spent more swap tokens than provided!")]
+    SwapTokenOverspent,
+    #[msg("Exchange did not return the input token!")]
+    InvalidSwapOutput,
+    #[msg("Swap output is below the minimum expected input token amount!")]
+    MinimumExpectedInputAmount,
+}
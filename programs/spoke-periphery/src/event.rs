@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct SwapBeforeBridge {
+    pub exchange: Pubkey,
+    pub swap_token: Pubkey,
+    pub across_input_token: Pubkey,
+    pub swap_token_amount: u64,
+    pub across_input_amount: u64,
+    pub across_output_token: Pubkey,
+    pub across_output_amount: u64,
+}
@@ -0,0 +1,3 @@
+mod swap_and_bridge;
+
+pub use swap_and_bridge::*;
@@ -0,0 +1,234 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke_signed},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+use svm_spoke::{
+    cpi_helpers::{approve_and_deposit, DepositParams},
+    program::SvmSpoke,
+};
+
+use crate::{error::PeripheryError, event::SwapBeforeBridge};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DepositData {
+    pub recipient: Pubkey,
+    pub output_token: Pubkey,
+    pub output_amount: u64,
+    pub destination_chain_id: u64,
+    pub exclusive_relayer: Pubkey,
+    pub quote_timestamp: u32,
+    pub fill_deadline: u32,
+    pub exclusivity_parameter: u32,
+    pub message: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwapAndDepositData {
+    pub swap_token_amount: u64, // Amount of swap tokens pulled from the depositor and offered to the exchange.
+    pub min_expected_input_token_amount: u64, // Minimum swap output that is deposited as input_amount.
+    pub exchange_data: Vec<u8>, // Instruction data passed to the exchange program.
+    pub deposit_data: DepositData,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapAndBridge<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    /// CHECK: Only used as the authority of the periphery swap token accounts and signer on the exchange CPI.
+    #[account(seeds = [b"swap_authority"], bump)]
+    pub swap_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Exchange program is arbitrary, but it cannot be the periphery itself.
+    #[account(executable, constraint = exchange.key() != crate::ID @ PeripheryError::InvalidExchange)]
+    pub exchange: UncheckedAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub swap_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub input_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = swap_token_mint, token::authority = depositor, token::token_program = token_program)]
+    pub depositor_swap_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // The spoke pool requires deposits to be pulled from the depositor's ATA of the input token.
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        associated_token::mint = input_token_mint,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program
+    )]
+    pub depositor_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        associated_token::mint = swap_token_mint,
+        associated_token::authority = swap_authority,
+        associated_token::token_program = token_program
+    )]
+    pub swap_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        associated_token::mint = input_token_mint,
+        associated_token::authority = swap_authority,
+        associated_token::token_program = token_program
+    )]
+    pub swap_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Validated by the spoke pool on deposit.
+    #[account(mut)]
+    pub spoke_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the spoke pool on deposit.
+    pub spoke_route: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the spoke pool on deposit.
+    #[account(mut)]
+    pub spoke_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the spoke pool on deposit.
+    pub spoke_event_authority: UncheckedAccount<'info>,
+
+    pub spoke_program: Program<'info, SvmSpoke>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn swap_and_bridge<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapAndBridge<'info>>,
+    swap_and_deposit_data: SwapAndDepositData,
+) -> Result<()> {
+    let SwapAndDepositData { swap_token_amount, min_expected_input_token_amount, exchange_data, deposit_data } =
+        swap_and_deposit_data;
+    let (across_output_token, across_output_amount) = (deposit_data.output_token, deposit_data.output_amount);
+    let swap_authority_seeds: &[&[u8]] = &[b"swap_authority", &[ctx.bumps.swap_authority]];
+    let signer_seeds = &[swap_authority_seeds];
+
+    // Balances before pulling swap tokens are used to isolate this swap from any dust held by the periphery.
+    let swap_token_balance_before = ctx.accounts.swap_token_account.amount;
+    let swap_output_balance_before = ctx.accounts.swap_output_token_account.amount;
+
+    // Pull swap tokens from the depositor so that the exchange only has access to the periphery held funds.
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.depositor_swap_token_account.to_account_info(),
+        mint: ctx.accounts.swap_token_mint.to_account_info(),
+        to: ctx.accounts.swap_token_account.to_account_info(),
+        authority: ctx.accounts.depositor.to_account_info(),
+    };
+    let cpi_context = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts);
+    transfer_checked(cpi_context, swap_token_amount, ctx.accounts.swap_token_mint.decimals)?;
+
+    // Execute the swap on the exchange program with the remaining accounts. Only the swap authority PDA can be a signer
+    // so that the exchange cannot act on behalf of the depositor.
+    let swap_authority = ctx.accounts.swap_authority.key();
+    let mut accounts = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut account_infos = Vec::with_capacity(ctx.remaining_accounts.len());
+    for account_info in ctx.remaining_accounts {
+        let is_swap_authority = account_info.key() == swap_authority;
+        match account_info.is_writable {
+            true => accounts.push(AccountMeta::new(account_info.key(), is_swap_authority)),
+            false => accounts.push(AccountMeta::new_readonly(account_info.key(), is_swap_authority)),
+        }
+        account_infos.push(account_info.to_owned());
+    }
+    let swap_instruction = Instruction { program_id: ctx.accounts.exchange.key(), accounts, data: exchange_data };
+    invoke_signed(&swap_instruction, &account_infos, signer_seeds)?;
+
+    ctx.accounts.swap_token_account.reload()?;
+    ctx.accounts.swap_output_token_account.reload()?;
+
+    // Any swap tokens not consumed by the exchange are refunded to the depositor.
+    let swap_token_leftover = ctx
+        .accounts
+        .swap_token_account
+        .amount
+        .checked_sub(swap_token_balance_before)
+        .ok_or(PeripheryError::SwapTokenOverspent)?;
+    let across_input_amount = ctx
+        .accounts
+        .swap_output_token_account
+        .amount
+        .checked_sub(swap_output_balance_before)
+        .ok_or(PeripheryError::InvalidSwapOutput)?;
+    if across_input_amount < min_expected_input_token_amount {
+        return err!(PeripheryError::MinimumExpectedInputAmount);
+    }
+
+    if swap_token_leftover > 0 {
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.swap_token_account.to_account_info(),
+            mint: ctx.accounts.swap_token_mint.to_account_info(),
+            to: ctx.accounts.depositor_swap_token_account.to_account_info(),
+            authority: ctx.accounts.swap_authority.to_account_info(),
+        };
+        let cpi_context =
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_accounts, signer_seeds);
+        transfer_checked(cpi_context, swap_token_leftover, ctx.accounts.swap_token_mint.decimals)?;
+    }
+
+    // Move the swap output to the depositor ATA from where the spoke pool pulls the deposit.
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.swap_output_token_account.to_account_info(),
+        mint: ctx.accounts.input_token_mint.to_account_info(),
+        to: ctx.accounts.depositor_input_token_account.to_account_info(),
+        authority: ctx.accounts.swap_authority.to_account_info(),
+    };
+    let cpi_context =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_accounts, signer_seeds);
+    transfer_checked(cpi_context, across_input_amount, ctx.accounts.input_token_mint.decimals)?;
+
+    // Deposit on behalf of the depositor that signed this instruction.
+    let deposit_accounts = svm_spoke::cpi_helpers::Deposit {
+        signer: ctx.accounts.depositor.to_account_info(),
+        state: ctx.accounts.spoke_state.to_account_info(),
+        route: ctx.accounts.spoke_route.to_account_info(),
+        depositor_token_account: ctx.accounts.depositor_input_token_account.to_account_info(),
+        vault: ctx.accounts.spoke_vault.to_account_info(),
+        mint: ctx.accounts.input_token_mint.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        event_authority: ctx.accounts.spoke_event_authority.to_account_info(),
+        program: ctx.accounts.spoke_program.to_account_info(),
+    };
+    let deposit_params = DepositParams {
+        depositor: ctx.accounts.depositor.key(),
+        recipient: deposit_data.recipient,
+        input_token: ctx.accounts.input_token_mint.key(),
+        output_token: deposit_data.output_token,
+        input_amount: across_input_amount,
+        output_amount: deposit_data.output_amount,
+        destination_chain_id: deposit_data.destination_chain_id,
+        exclusive_relayer: deposit_data.exclusive_relayer,
+        quote_timestamp: deposit_data.quote_timestamp,
+        fill_deadline: deposit_data.fill_deadline,
+        exclusivity_parameter: deposit_data.exclusivity_parameter,
+        message: deposit_data.message,
+    };
+    let cpi_context = CpiContext::new(ctx.accounts.spoke_program.to_account_info(), deposit_accounts);
+    approve_and_deposit(cpi_context, deposit_params)?;
+
+    emit_cpi!(SwapBeforeBridge {
+        exchange: ctx.accounts.exchange.key(),
+        swap_token: ctx.accounts.swap_token_mint.key(),
+        across_input_token: ctx.accounts.input_token_mint.key(),
+        swap_token_amount,
+        across_input_amount,
+        across_output_token,
+        across_output_amount,
+    });
+
+    Ok(())
+}
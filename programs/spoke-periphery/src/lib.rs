@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+#[cfg(not(feature = "no-entrypoint"))]
+use ::solana_security_txt::security_txt;
+
+#[cfg(not(feature = "no-entrypoint"))]
+security_txt! {
+    name: "Across",
+    project_url: "https://across.to",
+    contacts: "email:bugs@across.to",
+    policy: "https://docs.across.to/resources/bug-bounty",
+    preferred_languages: "en",
+    source_code: "https://github.com/across-protocol/contracts/tree/master/programs/spoke-periphery",
+    auditors: "OpenZeppelin"
+}
+
+declare_id!("C6tuo9x8oBFntqDg89w1MHwGFEDB6PJqE7wWbvJs4eAu");
+
+/// # Across SVM Spoke Periphery Program
+///
+/// Periphery program for the SVM Spoke enabling deposits in any SPL token. The program is the Solana counterpart of
+/// SpokePoolPeriphery.sol: it swaps the depositor's tokens into the input token of an enabled route on an arbitrary
+/// exchange program and deposits the swap output into the SVM Spoke within the same instruction.
+///
+/// For any issues, please reach out to bugs@across.to.
+pub mod error;
+pub mod event;
+mod instructions;
+
+use instructions::*;
+
+#[program]
+pub mod spoke_periphery {
+    use super::*;
+
+    /// Swaps tokens on an exchange program and deposits the swap output into the SVM Spoke.
+    ///
+    /// The swap tokens are pulled from the depositor into the swap_authority PDA owned token account and the exchange
+    /// program is invoked with exchange_data and the remaining accounts. Only the swap_authority PDA can sign the
+    /// exchange CPI. Any swap tokens not consumed by the exchange are refunded to the depositor. The swap output is
+    /// transferred to the depositor's ATA of the input token, approved to the SVM Spoke state PDA and deposited with
+    /// the depositor as the deposit signer, so the resulting deposit is equivalent to a direct deposit of the swap
+    /// output. Emits a SwapBeforeBridge event.
+    ///
+    /// ### Required Accounts:
+    /// - depositor (Signer): The account that provides the swap tokens and is the depositor on the SVM Spoke.
+    /// - swap_authority (Unchecked): PDA owning the periphery swap token accounts. Seed: ["swap_authority"].
+    /// - exchange (Unchecked): Exchange program to perform the swap. Cannot be the periphery program.
+    /// - swap_token_mint: The mint of the tokens provided by the depositor.
+    /// - input_token_mint: The mint of the input token of the deposit route.
+    /// - depositor_swap_token_account (Writable): The depositor's token account of the swap token.
+    /// - depositor_input_token_account (Writable): The depositor's ATA of the input token. Created if needed.
+    /// - swap_token_account (Writable): swap_authority ATA of the swap token. Created if needed.
+    /// - swap_output_token_account (Writable): swap_authority ATA of the input token. Created if needed.
+    /// - spoke_state (Writable): SVM Spoke state PDA.
+    /// - spoke_route: SVM Spoke route PDA of the input token and destination chain.
+    /// - spoke_vault (Writable): SVM Spoke vault of the input token.
+    /// - spoke_event_authority: SVM Spoke event authority PDA.
+    /// - spoke_program: The SVM Spoke program.
+    /// - token_program: The token program of both the swap and input token mints.
+    /// - associated_token_program: The associated token program.
+    /// - system_program: The system program.
+    /// - remaining_accounts: Accounts passed to the exchange program in the order expected by the exchange.
+    ///
+    /// ### Parameters:
+    /// - swap_and_deposit_data: Swap parameters and deposit parameters:
+    ///   - swap_token_amount: Amount of swap tokens pulled from the depositor and offered to the exchange.
+    ///   - min_expected_input_token_amount: Minimum swap output. Protects the depositor from slippage.
+    ///   - exchange_data: Instruction data for the exchange program.
+    ///   - deposit_data: Parameters of the deposit as in the SVM Spoke deposit instruction, excluding the depositor,
+    ///     input_token and input_amount that are derived from the swap.
+    pub fn swap_and_bridge<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapAndBridge<'info>>,
+        swap_and_deposit_data: SwapAndDepositData,
+    ) -> Result<()> {
+        instructions::swap_and_bridge(ctx, swap_and_deposit_data)
+    }
+}
@@ -9,3 +9,18 @@ pub const MESSAGE_TRANSMITTER_PROGRAM_ID: Pubkey = pubkey!("CCTPmbSD7gX1bxKPAmg7
 pub const MAX_EXCLUSIVITY_PERIOD_SECONDS: u32 = 31_536_000;
 
 pub const ZERO_DEPOSIT_ID: [u8; 32] = [0u8; 32];
+
+// Maximum lengths of integrator metadata. These must match the max_len attributes of the Integrator account.
+pub const MAX_INTEGRATOR_NAME_LEN: usize = 32;
+pub const MAX_INTEGRATOR_METADATA_URI_LEN: usize = 200;
+
+// Claimed bitmap capacity of root bundles relayed from the HubPool, as the relayRootBundle message does not include the
+// number of relayer refund leaves. Leaf IDs beyond it cannot be executed, so it is sized well above the leaf count of
+// any realistic bundle while keeping the rent paid upfront by the relayer of the root bundle small.
+pub const DEFAULT_RELAYER_REFUND_LEAF_CAPACITY: u32 = 2048;
+
+// Number of unexpired relays that can be tracked in each of the 256 fill status buckets.
+pub const FILL_STATUS_BUCKET_CAPACITY: usize = 128;
+
+// Time after the fill during which anyone can execute a deferred Across+ message before it can be closed unexecuted.
+pub const PENDING_RELAY_MESSAGE_EXECUTION_WINDOW: u32 = 86400;
@@ -10,8 +10,8 @@ use crate::{
     RelayData,
 };
 
-pub fn is_local_or_remote_owner(signer: &Signer, state: &Account<State>) -> bool {
-    signer.key() == state.owner || signer.key() == get_self_authority_pda()
+pub fn is_local_or_remote_owner(signer: &Signer, state: &Account<State>) -> Result<bool> {
+    Ok(signer.key() == state.owner || signer.key() == get_self_authority_pda(state.self_authority_bump)?)
 }
 
 pub fn is_relay_hash_valid(relay_hash: &[u8; 32], relay_data: &RelayData, state: &Account<State>) -> bool {
@@ -31,3 +31,26 @@ pub fn is_valid_associated_token_account(
         && token_account.key()
             == get_associated_token_address_with_program_id(authority, &mint.key(), &token_program.key())
 }
+
+// Implements the same checks as Anchor's associated_token constraint for token accounts that cannot be deserialized in
+// the accounts context as they might be created within the instruction.
+pub fn check_associated_token_account(
+    token_account: &AccountInfo,
+    mint: &InterfaceAccount<Mint>,
+    token_program: &Interface<TokenInterface>,
+    authority: &Pubkey,
+) -> Result<()> {
+    if token_account.owner != &token_program.key() {
+        return err!(ErrorCode::ConstraintAssociatedTokenTokenProgram);
+    }
+    let token_account_data = TokenAccount::try_deserialize(&mut &token_account.try_borrow_data()?[..])?;
+    if &token_account_data.owner != authority {
+        return err!(ErrorCode::ConstraintTokenOwner);
+    }
+    if token_account.key() != get_associated_token_address_with_program_id(authority, &mint.key(), &token_program.key())
+    {
+        return err!(ErrorCode::ConstraintAssociated);
+    }
+
+    Ok(())
+}
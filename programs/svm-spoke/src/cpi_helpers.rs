@@ -0,0 +1,173 @@
+//! Typed helpers for third-party programs depositing into the SVM Spoke via CPI.
+//!
+//! These helpers build the deposit instruction from the program `instruction` and `accounts` modules instead of the
+//! Anchor generated `cpi` module, so composing programs only need the `no-entrypoint` feature. The generated `cpi`
+//! module cannot be built for this program as it expects a lifetime on the accountless `Null` view context.
+//!
+//! Deposits pull tokens from `depositor_token_account` with the state PDA as the delegate, so the depositor must approve
+//! the state PDA for `input_amount` before depositing. `approve_and_deposit` performs both steps in a single call and
+//! supports PDA depositors by passing their signer seeds in the CPI context. The depositor token account can be any
+//! token account owned by the depositor, it is not required to be an ATA.
+//!
+//! Example from within a program where the depositor is a PDA with seeds `[b"vault", &[bump]]`:
+//! ```ignore
+//! let cpi_accounts = svm_spoke::cpi_helpers::Deposit {
+//!     signer: vault.to_account_info(),
+//!     state: spoke_state.to_account_info(),
+//!     route: spoke_route.to_account_info(),
+//!     depositor_token_account: vault_token_account.to_account_info(),
+//!     vault: spoke_vault.to_account_info(),
+//!     mint: mint.to_account_info(),
+//!     token_program: token_program.to_account_info(),
+//!     event_authority: spoke_event_authority.to_account_info(),
+//!     program: spoke_program.to_account_info(),
+//! };
+//! let signer_seeds: &[&[&[u8]]] = &[&[b"vault", &[bump]]];
+//! let cpi_context = CpiContext::new_with_signer(spoke_program.to_account_info(), cpi_accounts, signer_seeds);
+//! svm_spoke::cpi_helpers::approve_and_deposit(cpi_context, deposit_params)?;
+//! ```
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke_signed},
+    InstructionData,
+};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{approve, Approve},
+};
+
+/// Accounts of the deposit instruction. See svm_spoke::deposit for the description of each account.
+#[derive(Clone)]
+pub struct Deposit<'info> {
+    pub signer: AccountInfo<'info>,
+    pub state: AccountInfo<'info>,
+    pub route: AccountInfo<'info>,
+    pub depositor_token_account: AccountInfo<'info>,
+    pub vault: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub event_authority: AccountInfo<'info>,
+    pub program: AccountInfo<'info>,
+}
+
+impl ToAccountMetas for Deposit<'_> {
+    // Account metas follow the Deposit accounts struct of the program, so that they cannot get out of sync.
+    fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+        crate::accounts::Deposit {
+            signer: self.signer.key(),
+            state: self.state.key(),
+            route: self.route.key(),
+            depositor_token_account: self.depositor_token_account.key(),
+            vault: self.vault.key(),
+            mint: self.mint.key(),
+            token_program: self.token_program.key(),
+            event_authority: self.event_authority.key(),
+            program: self.program.key(),
+        }
+        .to_account_metas(is_signer)
+    }
+}
+
+impl<'info> ToAccountInfos<'info> for Deposit<'info> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![
+            self.signer.clone(),
+            self.state.clone(),
+            self.route.clone(),
+            self.depositor_token_account.clone(),
+            self.vault.clone(),
+            self.mint.clone(),
+            self.token_program.clone(),
+            self.event_authority.clone(),
+            self.program.clone(),
+        ]
+    }
+}
+
+/// Parameters of the deposit instruction. See svm_spoke::deposit for the description of each field.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DepositParams {
+    pub depositor: Pubkey,
+    pub recipient: Pubkey,
+    pub input_token: Pubkey,
+    pub output_token: Pubkey,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    pub destination_chain_id: u64,
+    pub exclusive_relayer: Pubkey,
+    pub quote_timestamp: u32,
+    pub fill_deadline: u32,
+    pub exclusivity_parameter: u32,
+    pub message: Vec<u8>,
+}
+
+/// Address of the state PDA for the given seed (0 on mainnet).
+pub fn get_state_address(seed: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"state", seed.to_le_bytes().as_ref()], &crate::ID).0
+}
+
+/// Address of the route PDA for the given input token and destination chain.
+pub fn get_route_address(input_token: &Pubkey, seed: u64, destination_chain_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"route",
+            input_token.as_ref(),
+            seed.to_le_bytes().as_ref(),
+            destination_chain_id.to_le_bytes().as_ref(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Address of the vault holding deposited tokens, which is the state ATA of the input token.
+pub fn get_vault_address(state: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(state, mint, token_program)
+}
+
+/// Address of the event authority PDA required by all instructions emitting events via CPI.
+pub fn get_event_authority_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &crate::ID).0
+}
+
+/// Deposits into the SVM Spoke. The depositor must have already approved the state PDA for input_amount.
+pub fn deposit<'info>(ctx: CpiContext<'_, '_, '_, 'info, Deposit<'info>>, params: DepositParams) -> Result<()> {
+    let data = crate::instruction::Deposit {
+        depositor: params.depositor,
+        recipient: params.recipient,
+        input_token: params.input_token,
+        output_token: params.output_token,
+        input_amount: params.input_amount,
+        output_amount: params.output_amount,
+        destination_chain_id: params.destination_chain_id,
+        exclusive_relayer: params.exclusive_relayer,
+        quote_timestamp: params.quote_timestamp,
+        fill_deadline: params.fill_deadline,
+        exclusivity_parameter: params.exclusivity_parameter,
+        message: params.message,
+    }
+    .data();
+    let instruction = Instruction { program_id: ctx.program.key(), accounts: ctx.to_account_metas(None), data };
+
+    invoke_signed(&instruction, &ctx.to_account_infos(), ctx.signer_seeds)?;
+
+    Ok(())
+}
+
+/// Approves the state PDA for input_amount on behalf of the signer and deposits into the SVM Spoke. The signer must own
+/// depositor_token_account, so PDA depositors should sign with the seeds passed in the CPI context.
+pub fn approve_and_deposit<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, Deposit<'info>>,
+    params: DepositParams,
+) -> Result<()> {
+    let approve_accounts = Approve {
+        to: ctx.accounts.depositor_token_account.clone(),
+        delegate: ctx.accounts.state.clone(),
+        authority: ctx.accounts.signer.clone(),
+    };
+    let approve_context =
+        CpiContext::new_with_signer(ctx.accounts.token_program.clone(), approve_accounts, ctx.signer_seeds);
+    approve(approve_context, params.input_amount)?;
+
+    deposit(ctx, params)
+}
@@ -74,6 +74,54 @@ pub enum SvmError {
     InvalidProductionSeed,
     #[msg("Invalid remaining accounts for ATA creation!")]
     InvalidATACreationAccounts,
+    #[msg("Integrator metadata exceeds maximum length!")]
+    IntegratorMetadataTooLong,
+    #[msg("Only the integrator authority can call this function!")]
+    NotIntegratorAuthority,
+    #[msg("Same chain deposit destination must be this chain!")]
+    InvalidSameChainDestination,
+    #[msg("Invalid same chain deposit payer!")]
+    InvalidSameChainDepositPayer,
+    #[msg("The same chain deposit fill deadline has not passed!")]
+    CanOnlyCloseSameChainDepositIfFillDeadlinePassed,
+    #[msg("Not all relayer refund leaves have been executed!")]
+    RootBundleRefundsNotExecuted,
+    #[msg("The root bundle slow fill deadline has not passed!")]
+    CanOnlyCloseRootBundleIfSlowFillDeadlinePassed,
+    #[msg("Invalid root bundle payer!")]
+    InvalidRootBundlePayer,
+    #[msg("Relayer refund leaf does not match the leaf in progress!")]
+    RefundLeafProgressMismatch,
+    #[msg("Claim account balance is insufficient to fund the fill!")]
+    InsufficientClaimAccountBalance,
+    #[msg("Invalid remaining accounts for batch refund claim!")]
+    InvalidClaimRefundAccounts,
+    #[msg("Relayer refund leaf ID exceeds the root bundle claimed bitmap capacity!")]
+    ClaimedBitmapIndexOutOfRange,
+    #[msg("Fill status bucket is full and no payer was provided for the fills PDA!")]
+    FillStatusBucketFull,
+    #[msg("Fill status account does not match the fill status backend!")]
+    InvalidFillStatusBackendAccount,
+    #[msg("Invalid remaining accounts for batch fill PDA close!")]
+    InvalidCloseFillPdasAccounts,
+    #[msg("Fill tombstones account is required to close fills PDAs!")]
+    MissingFillTombstones,
+    #[msg("Pending relay message account is required when the message allows handler failure!")]
+    MissingPendingRelayMessage,
+    #[msg("Pending relay message account is only used for relays with a message that allows deferral!")]
+    UnexpectedPendingRelayMessage,
+    #[msg("Pending relay message can only be closed once the execution deadline has passed!")]
+    CanOnlyClosePendingRelayMessageIfExecutionDeadlinePassed,
+    #[msg("Integrator is not registered in the integrator registry!")]
+    UnregisteredIntegrator,
+    #[msg("Account has already been migrated to the current layout!")]
+    AccountAlreadyMigrated,
+    #[msg("Invalid refund leaf progress payer!")]
+    InvalidRefundLeafProgressPayer,
+    #[msg("Same chain relays can only be filled with fill_same_chain_relay!")]
+    SameChainRelayRequiresEscrow,
+    #[msg("Fill status backend cannot be switched from Bucketed back to Pda!")]
+    InvalidFillStatusBackendSwitch,
 }
 
 // CCTP specific errors.
@@ -114,4 +162,14 @@ pub enum AcrossPlusError {
     NotWritableMessageAccountKey,
     #[msg("Missing value recipient key")]
     MissingValueRecipientKey,
+    #[msg("Unsupported message version")]
+    UnsupportedMessageVersion,
+    #[msg("Malformed versioned message")]
+    MalformedVersionedMessage,
+    #[msg("Insufficient compute units for message handler")]
+    InsufficientComputeUnits,
+    #[msg("Only the handler signer can sign message instructions")]
+    UnsupportedMessageSigner,
+    #[msg("Too many message accounts")]
+    TooManyMessageAccounts,
 }
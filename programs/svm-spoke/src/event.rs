@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::state::FillStatusBackend;
+
 // Admin events
 #[event]
 pub struct SetXDomainAdmin {
@@ -40,6 +42,18 @@ pub struct EmergencyDeletedRootBundle {
     pub root_bundle_id: u32,
 }
 
+#[event]
+pub struct SetFillStatusBackend {
+    pub backend: FillStatusBackend,
+}
+
+#[event]
+pub struct SetRootBundleCompletionTarget {
+    pub root_bundle_id: u32,
+    pub relayer_refund_leaf_count: u32,
+    pub slow_fill_deadline: u32,
+}
+
 // Deposit events
 #[event]
 pub struct FundsDeposited {
@@ -58,6 +72,30 @@ pub struct FundsDeposited {
     pub message: Vec<u8>,
 }
 
+#[event]
+pub struct IntegratorDeposited {
+    pub deposit_id: [u8; 32],
+    pub integrator_id: u16,
+    pub depositor: Pubkey,
+}
+
+// Integrator events
+#[event]
+pub struct RegisteredIntegrator {
+    pub integrator_id: u16,
+    pub authority: Pubkey,
+    pub name: String,
+    pub metadata_uri: String,
+}
+
+#[event]
+pub struct UpdatedIntegrator {
+    pub integrator_id: u16,
+    pub authority: Pubkey,
+    pub name: String,
+    pub metadata_uri: String,
+}
+
 // Fill events
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
 pub enum FillType {
@@ -91,6 +129,15 @@ pub struct FilledRelay {
     pub recipient: Pubkey,
     pub message_hash: [u8; 32],
     pub relay_execution_info: RelayExecutionEventInfo,
+    pub recipient_token_account_rent: u64, // Rent paid by the filler for creating the recipient ATA, 0 if it existed.
+}
+
+#[event]
+pub struct SettledSameChainRelay {
+    pub deposit_id: [u8; 32],
+    pub relayer: Pubkey,
+    pub input_token: Pubkey,
+    pub input_amount: u64,
 }
 
 // Slow fill events
@@ -121,6 +168,20 @@ pub struct ExecutedRelayerRefundRoot {
     pub l2_token_address: Pubkey,
     pub refund_addresses: Vec<Pubkey>,
     pub deferred_refunds: bool,
+    pub deferred_refund_addresses: Vec<Pubkey>, // Refunds accrued to claim accounts when executed in hybrid mode.
+    pub caller: Pubkey,
+}
+
+#[event]
+pub struct ExecutedRelayerRefundChunk {
+    pub root_bundle_id: u32,
+    pub leaf_id: u32,
+    pub l2_token_address: Pubkey,
+    pub start_index: u32,
+    pub refund_amounts: Vec<u64>,
+    pub refund_addresses: Vec<Pubkey>,
+    pub completed: bool,
+    pub deferred_refunds: bool,
     pub caller: Pubkey,
 }
 
@@ -145,3 +206,33 @@ pub struct TokensBridged {
     pub l2_token_address: Pubkey,
     pub caller: Pubkey,
 }
+
+#[event]
+pub struct ClosedRootBundle {
+    pub root_bundle_id: u32,
+    pub payer: Pubkey,
+    pub caller: Pubkey,
+}
+
+#[event]
+pub struct DeferredRelayMessage {
+    pub relay_hash: [u8; 32],
+    pub message_hash: [u8; 32],
+    pub relayer: Pubkey,
+    pub execution_deadline: u32,
+    pub allow_handler_failure: bool,
+}
+
+#[event]
+pub struct ExecutedRelayMessage {
+    pub relay_hash: [u8; 32],
+    pub message_hash: [u8; 32],
+    pub caller: Pubkey,
+}
+
+#[event]
+pub struct ExpiredRelayMessage {
+    pub relay_hash: [u8; 32],
+    pub message_hash: [u8; 32],
+    pub caller: Pubkey,
+}
@@ -10,10 +10,10 @@ use crate::{
     error::SvmError,
     event::{
         EmergencyDeletedRootBundle, EnabledDepositRoute, PausedDeposits, PausedFills, RelayedRootBundle,
-        SetXDomainAdmin, TransferredOwnership,
+        SetFillStatusBackend, SetRootBundleCompletionTarget, SetXDomainAdmin, TransferredOwnership,
     },
-    state::{RootBundle, Route, State},
-    utils::{initialize_current_time, set_seed},
+    state::{FillStatusBackend, RootBundle, Route, State},
+    utils::{get_current_time, initialize_current_time, set_seed},
 };
 
 #[derive(Accounts)]
@@ -52,6 +52,8 @@ pub fn initialize(
     state.cross_domain_admin = cross_domain_admin;
     state.deposit_quote_time_buffer = deposit_quote_time_buffer;
     state.fill_deadline_buffer = fill_deadline_buffer;
+    state.bump = ctx.bumps.state;
+    state.self_authority_bump = Pubkey::find_program_address(&[b"self_authority"], &crate::ID).1;
 
     // Set seed and initialize current time. Both enable testing functionality and are no-ops in production.
     set_seed(state, seed)?;
@@ -63,10 +65,10 @@ pub fn initialize(
 #[event_cpi]
 #[derive(Accounts)]
 pub struct PauseDeposits<'info> {
-    #[account(constraint = is_local_or_remote_owner(&signer, &state) @ SvmError::NotOwner)]
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
     pub signer: Signer<'info>,
 
-    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 }
 
@@ -82,10 +84,10 @@ pub fn pause_deposits(ctx: Context<PauseDeposits>, pause: bool) -> Result<()> {
 #[event_cpi]
 #[derive(Accounts)]
 pub struct PauseFills<'info> {
-    #[account(constraint = is_local_or_remote_owner(&signer, &state) @ SvmError::NotOwner)]
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
     pub signer: Signer<'info>,
 
-    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 }
 
@@ -98,13 +100,38 @@ pub fn pause_fills(ctx: Context<PauseFills>, pause: bool) -> Result<()> {
     Ok(())
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetFillStatusBackendState<'info> {
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+}
+
+pub fn set_fill_status_backend(ctx: Context<SetFillStatusBackendState>, backend: FillStatusBackend) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    // The Bucketed backend also reads fills PDAs, but the Pda backend does not read buckets, so switching back would
+    // allow relays tracked in buckets to be filled or slow filled again.
+    if state.fill_status_backend == FillStatusBackend::Bucketed && backend == FillStatusBackend::Pda {
+        return err!(SvmError::InvalidFillStatusBackendSwitch);
+    }
+    state.fill_status_backend = backend;
+
+    emit_cpi!(SetFillStatusBackend { backend });
+
+    Ok(())
+}
+
 #[event_cpi]
 #[derive(Accounts)]
 pub struct TransferOwnership<'info> {
     #[account(address = state.owner @ SvmError::NotOwner)]
     pub signer: Signer<'info>,
 
-    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 }
 
@@ -120,10 +147,10 @@ pub fn transfer_ownership(ctx: Context<TransferOwnership>, new_owner: Pubkey) ->
 #[event_cpi]
 #[derive(Accounts)]
 pub struct SetCrossDomainAdmin<'info> {
-    #[account(constraint = is_local_or_remote_owner(&signer, &state) @ SvmError::NotOwner)]
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
     pub signer: Signer<'info>,
 
-    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 }
 
@@ -140,13 +167,13 @@ pub fn set_cross_domain_admin(ctx: Context<SetCrossDomainAdmin>, cross_domain_ad
 #[derive(Accounts)]
 #[instruction(origin_token: Pubkey, destination_chain_id: u64)]
 pub struct SetEnableRoute<'info> {
-    #[account(constraint = is_local_or_remote_owner(&signer, &state) @ SvmError::NotOwner)]
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
     pub signer: Signer<'info>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 
     #[account(
@@ -191,6 +218,7 @@ pub fn set_enable_route(
     enabled: bool,
 ) -> Result<()> {
     ctx.accounts.route.enabled = enabled;
+    ctx.accounts.route.bump = ctx.bumps.route;
 
     emit_cpi!(EnabledDepositRoute { origin_token, destination_chain_id, enabled });
 
@@ -199,24 +227,26 @@ pub fn set_enable_route(
 
 #[event_cpi]
 #[derive(Accounts)]
+#[instruction(relayer_refund_root: [u8; 32], slow_relay_root: [u8; 32], relayer_refund_leaf_capacity: u32)]
 pub struct RelayRootBundle<'info> {
-    #[account(constraint = is_local_or_remote_owner(&signer, &state) @ SvmError::NotOwner)]
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
     pub signer: Signer<'info>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 
     #[account(
         init, // Init to create root bundle account. Prevents re-initialization for a given root..
         payer = payer,
-        space = DISCRIMINATOR_SIZE + RootBundle::INIT_SPACE,
+        // Pre-size the claimed bitmap so that executing relayer refund leaves does not require reallocation.
+        space = RootBundle::space(relayer_refund_leaf_capacity),
         seeds = [b"root_bundle", state.seed.to_le_bytes().as_ref(), state.root_bundle_id.to_le_bytes().as_ref()],
         bump
     )]
-    pub root_bundle: Account<'info, RootBundle>,
+    pub root_bundle: AccountLoader<'info, RootBundle>,
 
     pub system_program: Program<'info, System>,
 }
@@ -227,9 +257,13 @@ pub fn relay_root_bundle(
     slow_relay_root: [u8; 32],
 ) -> Result<()> {
     let state = &mut ctx.accounts.state;
-    let root_bundle = &mut ctx.accounts.root_bundle;
+    let root_bundle = &mut ctx.accounts.root_bundle.load_init()?;
     root_bundle.relayer_refund_root = relayer_refund_root;
     root_bundle.slow_relay_root = slow_relay_root;
+    root_bundle.slow_fill_deadline =
+        RootBundle::slow_fill_deadline_bound(&slow_relay_root, get_current_time(state)?, state.fill_deadline_buffer);
+    root_bundle.payer = ctx.accounts.payer.key();
+    root_bundle.bump = ctx.bumps.root_bundle;
 
     emit_cpi!(RelayedRootBundle { root_bundle_id: state.root_bundle_id, relayer_refund_root, slow_relay_root });
 
@@ -242,22 +276,22 @@ pub fn relay_root_bundle(
 #[derive(Accounts)]
 #[instruction(root_bundle_id: u32)]
 pub struct EmergencyDeleteRootBundleState<'info> {
-    #[account(constraint = is_local_or_remote_owner(&signer, &state) @ SvmError::NotOwner)]
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
     pub signer: Signer<'info>,
 
     #[account(mut)]
-    // We do not restrict who can receive lamports from closing root_bundle account as that would require storing the
-    // original payer when root bundle was relayed and unnecessarily make it more expensive to relay in the happy path.
+    // We do not restrict who can receive lamports from closing root_bundle account in an emergency. Completed root
+    // bundles are instead closed permissionlessly via close_root_bundle, refunding the original payer.
     pub closer: SystemAccount<'info>,
 
-    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 
     #[account(mut,
         seeds =[b"root_bundle", state.seed.to_le_bytes().as_ref(), root_bundle_id.to_le_bytes().as_ref()],
         close = closer,
         bump)]
-    pub root_bundle: Account<'info, RootBundle>,
+    pub root_bundle: AccountLoader<'info, RootBundle>,
 }
 
 pub fn emergency_delete_root_bundle(ctx: Context<EmergencyDeleteRootBundleState>, root_bundle_id: u32) -> Result<()> {
@@ -265,3 +299,37 @@ pub fn emergency_delete_root_bundle(ctx: Context<EmergencyDeleteRootBundleState>
 
     Ok(())
 }
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(root_bundle_id: u32)]
+pub struct SetRootBundleCompletionTargetState<'info> {
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(
+        mut,
+        seeds = [b"root_bundle", state.seed.to_le_bytes().as_ref(), root_bundle_id.to_le_bytes().as_ref()],
+        bump = root_bundle.load()?.bump
+    )]
+    pub root_bundle: AccountLoader<'info, RootBundle>,
+}
+
+pub fn set_root_bundle_completion_target(
+    ctx: Context<SetRootBundleCompletionTargetState>,
+    root_bundle_id: u32,
+    relayer_refund_leaf_count: u32,
+    slow_fill_deadline: u32,
+) -> Result<()> {
+    let root_bundle = &mut ctx.accounts.root_bundle.load_mut()?;
+    root_bundle.completion_target_set = 1;
+    root_bundle.relayer_refund_leaf_count = relayer_refund_leaf_count;
+    root_bundle.slow_fill_deadline = slow_fill_deadline;
+
+    emit_cpi!(SetRootBundleCompletionTarget { root_bundle_id, relayer_refund_leaf_count, slow_fill_deadline });
+
+    Ok(())
+}
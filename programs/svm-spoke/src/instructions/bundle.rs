@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use anchor_lang::{prelude::*, solana_program::keccak};
 use anchor_spl::{
     associated_token,
@@ -7,9 +9,11 @@ use anchor_spl::{
 use crate::{
     constants::DISCRIMINATOR_SIZE,
     error::{CommonError, SvmError},
-    event::{ExecutedRelayerRefundRoot, TokensBridged},
-    state::{ClaimAccount, ExecuteRelayerRefundLeafParams, RootBundle, State, TransferLiability},
-    utils::{is_claimed, set_claimed, verify_merkle_proof},
+    event::{ClosedRootBundle, ExecutedRelayerRefundChunk, ExecutedRelayerRefundRoot, TokensBridged},
+    state::{
+        ClaimAccount, ExecuteRelayerRefundLeafParams, RelayerRefundLeafProgress, RootBundle, State, TransferLiability,
+    },
+    utils::{get_current_time, get_merkle_root, is_claimed, set_claimed, verify_merkle_proof},
 };
 
 #[event_cpi]
@@ -21,21 +25,14 @@ pub struct ExecuteRelayerRefundLeaf<'info> {
     #[account(mut, seeds = [b"instruction_params", signer.key().as_ref()], bump, close = signer)]
     pub instruction_params: Account<'info, ExecuteRelayerRefundLeafParams>, // Contains all leaf & proof information.
 
-    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 
     #[account(
         mut,
-        seeds = [b"root_bundle", state.seed.to_le_bytes().as_ref(), instruction_params.root_bundle_id.to_le_bytes().as_ref()], bump,
-        // Realloc to let the size of the dynamic array within root_bundle to grow as leafs are executed.
-        realloc = std::cmp::max(
-            DISCRIMINATOR_SIZE + RootBundle::INIT_SPACE + instruction_params.relayer_refund_leaf.leaf_id as usize / 8,
-            root_bundle.to_account_info().data_len()
-        ),
-        realloc::payer = signer,
-        realloc::zero = false
+        seeds = [b"root_bundle", state.seed.to_le_bytes().as_ref(), instruction_params.root_bundle_id.to_le_bytes().as_ref()], bump = root_bundle.load()?.bump
     )]
-    pub root_bundle: Account<'info, RootBundle>,
+    pub root_bundle: AccountLoader<'info, RootBundle>,
 
     #[account(
         mut,
@@ -98,9 +95,17 @@ impl RelayerRefundLeaf {
     }
 }
 
+// Determines how relayer refunds are paid out when executing a relayer refund leaf.
+#[derive(PartialEq)]
+pub enum RefundMode {
+    Direct,   // Transfer all refunds to the recipient ATAs.
+    Deferred, // Accrue all refunds to the recipient claim accounts.
+    Hybrid,   // Transfer refunds to usable recipient ATAs and accrue the rest to claim accounts.
+}
+
 pub fn execute_relayer_refund_leaf<'c, 'info>(
-    ctx: Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeaf<'info>>,
-    deferred_refunds: bool,
+    mut ctx: Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeaf<'info>>,
+    refund_mode: RefundMode,
 ) -> Result<()>
 where
     'c: 'info, // The lifetime constraint 'c: 'info ensures that the lifetime 'c is at least as long as 'info.
@@ -109,44 +114,221 @@ where
     let instruction_params = &ctx.accounts.instruction_params;
     let root_bundle_id = instruction_params.root_bundle_id;
     let relayer_refund_leaf = instruction_params.relayer_refund_leaf.to_owned();
-    let proof = instruction_params.proof.to_owned();
 
-    let state = &ctx.accounts.state;
+    let leaf_hash = claim_relayer_refund_leaf(&mut ctx, &relayer_refund_leaf)?;
+    ctx.accounts.root_bundle.load_mut()?.record_claimed_leaf(&leaf_hash);
 
-    let root = ctx.accounts.root_bundle.relayer_refund_root;
-    let leaf = relayer_refund_leaf.to_keccak_hash()?;
-    verify_merkle_proof(root, leaf, proof)?;
+    if ctx.remaining_accounts.len() < relayer_refund_leaf.refund_addresses.len() {
+        return err!(ErrorCode::AccountNotEnoughKeys);
+    }
 
-    if relayer_refund_leaf.chain_id != state.chain_id {
-        return err!(CommonError::InvalidChainId);
+    // Check if vault has sufficient balance for all the refunds.
+    let total_refund_amount: u64 = relayer_refund_leaf.refund_amounts.iter().sum();
+    if ctx.accounts.vault.amount < total_refund_amount {
+        return err!(CommonError::InsufficientSpokePoolBalanceToExecuteLeaf);
     }
 
-    if is_claimed(&ctx.accounts.root_bundle.claimed_bitmap, relayer_refund_leaf.leaf_id) {
-        return err!(CommonError::ClaimedMerkleLeaf);
+    // Depending on the called instruction flavor, we either accrue the refunds to claim accounts, transfer them or
+    // transfer them falling back to claim accounts for recipients whose token accounts cannot receive the transfer.
+    let refund_range = 0..relayer_refund_leaf.refund_addresses.len();
+    let deferred_refund_addresses = match refund_mode {
+        RefundMode::Direct => {
+            distribute_relayer_refunds(&ctx, &relayer_refund_leaf, refund_range)?;
+            Vec::new()
+        }
+        RefundMode::Deferred => {
+            accrue_relayer_refunds(&ctx, &relayer_refund_leaf, refund_range)?;
+            Vec::new()
+        }
+        RefundMode::Hybrid => distribute_relayer_refunds_with_fallback(&ctx, &relayer_refund_leaf)?,
+    };
+
+    bridge_amount_to_return(&mut ctx, &relayer_refund_leaf)?;
+
+    emit_cpi!(ExecutedRelayerRefundRoot {
+        amount_to_return: relayer_refund_leaf.amount_to_return,
+        chain_id: relayer_refund_leaf.chain_id,
+        refund_amounts: relayer_refund_leaf.refund_amounts,
+        root_bundle_id,
+        leaf_id: relayer_refund_leaf.leaf_id,
+        l2_token_address: ctx.accounts.mint.key(),
+        refund_addresses: relayer_refund_leaf.refund_addresses,
+        deferred_refunds: refund_mode == RefundMode::Deferred,
+        deferred_refund_addresses,
+        caller: ctx.accounts.signer.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRelayerRefundLeafChunk<'info> {
+    pub execute: ExecuteRelayerRefundLeaf<'info>,
+
+    // Tracks the refunds paid out so far. Initialized by the first valid chunk and closed to its payer on completion.
+    #[account(
+        init_if_needed,
+        payer = execute.signer,
+        space = DISCRIMINATOR_SIZE + RelayerRefundLeafProgress::INIT_SPACE,
+        seeds = [
+            b"refund_leaf_progress",
+            execute.state.seed.to_le_bytes().as_ref(),
+            execute.instruction_params.root_bundle_id.to_le_bytes().as_ref(),
+            execute.instruction_params.relayer_refund_leaf.leaf_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub leaf_progress: Account<'info, RelayerRefundLeafProgress>,
+
+    /// CHECK: Only receives the rent of the closed leaf_progress, validated against the stored payer on completion.
+    #[account(mut)]
+    pub leaf_progress_payer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn execute_relayer_refund_leaf_chunk<'c, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeafChunk<'info>>,
+    deferred_refunds: bool,
+) -> Result<()>
+where
+    'c: 'info,
+{
+    let relayer_refund_leaf = ctx.accounts.execute.instruction_params.relayer_refund_leaf.to_owned();
+    let leaf_hash = relayer_refund_leaf.to_keccak_hash()?;
+    let leaf_progress = &mut ctx.accounts.leaf_progress;
+    let mut execute_ctx =
+        Context::new(ctx.program_id, &mut ctx.accounts.execute, ctx.remaining_accounts, ctx.bumps.execute);
+
+    // The first chunk verifies and claims the leaf so that it cannot be executed again in any mode. The progress account
+    // is only bound to the leaf and its rent payer once the leaf is verified, so that any failed attempt reverts its
+    // initialization. Subsequent chunks only need to match the leaf in progress.
+    if leaf_progress.leaf_hash == [0u8; 32] {
+        claim_relayer_refund_leaf(&mut execute_ctx, &relayer_refund_leaf)?;
+        bridge_amount_to_return(&mut execute_ctx, &relayer_refund_leaf)?;
+        leaf_progress.leaf_hash = leaf_hash;
+        leaf_progress.payer = execute_ctx.accounts.signer.key();
+    } else if leaf_progress.leaf_hash != leaf_hash {
+        return err!(SvmError::RefundLeafProgressMismatch);
     }
 
-    set_claimed(&mut ctx.accounts.root_bundle.claimed_bitmap, relayer_refund_leaf.leaf_id);
+    let next_refund_index =
+        execute_refund_chunk(&execute_ctx, &relayer_refund_leaf, leaf_progress.next_refund_index, deferred_refunds)?;
+    leaf_progress.next_refund_index = next_refund_index;
 
-    if relayer_refund_leaf.refund_addresses.len() != relayer_refund_leaf.refund_amounts.len() {
-        return err!(CommonError::InvalidMerkleLeaf);
+    // Count the leaf as claimed only once completed so that the root bundle cannot be closed while refunds are pending.
+    if next_refund_index as usize == relayer_refund_leaf.refund_addresses.len() {
+        if ctx.accounts.leaf_progress_payer.key() != leaf_progress.payer {
+            return err!(SvmError::InvalidRefundLeafProgressPayer);
+        }
+        execute_ctx
+            .accounts
+            .root_bundle
+            .load_mut()?
+            .record_claimed_leaf(&leaf_hash);
+        let leaf_progress_payer = ctx.accounts.leaf_progress_payer.to_account_info();
+        ctx.accounts.leaf_progress.close(leaf_progress_payer)?;
     }
 
-    if ctx.remaining_accounts.len() < relayer_refund_leaf.refund_addresses.len() {
+    Ok(())
+}
+
+// Pays out the refunds of the leaf starting at start_index, one for each of the remaining accounts, and returns the index
+// of the next refund to be paid out.
+fn execute_refund_chunk<'c, 'info>(
+    ctx: &Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeaf<'info>>,
+    relayer_refund_leaf: &RelayerRefundLeaf,
+    start_index: u32,
+    deferred_refunds: bool,
+) -> Result<u32>
+where
+    'c: 'info,
+{
+    let start = start_index as usize;
+    let refund_count = relayer_refund_leaf.refund_addresses.len();
+    let end = std::cmp::min(start + ctx.remaining_accounts.len(), refund_count);
+    if start == end && start < refund_count {
         return err!(ErrorCode::AccountNotEnoughKeys);
     }
 
-    // Check if vault has sufficient balance for all the refunds.
-    let total_refund_amount: u64 = relayer_refund_leaf.refund_amounts.iter().sum();
-    if ctx.accounts.vault.amount < total_refund_amount {
+    // Check if vault has sufficient balance for the refunds in this chunk.
+    let chunk_refund_amount: u64 = relayer_refund_leaf.refund_amounts[start..end].iter().sum();
+    if ctx.accounts.vault.amount < chunk_refund_amount {
         return err!(CommonError::InsufficientSpokePoolBalanceToExecuteLeaf);
     }
 
-    // Depending on the called instruction flavor, we either accrue the refunds to claim accounts or transfer them.
     match deferred_refunds {
-        true => accrue_relayer_refunds(&ctx, &relayer_refund_leaf)?,
-        false => distribute_relayer_refunds(&ctx, &relayer_refund_leaf)?,
+        true => accrue_relayer_refunds(ctx, relayer_refund_leaf, start..end)?,
+        false => distribute_relayer_refunds(ctx, relayer_refund_leaf, start..end)?,
     }
 
+    emit_cpi!(ExecutedRelayerRefundChunk {
+        root_bundle_id: ctx.accounts.instruction_params.root_bundle_id,
+        leaf_id: relayer_refund_leaf.leaf_id,
+        l2_token_address: ctx.accounts.mint.key(),
+        start_index,
+        refund_amounts: relayer_refund_leaf.refund_amounts[start..end].to_vec(),
+        refund_addresses: relayer_refund_leaf.refund_addresses[start..end].to_vec(),
+        completed: end == refund_count,
+        deferred_refunds,
+        caller: ctx.accounts.signer.key(),
+    });
+
+    // Completed leaves are reported the same way as leaves executed in a single transaction.
+    if end == refund_count {
+        emit_cpi!(ExecutedRelayerRefundRoot {
+            amount_to_return: relayer_refund_leaf.amount_to_return,
+            chain_id: relayer_refund_leaf.chain_id,
+            refund_amounts: relayer_refund_leaf.refund_amounts.to_owned(),
+            root_bundle_id: ctx.accounts.instruction_params.root_bundle_id,
+            leaf_id: relayer_refund_leaf.leaf_id,
+            l2_token_address: ctx.accounts.mint.key(),
+            refund_addresses: relayer_refund_leaf.refund_addresses.to_owned(),
+            deferred_refunds,
+            deferred_refund_addresses: Vec::new(),
+            caller: ctx.accounts.signer.key(),
+        });
+    }
+
+    Ok(end as u32)
+}
+
+// Verifies the leaf inclusion in the relayer refund root and marks it as claimed in the root bundle bitmap. Returns the
+// verified leaf hash.
+fn claim_relayer_refund_leaf(
+    ctx: &mut Context<ExecuteRelayerRefundLeaf>,
+    relayer_refund_leaf: &RelayerRefundLeaf,
+) -> Result<[u8; 32]> {
+    let proof = ctx.accounts.instruction_params.proof.to_owned();
+
+    let root = ctx.accounts.root_bundle.load()?.relayer_refund_root;
+    let leaf = relayer_refund_leaf.to_keccak_hash()?;
+    verify_merkle_proof(root, leaf, proof)?;
+
+    if relayer_refund_leaf.chain_id != ctx.accounts.state.chain_id {
+        return err!(CommonError::InvalidChainId);
+    }
+
+    // Operate on the claimed bitmap in place, following the zero-copy root bundle data.
+    let mut root_bundle_data = ctx.accounts.root_bundle.as_ref().try_borrow_mut_data()?;
+    let claimed_bitmap = &mut root_bundle_data[RootBundle::CLAIMED_BITMAP_OFFSET..];
+    if is_claimed(claimed_bitmap, relayer_refund_leaf.leaf_id) {
+        return err!(CommonError::ClaimedMerkleLeaf);
+    }
+
+    set_claimed(claimed_bitmap, relayer_refund_leaf.leaf_id)?;
+
+    if relayer_refund_leaf.refund_addresses.len() != relayer_refund_leaf.refund_amounts.len() {
+        return err!(CommonError::InvalidMerkleLeaf);
+    }
+
+    Ok(leaf)
+}
+
+fn bridge_amount_to_return(
+    ctx: &mut Context<ExecuteRelayerRefundLeaf>,
+    relayer_refund_leaf: &RelayerRefundLeaf,
+) -> Result<()> {
     if relayer_refund_leaf.amount_to_return > 0 {
         ctx.accounts.transfer_liability.pending_to_hub_pool += relayer_refund_leaf.amount_to_return;
 
@@ -159,85 +341,216 @@ where
         });
     }
 
-    emit_cpi!(ExecutedRelayerRefundRoot {
-        amount_to_return: relayer_refund_leaf.amount_to_return,
-        chain_id: relayer_refund_leaf.chain_id,
-        refund_amounts: relayer_refund_leaf.refund_amounts,
-        root_bundle_id,
-        leaf_id: relayer_refund_leaf.leaf_id,
-        l2_token_address: ctx.accounts.mint.key(),
-        refund_addresses: relayer_refund_leaf.refund_addresses,
-        deferred_refunds,
-        caller: ctx.accounts.signer.key(),
-    });
-
     Ok(())
 }
 
 fn distribute_relayer_refunds<'info>(
     ctx: &Context<'_, '_, '_, 'info, ExecuteRelayerRefundLeaf<'info>>,
     relayer_refund_leaf: &RelayerRefundLeaf,
+    refund_range: Range<usize>,
 ) -> Result<()> {
-    // Derive the signer seeds for the state. The vault owns the state PDA so we need to derive this to create the
-    // signer seeds to execute the CPI transfer from the vault to the refund recipient's token account.
-    let state_seed_bytes = ctx.accounts.state.seed.to_le_bytes();
-    let seeds = &[b"state", state_seed_bytes.as_ref(), &[ctx.bumps.state]];
-    let signer_seeds = &[&seeds[..]];
+    let start = refund_range.start;
+    for (i, amount) in relayer_refund_leaf.refund_amounts[refund_range].iter().enumerate() {
+        // It should be safe to access elements of refund_addresses and remaining_accounts as their lengths are checked
+        // before calling this internal function. Remaining accounts are indexed relative to the start of the range.
+        let refund_token_account = &ctx.remaining_accounts[i];
+        check_refund_token_account(ctx, refund_token_account, &relayer_refund_leaf.refund_addresses[start + i], i)?;
+        transfer_refund(ctx, refund_token_account, *amount)?;
+    }
 
-    for (i, amount) in relayer_refund_leaf.refund_amounts.iter().enumerate() {
-        // We only need to check the refund account matches the associated token address for the relayer.
-        // All other required checks are performed within the transfer CPI. We do not check the token account authority
-        // as the relayer might have transferred it to a multisig or any other wallet.
+    Ok(())
+}
+
+fn accrue_relayer_refunds<'c, 'info>(
+    ctx: &Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeaf<'info>>,
+    relayer_refund_leaf: &RelayerRefundLeaf,
+    refund_range: Range<usize>,
+) -> Result<()>
+where
+    'c: 'info,
+{
+    let start = refund_range.start;
+    for (i, amount) in relayer_refund_leaf.refund_amounts[refund_range].iter().enumerate() {
         // It should be safe to access elements of refund_addresses and remaining_accounts as their lengths are checked
-        // before calling this internal function.
+        // before calling this internal function. Remaining accounts are indexed relative to the start of the range.
+        accrue_refund(ctx, relayer_refund_leaf, start + i, i, *amount)?;
+    }
+
+    Ok(())
+}
+
+// Transfers refunds to the recipient token accounts where possible and accrues the rest to claim accounts. Claim
+// accounts for the deferred refunds follow the refund token accounts in remaining_accounts, in the order of refunds.
+// Returns the refund addresses that were deferred.
+fn distribute_relayer_refunds_with_fallback<'c, 'info>(
+    ctx: &Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeaf<'info>>,
+    relayer_refund_leaf: &RelayerRefundLeaf,
+) -> Result<Vec<Pubkey>>
+where
+    'c: 'info,
+{
+    let mut deferred_refund_addresses = Vec::new();
+    let mut claim_account_index = relayer_refund_leaf.refund_addresses.len();
+
+    for (i, amount) in relayer_refund_leaf.refund_amounts.iter().enumerate() {
         let refund_token_account = &ctx.remaining_accounts[i];
-        let associated_token_address = associated_token::get_associated_token_address_with_program_id(
-            &relayer_refund_leaf.refund_addresses[i],
-            &ctx.accounts.mint.key(),
-            &ctx.accounts.token_program.key(),
-        );
-        if refund_token_account.key() != associated_token_address {
-            return Err(Error::from(SvmError::InvalidRefund).with_account_name(&format!("remaining_accounts[{}]", i)));
+        check_refund_token_account(ctx, refund_token_account, &relayer_refund_leaf.refund_addresses[i], i)?;
+
+        if is_usable_token_account(refund_token_account) {
+            transfer_refund(ctx, refund_token_account, *amount)?;
+            continue;
         }
 
-        let transfer_accounts = TransferChecked {
-            from: ctx.accounts.vault.to_account_info(),
-            mint: ctx.accounts.mint.to_account_info(),
-            to: refund_token_account.to_account_info(),
-            authority: ctx.accounts.state.to_account_info(),
-        };
-        let cpi_context =
-            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_accounts, signer_seeds);
-        transfer_checked(cpi_context, amount.to_owned(), ctx.accounts.mint.decimals)?;
+        if ctx.remaining_accounts.len() <= claim_account_index {
+            return err!(ErrorCode::AccountNotEnoughKeys);
+        }
+        accrue_refund(ctx, relayer_refund_leaf, i, claim_account_index, *amount)?;
+        claim_account_index += 1;
+        deferred_refund_addresses.push(relayer_refund_leaf.refund_addresses[i]);
+    }
+
+    Ok(deferred_refund_addresses)
+}
+
+fn check_refund_token_account<'info>(
+    ctx: &Context<'_, '_, '_, 'info, ExecuteRelayerRefundLeaf<'info>>,
+    refund_token_account: &AccountInfo<'info>,
+    refund_address: &Pubkey,
+    account_index: usize,
+) -> Result<()> {
+    // We only need to check the refund account matches the associated token address for the relayer.
+    // All other required checks are performed within the transfer CPI. We do not check the token account authority
+    // as the relayer might have transferred it to a multisig or any other wallet.
+    let associated_token_address = associated_token::get_associated_token_address_with_program_id(
+        refund_address,
+        &ctx.accounts.mint.key(),
+        &ctx.accounts.token_program.key(),
+    );
+    if refund_token_account.key() != associated_token_address {
+        return Err(
+            Error::from(SvmError::InvalidRefund).with_account_name(&format!("remaining_accounts[{}]", account_index))
+        );
     }
 
     Ok(())
 }
 
-fn accrue_relayer_refunds<'c, 'info>(
+// The ATA address is already verified, so the account can receive the transfer if it exists and is not frozen.
+fn is_usable_token_account<'info>(refund_token_account: &'info AccountInfo<'info>) -> bool {
+    match InterfaceAccount::<TokenAccount>::try_from(refund_token_account) {
+        Ok(token_account) => !token_account.is_frozen(),
+        Err(_) => false,
+    }
+}
+
+fn transfer_refund<'info>(
+    ctx: &Context<'_, '_, '_, 'info, ExecuteRelayerRefundLeaf<'info>>,
+    refund_token_account: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    // Derive the signer seeds for the state. The vault owns the state PDA so we need to derive this to create the
+    // signer seeds to execute the CPI transfer from the vault to the refund recipient's token account.
+    let state_seed_bytes = ctx.accounts.state.seed.to_le_bytes();
+    let seeds = &[b"state", state_seed_bytes.as_ref(), &[ctx.accounts.state.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.vault.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: refund_token_account.to_account_info(),
+        authority: ctx.accounts.state.to_account_info(),
+    };
+    let cpi_context =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_accounts, signer_seeds);
+    transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)
+}
+
+fn accrue_refund<'c, 'info>(
     ctx: &Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeaf<'info>>,
     relayer_refund_leaf: &RelayerRefundLeaf,
+    refund_index: usize,
+    account_index: usize,
+    amount: u64,
 ) -> Result<()>
 where
     'c: 'info,
 {
-    for (i, amount) in relayer_refund_leaf.refund_amounts.iter().enumerate() {
-        // It should be safe to access elements of refund_addresses and remaining_accounts as their lengths are checked
-        // before calling this internal function.
-        let mut claim_account = ClaimAccount::try_from(
-            &ctx.remaining_accounts[i],
-            &relayer_refund_leaf.mint_public_key,
-            &relayer_refund_leaf.refund_addresses[i],
-        )
-        .map_err(|e| e.with_account_name(&format!("remaining_accounts[{}]", i)))?;
+    let mut claim_account = ClaimAccount::try_from(
+        &ctx.remaining_accounts[account_index],
+        &relayer_refund_leaf.mint_public_key,
+        &relayer_refund_leaf.refund_addresses[refund_index],
+    )
+    .map_err(|e| e.with_account_name(&format!("remaining_accounts[{}]", account_index)))?;
+
+    claim_account.amount += amount;
+
+    // Persist the updated claim account (Anchor handles this only for static accounts).
+    claim_account
+        .exit(ctx.program_id)
+        .map_err(|e| e.with_account_name(&format!("remaining_accounts[{}]", account_index)))
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(root_bundle_id: u32)]
+pub struct CloseRootBundle<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
 
-        claim_account.amount += amount;
+    #[account(
+        mut,
+        seeds = [b"root_bundle", state.seed.to_le_bytes().as_ref(), root_bundle_id.to_le_bytes().as_ref()],
+        bump = root_bundle.load()?.bump,
+        close = payer
+    )]
+    pub root_bundle: AccountLoader<'info, RootBundle>,
 
-        // Persist the updated claim account (Anchor handles this only for static accounts).
-        claim_account
-            .exit(ctx.program_id)
-            .map_err(|e| e.with_account_name(&format!("remaining_accounts[{}]", i)))?;
+    /// CHECK: Only receives the rent of the closed root_bundle.
+    #[account(mut, address = root_bundle.load()?.payer @ SvmError::InvalidRootBundlePayer)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn close_root_bundle(
+    ctx: Context<CloseRootBundle>,
+    root_bundle_id: u32,
+    relayer_refund_leaf_hashes: Vec<[u8; 32]>,
+) -> Result<()> {
+    let root_bundle = &ctx.accounts.root_bundle.load()?;
+
+    // Leaf IDs are verified against the relayer refund root and can only be claimed once.
+    if root_bundle.completion_target_set != 0 {
+        if root_bundle.claimed_leaf_count < root_bundle.relayer_refund_leaf_count {
+            return err!(SvmError::RootBundleRefundsNotExecuted);
+        }
+    } else {
+        // Without a recorded leaf count, the caller proves that the passed leaf hashes are all the leaves of the relayer
+        // refund root and that they match the leaves executed so far.
+        let mut leaf_hashes = relayer_refund_leaf_hashes;
+        leaf_hashes.sort_unstable();
+        leaf_hashes.dedup();
+        if get_merkle_root(&leaf_hashes) != root_bundle.relayer_refund_root {
+            return err!(CommonError::InvalidMerkleProof);
+        }
+        let leaf_hashes_xor = leaf_hashes.iter().fold([0u8; 32], |mut xor, leaf_hash| {
+            xor.iter_mut().zip(leaf_hash).for_each(|(acc, byte)| *acc ^= byte);
+            xor
+        });
+        if root_bundle.claimed_leaf_count as usize != leaf_hashes.len()
+            || root_bundle.claimed_leaf_hashes != leaf_hashes_xor
+        {
+            return err!(SvmError::RootBundleRefundsNotExecuted);
+        }
     }
 
+    // Slow fill leaves can no longer be executed once their fill deadline has passed.
+    let current_time = get_current_time(&ctx.accounts.state)?;
+    if current_time <= root_bundle.slow_fill_deadline {
+        return err!(SvmError::CanOnlyCloseRootBundleIfSlowFillDeadlinePassed);
+    }
+
+    emit_cpi!(ClosedRootBundle { root_bundle_id, payer: ctx.accounts.payer.key(), caller: ctx.accounts.signer.key() });
+
     Ok(())
 }
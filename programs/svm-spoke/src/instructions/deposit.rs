@@ -9,8 +9,8 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::{
     constants::{MAX_EXCLUSIVITY_PERIOD_SECONDS, ZERO_DEPOSIT_ID},
     error::{CommonError, SvmError},
-    event::FundsDeposited,
-    state::{Route, State},
+    event::{FundsDeposited, IntegratorDeposited},
+    state::{Integrator, Route, State},
     utils::{get_current_time, get_unsafe_deposit_id, transfer_from},
 };
 
@@ -31,23 +31,24 @@ pub struct Deposit<'info> {
     #[account(
         mut,
         seeds = [b"state", state.seed.to_le_bytes().as_ref()],
-        bump,
+        bump = state.bump,
         constraint = !state.paused_deposits @ CommonError::DepositsArePaused
     )]
     pub state: Account<'info, State>,
 
     #[account(
         seeds = [b"route", input_token.as_ref(), state.seed.to_le_bytes().as_ref(), destination_chain_id.to_le_bytes().as_ref()],
-        bump,
+        bump = route.bump,
         constraint = route.enabled @ CommonError::DisabledRoute
     )]
     pub route: Account<'info, Route>,
 
+    // Not required to be an ATA so that program controlled depositors (PDAs) can deposit from any token account they own.
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = depositor,
-        associated_token::token_program = token_program
+        token::mint = mint,
+        token::authority = depositor,
+        token::token_program = token_program
     )]
     pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
 
@@ -83,7 +84,8 @@ pub fn _deposit(
     fill_deadline: u32,
     exclusivity_parameter: u32,
     message: Vec<u8>,
-) -> Result<()> {
+    integrator_id: Option<u16>,
+) -> Result<u32> {
     let state = &mut ctx.accounts.state;
 
     let current_time = get_current_time(state)?;
@@ -113,7 +115,7 @@ pub fn _deposit(
         &ctx.accounts.vault,
         input_amount,
         state,
-        ctx.bumps.state,
+        state.bump,
         &ctx.accounts.mint,
         &ctx.accounts.token_program,
     )?;
@@ -141,7 +143,15 @@ pub fn _deposit(
         message,
     });
 
-    Ok(())
+    // Companion event to attribute the deposit to an integrator, equivalent to the EVM integrator ID calldata suffix.
+    // Only registered integrators can be attributed, so the integrator PDA is passed as the first remaining account.
+    if let Some(integrator_id) = integrator_id {
+        let integrator = ctx.remaining_accounts.first().ok_or(ErrorCode::AccountNotEnoughKeys)?;
+        Integrator::check_registered(integrator, state.seed, integrator_id)?;
+        emit_cpi!(IntegratorDeposited { deposit_id: applied_deposit_id, integrator_id, depositor });
+    }
+
+    Ok(exclusivity_deadline)
 }
 
 pub fn deposit(
@@ -158,6 +168,7 @@ pub fn deposit(
     fill_deadline: u32,
     exclusivity_parameter: u32,
     message: Vec<u8>,
+    integrator_id: Option<u16>,
 ) -> Result<()> {
     _deposit(
         ctx,
@@ -174,6 +185,7 @@ pub fn deposit(
         fill_deadline,
         exclusivity_parameter,
         message,
+        integrator_id,
     )?;
 
     Ok(())
@@ -192,6 +204,7 @@ pub fn deposit_now(
     fill_deadline_offset: u32,
     exclusivity_period: u32,
     message: Vec<u8>,
+    integrator_id: Option<u16>,
 ) -> Result<()> {
     let state = &mut ctx.accounts.state;
     let current_time = get_current_time(state)?;
@@ -209,6 +222,7 @@ pub fn deposit_now(
         current_time + fill_deadline_offset,
         exclusivity_period,
         message,
+        integrator_id,
     )?;
 
     Ok(())
@@ -229,6 +243,7 @@ pub fn unsafe_deposit(
     fill_deadline: u32,
     exclusivity_parameter: u32,
     message: Vec<u8>,
+    integrator_id: Option<u16>,
 ) -> Result<()> {
     // Calculate the unsafe deposit ID as a [u8; 32]
     let deposit_id = get_unsafe_deposit_id(ctx.accounts.signer.key(), depositor, deposit_nonce);
@@ -247,6 +262,7 @@ pub fn unsafe_deposit(
         fill_deadline,
         exclusivity_parameter,
         message,
+        integrator_id,
     )?;
 
     Ok(())
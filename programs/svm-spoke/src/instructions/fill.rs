@@ -7,11 +7,18 @@ use anchor_spl::{
 use crate::{
     common::RelayData,
     constants::DISCRIMINATOR_SIZE,
-    constraints::is_relay_hash_valid,
+    constraints::{check_associated_token_account, is_relay_hash_valid},
     error::{CommonError, SvmError},
-    event::{FillType, FilledRelay, RelayExecutionEventInfo},
-    state::{FillRelayParams, FillStatus, FillStatusAccount, State},
-    utils::{get_current_time, hash_non_empty_message, invoke_handler, transfer_from},
+    event::{ClaimedRelayerRefund, FillType, FilledRelay, RelayExecutionEventInfo, SettledSameChainRelay},
+    instructions::{check_fill_tombstones_shard, record_fill_tombstone, FillStatusTracker},
+    state::{
+        ClaimAccount, FillRelayParams, FillStatus, FillStatusAccount, FillStatusBucket, FillTombstones,
+        PendingRelayMessage, State,
+    },
+    utils::{
+        create_associated_token_account_if_needed, get_current_time, get_relay_hash, hash_non_empty_message,
+        invoke_or_defer_handler, is_exclusive_relayer, transfer_from,
+    },
 };
 
 #[event_cpi]
@@ -27,7 +34,7 @@ pub struct FillRelay<'info> {
 
     #[account(
         seeds = [b"state", state.seed.to_le_bytes().as_ref()],
-        bump,
+        bump = state.bump,
         constraint = !state.paused_fills @ CommonError::FillsArePaused
     )]
     pub state: Account<'info, State>,
@@ -49,18 +56,12 @@ pub struct FillRelay<'info> {
     )]
     pub relayer_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        // Ensures tokens go to ATA owned by the recipient.
-        associated_token::authority = relay_data
-            .clone()
-            .unwrap_or_else(|| instruction_params.as_ref().unwrap().relay_data.clone())
-            .recipient,
-        associated_token::token_program = token_program
-    )]
-    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Recipient's ATA for the output token. It is created if needed and validated within the instruction to
+    /// ensure tokens go to the ATA owned by the recipient.
+    #[account(mut)]
+    pub recipient_token_account: UncheckedAccount<'info>,
 
+    // Only used with the Pda fill status backend.
     #[account(
         init_if_needed,
         payer = signer,
@@ -72,11 +73,49 @@ pub struct FillRelay<'info> {
             &relay_data.clone().unwrap_or_else(|| instruction_params.as_ref().unwrap().relay_data.clone()),
             &state) @ SvmError::InvalidRelayHash
     )]
-    pub fill_status: Account<'info, FillStatusAccount>,
+    pub fill_status: Option<Account<'info, FillStatusAccount>>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    // Only used with the Bucketed fill status backend.
+    #[account(
+        mut,
+        seeds = [b"fill_status_bucket", state.seed.to_le_bytes().as_ref(), [relay_hash[0]].as_ref()],
+        bump = fill_status_bucket.load()?.bump,
+        constraint = is_relay_hash_valid(
+            &relay_hash,
+            &relay_data.clone().unwrap_or_else(|| instruction_params.as_ref().unwrap().relay_data.clone()),
+            &state) @ SvmError::InvalidRelayHash
+    )]
+    pub fill_status_bucket: Option<AccountLoader<'info, FillStatusBucket>>,
+
+    /// CHECK: Fills PDA of the relay, only used with the Bucketed fill status backend. It is read for relays tracked
+    /// before switching backends and only created when the fill_status_bucket is full.
+    #[account(mut, seeds = [b"fills", relay_hash.as_ref()], bump)]
+    pub fill_status_overflow: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Only required when the recipient_token_account does not exist yet and has to be created by the signer.
+    #[account(
+        address = relay_data
+            .clone()
+            .unwrap_or_else(|| instruction_params.as_ref().unwrap().relay_data.clone())
+            .recipient
+    )]
+    pub recipient: Option<UncheckedAccount<'info>>,
+
+    // Only used when deferring the message execution, in which case it is stored for execute_relay_message.
+    #[account(
+        init,
+        payer = signer,
+        space = PendingRelayMessage::space(
+            &relay_data.clone().unwrap_or_else(|| instruction_params.as_ref().unwrap().relay_data.clone()).message
+        ),
+        seeds = [b"pending_relay_message", relay_hash.as_ref()],
+        bump
+    )]
+    pub pending_relay_message: Option<Account<'info, PendingRelayMessage>>,
 }
 
 pub fn fill_relay<'info>(
@@ -84,17 +123,101 @@ pub fn fill_relay<'info>(
     relay_data: Option<RelayData>,
     repayment_chain_id: Option<u64>,
     repayment_address: Option<Pubkey>,
+    exclusivity_proof: Option<Vec<[u8; 32]>>,
 ) -> Result<()> {
+    _fill_relay(
+        ctx,
+        relay_data,
+        repayment_chain_id,
+        repayment_address,
+        exclusivity_proof,
+        FillFundingSource::RelayerTokenAccount,
+        false,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FillRelayFromClaimAccount<'info> {
+    pub fill: FillRelay<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = fill.mint,
+        associated_token::authority = fill.state,
+        associated_token::token_program = fill.token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    // Refunds accrued to the signer for the output token fund the fill.
+    #[account(
+        mut,
+        seeds = [b"claim_account", fill.mint.key().as_ref(), fill.signer.key().as_ref()],
+        bump = claim_account.bump
+    )]
+    pub claim_account: Account<'info, ClaimAccount>,
+}
+
+pub fn fill_relay_from_claim_account<'info>(
+    ctx: Context<'_, '_, '_, 'info, FillRelayFromClaimAccount<'info>>,
+    relay_data: Option<RelayData>,
+    repayment_chain_id: Option<u64>,
+    repayment_address: Option<Pubkey>,
+    exclusivity_proof: Option<Vec<[u8; 32]>>,
+) -> Result<()> {
+    let funding_source =
+        FillFundingSource::ClaimAccount { vault: &ctx.accounts.vault, claim_account: &mut ctx.accounts.claim_account };
+    let fill_ctx = Context::new(ctx.program_id, &mut ctx.accounts.fill, ctx.remaining_accounts, ctx.bumps.fill);
+    _fill_relay(fill_ctx, relay_data, repayment_chain_id, repayment_address, exclusivity_proof, funding_source, false)?;
+
+    Ok(())
+}
+
+// Source of the output tokens sent to the recipient when filling a relay.
+pub enum FillFundingSource<'a, 'info> {
+    // Relayer must have delegated output_amount to the state PDA.
+    RelayerTokenAccount,
+    // Refunds accrued to the signer's claim account are debited and paid out from the vault.
+    ClaimAccount {
+        vault: &'a InterfaceAccount<'info, TokenAccount>,
+        claim_account: &'a mut Account<'info, ClaimAccount>,
+    },
+}
+
+// Internal fill logic shared with same chain fills. Returns the relay data after resolving instruction params so that the
+// caller can settle the same chain deposit input. same_chain_settlement must only be set by the caller releasing the
+// escrowed input, as it allows filling same chain relays and emits the companion settlement event.
+pub fn _fill_relay<'info>(
+    ctx: Context<'_, '_, '_, 'info, FillRelay<'info>>,
+    relay_data: Option<RelayData>,
+    repayment_chain_id: Option<u64>,
+    repayment_address: Option<Pubkey>,
+    exclusivity_proof: Option<Vec<[u8; 32]>>,
+    funding_source: FillFundingSource<'_, 'info>,
+    same_chain_settlement: bool,
+) -> Result<RelayData> {
     let FillRelayParams { relay_data, repayment_chain_id, repayment_address } =
         unwrap_fill_relay_params(relay_data, repayment_chain_id, repayment_address, &ctx.accounts.instruction_params);
 
     let state = &ctx.accounts.state;
     let current_time = get_current_time(state)?;
 
-    // Check if the exclusivity deadline has passed or if the caller is the exclusive relayer
-    if relay_data.exclusive_relayer != ctx.accounts.signer.key()
-        && relay_data.exclusivity_deadline >= current_time
+    // Same chain relays must release the escrowed deposit input to the filler, so they can only be filled through
+    // fill_same_chain_relay. Otherwise the filler would not be reimbursed and the input would be refunded in a bundle.
+    if relay_data.origin_chain_id == state.chain_id && !same_chain_settlement {
+        return err!(SvmError::SameChainRelayRequiresEscrow);
+    }
+
+    // Check if the exclusivity deadline has passed or if the caller is the exclusive relayer or a member of the
+    // exclusive relayer set (proven against the set root stored in exclusive_relayer).
+    if relay_data.exclusivity_deadline >= current_time
         && relay_data.exclusive_relayer != Pubkey::default()
+        && !is_exclusive_relayer(
+            &relay_data.exclusive_relayer,
+            &ctx.accounts.signer.key(),
+            &exclusivity_proof.unwrap_or_default(),
+        )
     {
         return err!(CommonError::NotExclusiveRelayer);
     }
@@ -105,8 +228,16 @@ pub fn fill_relay<'info>(
     }
 
     // Check the fill status and set the fill type
-    let fill_status_account = &mut ctx.accounts.fill_status;
-    let fill_type = match fill_status_account.status {
+    let mut fill_status = FillStatusTracker::new(
+        state,
+        &mut ctx.accounts.fill_status,
+        &ctx.accounts.fill_status_bucket,
+        &ctx.accounts.fill_status_overflow,
+        Some(ctx.accounts.signer.to_account_info()),
+        &ctx.accounts.system_program,
+        &relay_data,
+    )?;
+    let fill_type = match fill_status.status()? {
         FillStatus::Filled => {
             return err!(CommonError::RelayFilled);
         }
@@ -114,24 +245,83 @@ pub fn fill_relay<'info>(
         _ => FillType::FastFill,
     };
 
-    // Relayer must have delegated output_amount to the state PDA
-    transfer_from(
-        &ctx.accounts.relayer_token_account,
-        &ctx.accounts.recipient_token_account,
-        relay_data.output_amount,
-        state,
-        ctx.bumps.state,
+    // Relayer pays for the recipient ATA if it does not exist yet. The rent is reported in the FilledRelay event.
+    let recipient_token_account_rent = create_associated_token_account_if_needed(
+        Some(ctx.accounts.signer.as_ref()),
+        ctx.accounts.recipient_token_account.as_ref(),
+        ctx.accounts.recipient.as_ref().map(|recipient| recipient.as_ref()),
         &ctx.accounts.mint,
         &ctx.accounts.token_program,
-    )?;
+        Some(&ctx.accounts.associated_token_program),
+        &ctx.accounts.system_program,
+    )
+    .map_err(|e| e.with_account_name("recipient_token_account"))?;
+    check_associated_token_account(
+        ctx.accounts.recipient_token_account.as_ref(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        &relay_data.recipient,
+    )
+    .map_err(|e| e.with_account_name("recipient_token_account"))?;
+
+    match funding_source {
+        // Relayer must have delegated output_amount to the state PDA
+        FillFundingSource::RelayerTokenAccount => transfer_from(
+            &ctx.accounts.relayer_token_account,
+            &ctx.accounts.recipient_token_account,
+            relay_data.output_amount,
+            state,
+            ctx.accounts.state.bump,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+        )?,
+        FillFundingSource::ClaimAccount { vault, claim_account } => {
+            claim_account.amount = claim_account
+                .amount
+                .checked_sub(relay_data.output_amount)
+                .ok_or(SvmError::InsufficientClaimAccountBalance)?;
+            transfer_from(
+                vault,
+                &ctx.accounts.recipient_token_account,
+                relay_data.output_amount,
+                state,
+                ctx.accounts.state.bump,
+                &ctx.accounts.mint,
+                &ctx.accounts.token_program,
+            )?;
+
+            // Debited refunds are reported as claimed so that claim account balances can be tracked from events.
+            emit_cpi!(ClaimedRelayerRefund {
+                l2_token_address: ctx.accounts.mint.key(),
+                claim_amount: relay_data.output_amount,
+                refund_address: ctx.accounts.signer.key(),
+            });
+        }
+    }
 
     // Update the fill status to Filled, set the relayer and fill deadline
-    fill_status_account.status = FillStatus::Filled;
-    fill_status_account.relayer = *ctx.accounts.signer.key;
-    fill_status_account.fill_deadline = relay_data.fill_deadline;
+    fill_status.set_status(
+        FillStatus::Filled,
+        Some(*ctx.accounts.signer.key),
+        relay_data.fill_deadline,
+        current_time,
+    )?;
 
     if !relay_data.message.is_empty() {
-        invoke_handler(ctx.accounts.signer.as_ref(), ctx.remaining_accounts, &relay_data.message)?;
+        let deferred_relay_message = invoke_or_defer_handler(
+            ctx.accounts.signer.as_ref(),
+            ctx.remaining_accounts,
+            get_relay_hash(&relay_data, ctx.accounts.state.chain_id),
+            &relay_data.message,
+            &mut ctx.accounts.pending_relay_message,
+            ctx.bumps.pending_relay_message,
+            current_time,
+        )?;
+        if let Some(deferred_relay_message) = deferred_relay_message {
+            emit_cpi!(deferred_relay_message);
+        }
+    } else if ctx.accounts.pending_relay_message.is_some() {
+        return err!(SvmError::UnexpectedPendingRelayMessage);
     }
 
     // Empty message is not hashed and emits zeroed bytes32 for easier human observability.
@@ -158,9 +348,20 @@ pub fn fill_relay<'info>(
             updated_output_amount: relay_data.output_amount,
             fill_type,
         },
+        recipient_token_account_rent,
     });
 
-    Ok(())
+    // Companion event so that same chain fills settled from the deposit escrow are not also refunded in bundles.
+    if same_chain_settlement {
+        emit_cpi!(SettledSameChainRelay {
+            deposit_id: relay_data.deposit_id,
+            relayer: *ctx.accounts.signer.key,
+            input_token: relay_data.input_token,
+            input_amount: relay_data.input_amount,
+        });
+    }
+
+    Ok(relay_data)
 }
 
 // Helper to unwrap optional instruction params with fallback loading from buffer account.
@@ -190,12 +391,24 @@ pub struct CloseFillPda<'info> {
     #[account(mut, address = fill_status.relayer @ SvmError::NotRelayer)]
     pub signer: Signer<'info>,
 
-    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(
+        seeds = [b"state", state.seed.to_le_bytes().as_ref()],
+        bump = state.bump,
+        constraint = fill_tombstones.is_some() || !state.fill_tombstones_enabled @ SvmError::MissingFillTombstones
+    )]
     pub state: Account<'info, State>,
 
     // No need to check seed derivation as this method only evaluates fill deadline that is recorded in this account.
     #[account(mut, close = signer)]
     pub fill_status: Account<'info, FillStatusAccount>,
+
+    // Required once initialized so that the closed fills PDA can be told apart from an unfilled relay.
+    #[account(
+        mut,
+        seeds = [b"fill_tombstones", state.seed.to_le_bytes().as_ref(), [fill_status.key().to_bytes()[0]].as_ref()],
+        bump = fill_tombstones.load()?.bump
+    )]
+    pub fill_tombstones: Option<AccountLoader<'info, FillTombstones>>,
 }
 
 pub fn close_fill_pda(ctx: Context<CloseFillPda>) -> Result<()> {
@@ -207,5 +420,65 @@ pub fn close_fill_pda(ctx: Context<CloseFillPda>) -> Result<()> {
         return err!(SvmError::CanOnlyCloseFillStatusPdaIfFillDeadlinePassed);
     }
 
+    if let Some(fill_tombstones) = &ctx.accounts.fill_tombstones {
+        record_fill_tombstone(fill_tombstones.as_ref(), &ctx.accounts.fill_status)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseFillPdas<'info> {
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+}
+
+pub fn close_fill_pdas<'c, 'info>(ctx: Context<'_, '_, 'c, 'info, CloseFillPdas<'info>>) -> Result<()>
+where
+    'c: 'info,
+{
+    // Remaining accounts must be passed in tuples of fill status PDA and its recorded relayer, followed by the
+    // fill_tombstones shard of the fill status PDA once fill tombstones are initialized, so that the closed fills PDAs
+    // can be told apart from unfilled relays.
+    let tuple_len = if ctx.accounts.state.fill_tombstones_enabled {
+        3
+    } else {
+        2
+    };
+    let closes = ctx.remaining_accounts.chunks_exact(tuple_len);
+    if ctx.remaining_accounts.is_empty() || !closes.remainder().is_empty() {
+        return err!(SvmError::InvalidCloseFillPdasAccounts);
+    }
+
+    let current_time = get_current_time(&ctx.accounts.state)?;
+
+    for (i, accounts) in closes.enumerate() {
+        let account_name = |offset: usize| format!("remaining_accounts[{}]", i * tuple_len + offset);
+
+        // No need to check seed derivation as only the fill deadline and relayer recorded in this account are used.
+        let fill_status =
+            Account::<FillStatusAccount>::try_from(&accounts[0]).map_err(|e| e.with_account_name(account_name(0)))?;
+
+        // Rent is always returned to the recorded relayer, so anyone can close expired fill PDAs on their behalf.
+        if accounts[1].key() != fill_status.relayer {
+            return Err(Error::from(SvmError::NotRelayer).with_account_name(account_name(1)));
+        }
+
+        // Skip the deposits that have not expired yet so that a single entry does not block the whole batch.
+        if current_time <= fill_status.fill_deadline {
+            continue;
+        }
+
+        if let Some(fill_tombstones) = accounts.get(2) {
+            check_fill_tombstones_shard(fill_tombstones, &fill_status.key(), &ctx.accounts.state)
+                .map_err(|e| e.with_account_name(account_name(2)))?;
+            record_fill_tombstone(fill_tombstones, &fill_status).map_err(|e| e.with_account_name(account_name(2)))?;
+        }
+
+        fill_status
+            .close(accounts[1].clone())
+            .map_err(|e| e.with_account_name(account_name(0)))?;
+    }
+
     Ok(())
 }
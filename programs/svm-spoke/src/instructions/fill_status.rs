@@ -0,0 +1,389 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer},
+};
+
+use crate::{
+    common::RelayData,
+    constants::DISCRIMINATOR_SIZE,
+    constraints::is_local_or_remote_owner,
+    error::SvmError,
+    state::{
+        FillStatus, FillStatusAccount, FillStatusBackend, FillStatusBucket, FillStatusView, FillTombstone,
+        FillTombstones, State,
+    },
+    utils::get_relay_hash,
+};
+
+#[derive(Accounts)]
+#[instruction(bucket_index: u8)]
+pub struct InitializeFillStatusBucket<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    // Buckets are created once and reused by all relays whose hash starts with bucket_index, so no one is refunded.
+    #[account(
+        init,
+        payer = signer,
+        space = DISCRIMINATOR_SIZE + std::mem::size_of::<FillStatusBucket>(),
+        seeds = [b"fill_status_bucket", state.seed.to_le_bytes().as_ref(), [bucket_index].as_ref()],
+        bump
+    )]
+    pub fill_status_bucket: AccountLoader<'info, FillStatusBucket>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_fill_status_bucket(ctx: Context<InitializeFillStatusBucket>) -> Result<()> {
+    ctx.accounts.fill_status_bucket.load_init()?.bump = ctx.bumps.fill_status_bucket;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(relay_data: RelayData)]
+pub struct GetBucketedFillStatus<'info> {
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(
+        seeds = [
+            b"fill_status_bucket",
+            state.seed.to_le_bytes().as_ref(),
+            [get_relay_hash(&relay_data, state.chain_id)[0]].as_ref()
+        ],
+        bump = fill_status_bucket.load()?.bump
+    )]
+    pub fill_status_bucket: AccountLoader<'info, FillStatusBucket>,
+
+    /// CHECK: Fills PDA derived from the relay hash. It only exists if the relay overflowed the bucket or was tracked
+    /// before switching to the Bucketed backend.
+    #[account(seeds = [b"fills", get_relay_hash(&relay_data, state.chain_id).as_ref()], bump)]
+    pub fill_status: UncheckedAccount<'info>,
+}
+
+pub fn get_bucketed_fill_status(ctx: Context<GetBucketedFillStatus>, relay_data: RelayData) -> Result<FillStatus> {
+    if let Some(fill_status_account) = load_fill_status_pda(&ctx.accounts.fill_status)? {
+        return Ok(fill_status_account.status);
+    }
+
+    let relay_hash = get_relay_hash(&relay_data, ctx.accounts.state.chain_id);
+    Ok(ctx.accounts.fill_status_bucket.load()?.get_status(&relay_hash))
+}
+
+#[derive(Accounts)]
+#[instruction(shard_index: u8)]
+pub struct InitializeFillTombstones<'info> {
+    #[account(mut, constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = FillTombstones::TOMBSTONES_OFFSET,
+        seeds = [b"fill_tombstones", state.seed.to_le_bytes().as_ref(), [shard_index].as_ref()],
+        bump
+    )]
+    pub fill_tombstones: AccountLoader<'info, FillTombstones>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_fill_tombstones(ctx: Context<InitializeFillTombstones>) -> Result<()> {
+    ctx.accounts.fill_tombstones.load_init()?.bump = ctx.bumps.fill_tombstones;
+
+    // From now on fills PDAs can only be closed when recording their tombstones, so the owner should initialize all
+    // shards together, as fills PDAs of uninitialized shards cannot be closed.
+    ctx.accounts.state.fill_tombstones_enabled = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(relay_data: RelayData)]
+pub struct GetFillStatus<'info> {
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    /// CHECK: Fills PDA derived from the relay hash. It does not exist if the relay was never filled or it was closed.
+    #[account(seeds = [b"fills", get_relay_hash(&relay_data, state.chain_id).as_ref()], bump)]
+    pub fill_status: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"fill_tombstones", state.seed.to_le_bytes().as_ref(), [fill_status.key().to_bytes()[0]].as_ref()],
+        bump = fill_tombstones.load()?.bump
+    )]
+    pub fill_tombstones: Option<AccountLoader<'info, FillTombstones>>,
+
+    // Only used with the Bucketed fill status backend.
+    #[account(
+        seeds = [
+            b"fill_status_bucket",
+            state.seed.to_le_bytes().as_ref(),
+            [get_relay_hash(&relay_data, state.chain_id)[0]].as_ref()
+        ],
+        bump = fill_status_bucket.load()?.bump
+    )]
+    pub fill_status_bucket: Option<AccountLoader<'info, FillStatusBucket>>,
+}
+
+pub fn get_fill_status(ctx: Context<GetFillStatus>, relay_data: RelayData) -> Result<FillStatusView> {
+    let fill_status = &ctx.accounts.fill_status;
+
+    // Fills PDAs are read with both backends, as Bucketed falls back to them for relays that overflow the bucket.
+    if let Some(fill_status_account) = load_fill_status_pda(fill_status)? {
+        return Ok(FillStatusView {
+            status: fill_status_account.status,
+            relayer: fill_status_account.relayer,
+            fill_deadline: fill_status_account.fill_deadline,
+            closed: false,
+        });
+    }
+
+    match (ctx.accounts.state.fill_status_backend, &ctx.accounts.fill_status_bucket) {
+        (FillStatusBackend::Pda, None) => {}
+        (FillStatusBackend::Bucketed, Some(fill_status_bucket)) => {
+            let relay_hash = get_relay_hash(&relay_data, ctx.accounts.state.chain_id);
+            let fill_status_bucket = fill_status_bucket.load()?;
+            if let Some(entry) = fill_status_bucket
+                .entries
+                .iter()
+                .find(|entry| entry.relay_hash == relay_hash)
+            {
+                // Relayers are not recorded in buckets.
+                return Ok(FillStatusView {
+                    status: FillStatus::from_index(entry.status),
+                    relayer: Pubkey::default(),
+                    fill_deadline: entry.fill_deadline,
+                    closed: false,
+                });
+            }
+        }
+        _ => return err!(SvmError::InvalidFillStatusBackendAccount),
+    }
+
+    if let Some(fill_tombstones) = &ctx.accounts.fill_tombstones {
+        let fill_tombstones_data = fill_tombstones.as_ref().try_borrow_data()?;
+        let tombstones = &fill_tombstones_data[FillTombstones::TOMBSTONES_OFFSET..];
+        if let Some(tombstone) = FillTombstones::find(tombstones, &fill_status.key())? {
+            return Ok(FillStatusView {
+                status: tombstone.status,
+                relayer: tombstone.relayer,
+                fill_deadline: tombstone.fill_deadline,
+                closed: true,
+            });
+        }
+    }
+
+    Ok(FillStatusView { status: FillStatus::Unfilled, relayer: Pubkey::default(), fill_deadline: 0, closed: false })
+}
+
+// Returns the fills PDA contents, None if it was never created or it was closed.
+fn load_fill_status_pda(fill_status: &AccountInfo) -> Result<Option<FillStatusAccount>> {
+    if fill_status.owner != &crate::ID {
+        return Ok(None);
+    }
+
+    Ok(Some(FillStatusAccount::try_deserialize(&mut &fill_status.try_borrow_data()?[..])?))
+}
+
+// Creates the fills PDA for a relay that overflowed its fill status bucket. The address might have been pre-funded by
+// anyone, so only the missing rent is transferred in that case, as in Anchor init.
+fn create_fill_status_pda<'info>(
+    fill_status: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    relay_hash: &[u8; 32],
+) -> Result<()> {
+    let (_, bump) = Pubkey::find_program_address(&[b"fills", relay_hash.as_ref()], &crate::ID);
+    let signer_seeds: &[&[&[u8]]] = &[&[b"fills", relay_hash.as_ref(), &[bump]]];
+    let space = DISCRIMINATOR_SIZE + FillStatusAccount::INIT_SPACE;
+    let rent = Rent::get()?.minimum_balance(space);
+    let system_program = system_program.to_account_info();
+
+    if fill_status.lamports() == 0 {
+        let cpi_accounts = CreateAccount { from: payer.clone(), to: fill_status.clone() };
+        let cpi_context = CpiContext::new_with_signer(system_program, cpi_accounts, signer_seeds);
+        return create_account(cpi_context, rent, space as u64, &crate::ID);
+    }
+
+    let missing_rent = rent.saturating_sub(fill_status.lamports());
+    if missing_rent > 0 {
+        let cpi_accounts = Transfer { from: payer.clone(), to: fill_status.clone() };
+        transfer(CpiContext::new(system_program.clone(), cpi_accounts), missing_rent)?;
+    }
+    let cpi_accounts = Allocate { account_to_allocate: fill_status.clone() };
+    allocate(CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds), space as u64)?;
+    let cpi_accounts = Assign { account_to_assign: fill_status.clone() };
+    assign(CpiContext::new_with_signer(system_program, cpi_accounts, signer_seeds), &crate::ID)
+}
+
+// Records the tombstone of a fills PDA that is being closed in its fill_tombstones shard. The shard grows by one
+// tombstone whose rent is deducted from the closed fills PDA, so recording does not depend on a payer.
+pub fn record_fill_tombstone<'info>(
+    fill_tombstones: &AccountInfo<'info>,
+    fill_status: &Account<'info, FillStatusAccount>,
+) -> Result<()> {
+    let tombstone = FillTombstone {
+        fill_status: fill_status.key(),
+        relayer: fill_status.relayer,
+        fill_deadline: fill_status.fill_deadline,
+        status: fill_status.status.clone(),
+    };
+
+    let tombstones_len = fill_tombstones.data_len() - FillTombstones::TOMBSTONES_OFFSET;
+    let search_result = {
+        let fill_tombstones_data = fill_tombstones.try_borrow_data()?;
+        FillTombstones::search(&fill_tombstones_data[FillTombstones::TOMBSTONES_OFFSET..], &tombstone.fill_status)
+    };
+    let index = match search_result {
+        Ok(index) => index, // Only possible if the fills PDA was recreated, so the tombstone is overwritten in place.
+        Err(index) => {
+            let space = fill_tombstones.data_len() + FillTombstone::INIT_SPACE;
+            let missing_rent = Rent::get()?
+                .minimum_balance(space)
+                .saturating_sub(fill_tombstones.lamports());
+            fill_status.sub_lamports(missing_rent)?;
+            fill_tombstones.add_lamports(missing_rent)?;
+            fill_tombstones.realloc(space, false)?;
+
+            // Shift the following tombstones to keep them sorted.
+            let mut fill_tombstones_data = fill_tombstones.try_borrow_mut_data()?;
+            let tombstones = &mut fill_tombstones_data[FillTombstones::TOMBSTONES_OFFSET..];
+            let offset = index * FillTombstone::INIT_SPACE;
+            tombstones.copy_within(offset..tombstones_len, offset + FillTombstone::INIT_SPACE);
+            index
+        }
+    };
+
+    let offset = FillTombstones::TOMBSTONES_OFFSET + index * FillTombstone::INIT_SPACE;
+    let mut fill_tombstones_data = fill_tombstones.try_borrow_mut_data()?;
+    tombstone.serialize(&mut &mut fill_tombstones_data[offset..offset + FillTombstone::INIT_SPACE])?;
+
+    Ok(())
+}
+
+// Checks that the fill_tombstones account passed in remaining accounts is the initialized shard of the fills PDA.
+pub fn check_fill_tombstones_shard<'info>(
+    fill_tombstones: &'info AccountInfo<'info>,
+    fill_status: &Pubkey,
+    state: &State,
+) -> Result<()> {
+    let bump = AccountLoader::<FillTombstones>::try_from(fill_tombstones)?.load()?.bump;
+    let seeds: &[&[u8]] = &[
+        b"fill_tombstones",
+        &state.seed.to_le_bytes(),
+        &[fill_status.to_bytes()[0]],
+        &[bump],
+    ];
+    match Pubkey::create_program_address(seeds, &crate::ID) {
+        Ok(address) if &address == fill_tombstones.key => Ok(()),
+        _ => err!(ErrorCode::ConstraintSeeds),
+    }
+}
+
+// Tracks the fill status of a relay in the account matching the state fill status backend.
+pub enum FillStatusTracker<'a, 'info> {
+    Pda(&'a mut Account<'info, FillStatusAccount>),
+    Bucket {
+        fill_status_bucket: &'a AccountLoader<'info, FillStatusBucket>,
+        fill_status: &'a UncheckedAccount<'info>, // Fills PDA used when the relay does not fit in the bucket.
+        payer: Option<AccountInfo<'info>>,        // Pays for the fills PDA rent when the bucket is full.
+        system_program: &'a Program<'info, System>,
+        relay_hash: [u8; 32],
+    },
+}
+
+impl<'a, 'info> FillStatusTracker<'a, 'info> {
+    // Callers must provide exactly the accounts matching the backend. Relay hash is validated in account constraints.
+    pub fn new(
+        state: &State,
+        fill_status: &'a mut Option<Account<'info, FillStatusAccount>>,
+        fill_status_bucket: &'a Option<AccountLoader<'info, FillStatusBucket>>,
+        fill_status_overflow: &'a Option<UncheckedAccount<'info>>,
+        payer: Option<AccountInfo<'info>>,
+        system_program: &'a Program<'info, System>,
+        relay_data: &RelayData,
+    ) -> Result<Self> {
+        match (state.fill_status_backend, fill_status, fill_status_bucket, fill_status_overflow) {
+            (FillStatusBackend::Pda, Some(fill_status), None, None) => Ok(Self::Pda(fill_status)),
+            (FillStatusBackend::Bucketed, None, Some(fill_status_bucket), Some(fill_status)) => Ok(Self::Bucket {
+                fill_status_bucket,
+                fill_status,
+                payer,
+                system_program,
+                relay_hash: get_relay_hash(relay_data, state.chain_id),
+            }),
+            _ => err!(SvmError::InvalidFillStatusBackendAccount),
+        }
+    }
+
+    pub fn status(&self) -> Result<FillStatus> {
+        match self {
+            Self::Pda(fill_status) => Ok(fill_status.status.clone()),
+            Self::Bucket { fill_status_bucket, fill_status, relay_hash, .. } => {
+                match load_fill_status_pda(fill_status)? {
+                    Some(fill_status_account) => Ok(fill_status_account.status),
+                    None => Ok(fill_status_bucket.load()?.get_status(relay_hash)),
+                }
+            }
+        }
+    }
+
+    // The relayer is only recorded in fills PDAs to control who can close them. Pass None to keep the recorded relayer
+    // and fill deadline, as when executing a requested slow fill.
+    pub fn set_status(
+        &mut self,
+        status: FillStatus,
+        relayer: Option<Pubkey>,
+        fill_deadline: u32,
+        current_time: u32,
+    ) -> Result<()> {
+        match self {
+            Self::Pda(fill_status) => {
+                fill_status.status = status;
+                if let Some(relayer) = relayer {
+                    fill_status.relayer = relayer;
+                    fill_status.fill_deadline = fill_deadline;
+                }
+                Ok(())
+            }
+            Self::Bucket { fill_status_bucket, fill_status, payer, system_program, relay_hash } => {
+                // Relays already tracked in their fills PDA keep being tracked there.
+                let fill_status_account = match load_fill_status_pda(fill_status)? {
+                    Some(mut fill_status_account) => {
+                        fill_status_account.status = status;
+                        if let Some(relayer) = relayer {
+                            fill_status_account.relayer = relayer;
+                            fill_status_account.fill_deadline = fill_deadline;
+                        }
+                        fill_status_account
+                    }
+                    None => {
+                        if fill_status_bucket.load_mut()?.set_status(
+                            relay_hash,
+                            status.clone(),
+                            fill_deadline,
+                            current_time,
+                        ) {
+                            return Ok(());
+                        }
+
+                        // The bucket is full, so the relay overflows to its fills PDA paid by the payer.
+                        let payer = payer.as_ref().ok_or(SvmError::FillStatusBucketFull)?;
+                        create_fill_status_pda(fill_status, payer, system_program, relay_hash)?;
+                        FillStatusAccount { status, relayer: relayer.unwrap_or(payer.key()), fill_deadline }
+                    }
+                };
+
+                fill_status_account.try_serialize(&mut &mut fill_status.try_borrow_mut_data()?[..])
+            }
+        }
+    }
+}
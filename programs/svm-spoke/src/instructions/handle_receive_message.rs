@@ -4,7 +4,7 @@ use anchor_lang::{
 };
 
 use crate::{
-    constants::MESSAGE_TRANSMITTER_PROGRAM_ID,
+    constants::{DEFAULT_RELAYER_REFUND_LEAF_CAPACITY, MESSAGE_TRANSMITTER_PROGRAM_ID},
     error::{CallDataError, SvmError},
     program::SvmSpoke,
     state::State,
@@ -23,14 +23,14 @@ pub struct HandleReceiveMessage<'info> {
     pub authority_pda: Signer<'info>,
     #[account(
         seeds = [b"state", state.seed.to_le_bytes().as_ref()],
-        bump,
+        bump = state.bump,
         constraint = params.remote_domain == state.remote_domain @ SvmError::InvalidRemoteDomain,
         constraint = params.sender == state.cross_domain_admin @ SvmError::InvalidRemoteSender,
     )]
     pub state: Account<'info, State>,
 
     /// CHECK: empty PDA, used in authenticating self-CPI invoked by the received message.
-    #[account(seeds = [b"self_authority"], bump)]
+    #[account(seeds = [b"self_authority"], bump = state.self_authority_bump)]
     pub self_authority: UncheckedAccount<'info>,
     pub program: Program<'info, SvmSpoke>,
 }
@@ -82,7 +82,8 @@ fn translate_message(data: &Vec<u8>) -> Result<Vec<u8>> {
             let relayer_refund_root = utils::get_solidity_arg(data, 0)?;
             let slow_relay_root = utils::get_solidity_arg(data, 1)?;
 
-            (relayer_refund_root, slow_relay_root).encode_instruction_data("global:relay_root_bundle")
+            (relayer_refund_root, slow_relay_root, DEFAULT_RELAYER_REFUND_LEAF_CAPACITY)
+                .encode_instruction_data("global:relay_root_bundle")
         }
         s if s == utils::encode_solidity_selector("emergencyDeleteRootBundle(uint256)") => {
             let root_id = utils::decode_solidity_uint32(&utils::get_solidity_arg(data, 0)?)?;
@@ -97,7 +98,7 @@ fn translate_message(data: &Vec<u8>) -> Result<Vec<u8>> {
 // the self_authority account and passing all remaining accounts from the context. Instruction data is obtained within
 // handle_receive_message by translating the received message body into a valid instruction data for the invoked CPI.
 fn invoke_self<'info>(ctx: &Context<'_, '_, '_, 'info, HandleReceiveMessage<'info>>, data: &Vec<u8>) -> Result<()> {
-    let self_authority_seeds: &[&[&[u8]]] = &[&[b"self_authority", &[ctx.bumps.self_authority]]];
+    let self_authority_seeds: &[&[&[u8]]] = &[&[b"self_authority", &[ctx.accounts.state.self_authority_bump]]];
 
     let mut accounts = Vec::with_capacity(1 + ctx.remaining_accounts.len());
 
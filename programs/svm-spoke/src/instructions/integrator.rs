@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{DISCRIMINATOR_SIZE, MAX_INTEGRATOR_METADATA_URI_LEN, MAX_INTEGRATOR_NAME_LEN},
+    error::SvmError,
+    event::{RegisteredIntegrator, UpdatedIntegrator},
+    state::{Integrator, State},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(integrator_id: u16)]
+pub struct RegisterIntegrator<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(
+        init, // Use init, not init_if_needed, so that a registered integrator ID cannot be taken over.
+        payer = signer,
+        space = DISCRIMINATOR_SIZE + Integrator::INIT_SPACE,
+        seeds = [b"integrator", state.seed.to_le_bytes().as_ref(), integrator_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub integrator: Account<'info, Integrator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_integrator(
+    ctx: Context<RegisterIntegrator>,
+    integrator_id: u16,
+    name: String,
+    metadata_uri: String,
+) -> Result<()> {
+    validate_integrator_metadata(&name, &metadata_uri)?;
+
+    let integrator = &mut ctx.accounts.integrator;
+    integrator.authority = ctx.accounts.signer.key();
+    integrator.name = name.clone();
+    integrator.metadata_uri = metadata_uri.clone();
+
+    emit_cpi!(RegisteredIntegrator { integrator_id, authority: integrator.authority, name, metadata_uri });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(integrator_id: u16)]
+pub struct UpdateIntegrator<'info> {
+    #[account(address = integrator.authority @ SvmError::NotIntegratorAuthority)]
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(
+        mut,
+        seeds = [b"integrator", state.seed.to_le_bytes().as_ref(), integrator_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub integrator: Account<'info, Integrator>,
+}
+
+pub fn update_integrator(
+    ctx: Context<UpdateIntegrator>,
+    integrator_id: u16,
+    new_authority: Pubkey,
+    name: String,
+    metadata_uri: String,
+) -> Result<()> {
+    validate_integrator_metadata(&name, &metadata_uri)?;
+
+    let integrator = &mut ctx.accounts.integrator;
+    integrator.authority = new_authority;
+    integrator.name = name.clone();
+    integrator.metadata_uri = metadata_uri.clone();
+
+    emit_cpi!(UpdatedIntegrator { integrator_id, authority: new_authority, name, metadata_uri });
+
+    Ok(())
+}
+
+// Account space is allocated for the maximum lengths, so longer values would fail to serialize on exit.
+fn validate_integrator_metadata(name: &str, metadata_uri: &str) -> Result<()> {
+    if name.len() > MAX_INTEGRATOR_NAME_LEN || metadata_uri.len() > MAX_INTEGRATOR_METADATA_URI_LEN {
+        return err!(SvmError::IntegratorMetadataTooLong);
+    }
+
+    Ok(())
+}
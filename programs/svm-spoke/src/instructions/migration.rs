@@ -0,0 +1,253 @@
+use std::mem::size_of;
+
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+
+use crate::{
+    constants::DISCRIMINATOR_SIZE,
+    constraints::is_local_or_remote_owner,
+    error::SvmError,
+    state::{ClaimAccount, FillStatusBackend, RootBundle, Route, State},
+    utils::get_current_time,
+};
+
+// Accounts created before the canonical bumps and later fields were appended to State, Route and ClaimAccount cannot
+// be deserialized by the current program. These instructions grow such legacy accounts to the current layout. The
+// appended bytes are zero initialized, which is the legacy behavior of all appended fields except the stored bumps that
+// are set here from their canonical derivation. Migration is permissionless as it only writes derived values, so the
+// signer just pays for the additional rent.
+
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Legacy state cannot be deserialized before migration, its discriminator and seeds are checked on migration.
+    #[account(mut, owner = crate::ID)]
+    pub state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+    let state_info = ctx.accounts.state.to_account_info();
+    check_legacy_account(&state_info, &State::DISCRIMINATOR, State::LEGACY_SPACE)?;
+
+    // The seed follows the paused flags and owner in the state layout, which is unchanged in the legacy prefix.
+    let seed_offset = DISCRIMINATOR_SIZE + 2 * size_of::<bool>() + size_of::<Pubkey>();
+    let seed_bytes = state_info.data.borrow()[seed_offset..seed_offset + size_of::<u64>()].to_vec();
+    let (state_address, state_bump) = Pubkey::find_program_address(&[b"state", &seed_bytes], &crate::ID);
+    if state_info.key() != state_address {
+        return Err(Error::from(ErrorCode::ConstraintSeeds).with_pubkeys((state_info.key(), state_address)));
+    }
+
+    grow_legacy_account(
+        &state_info,
+        &ctx.accounts.signer,
+        &ctx.accounts.system_program,
+        DISCRIMINATOR_SIZE + State::INIT_SPACE,
+    )?;
+
+    let mut state = State::try_deserialize(&mut &state_info.data.borrow()[..])?;
+    state.bump = state_bump;
+    state.self_authority_bump = Pubkey::find_program_address(&[b"self_authority"], &crate::ID).1;
+    state.fill_status_backend = FillStatusBackend::Pda; // Legacy fills are tracked in fills PDAs.
+    state.fill_tombstones_enabled = false; // Closing fills PDAs only records tombstones once they are initialized.
+    state.try_serialize(&mut &mut state_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(origin_token: Pubkey, destination_chain_id: u64)]
+pub struct MigrateRoute<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    /// CHECK: Legacy route cannot be deserialized before migration, its discriminator is checked on migration.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [
+            b"route",
+            origin_token.as_ref(),
+            state.seed.to_le_bytes().as_ref(),
+            destination_chain_id.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub route: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_route(ctx: Context<MigrateRoute>) -> Result<()> {
+    let route_info = ctx.accounts.route.to_account_info();
+    check_legacy_account(&route_info, &Route::DISCRIMINATOR, Route::LEGACY_SPACE)?;
+    grow_legacy_account(
+        &route_info,
+        &ctx.accounts.signer,
+        &ctx.accounts.system_program,
+        DISCRIMINATOR_SIZE + Route::INIT_SPACE,
+    )?;
+
+    let mut route = Route::try_deserialize(&mut &route_info.data.borrow()[..])?;
+    route.bump = ctx.bumps.route;
+    route.try_serialize(&mut &mut route_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateClaimAccount<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is only used for claim_account PDA derivation.
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: This is only used for claim_account PDA derivation.
+    pub refund_address: UncheckedAccount<'info>,
+
+    /// CHECK: Legacy claim account cannot be deserialized before migration, its discriminator is checked on migration.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"claim_account", mint.key().as_ref(), refund_address.key().as_ref()],
+        bump
+    )]
+    pub claim_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_claim_account(ctx: Context<MigrateClaimAccount>) -> Result<()> {
+    let claim_account_info = ctx.accounts.claim_account.to_account_info();
+    check_legacy_account(&claim_account_info, &ClaimAccount::DISCRIMINATOR, ClaimAccount::LEGACY_SPACE)?;
+    grow_legacy_account(
+        &claim_account_info,
+        &ctx.accounts.signer,
+        &ctx.accounts.system_program,
+        DISCRIMINATOR_SIZE + ClaimAccount::INIT_SPACE,
+    )?;
+
+    let mut claim_account = ClaimAccount::try_deserialize(&mut &claim_account_info.data.borrow()[..])?;
+    claim_account.bump = ctx.bumps.claim_account;
+    claim_account.try_serialize(&mut &mut claim_account_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+// Root bundles relayed before the claimed bitmap was moved to zero-copy account data were Borsh encoded as the two roots
+// followed by the claimed bitmap Vec. Unlike the other migrations this also assigns the payer that will be refunded the
+// rent once the bundle is closed, so it is only callable by the owner.
+#[derive(Accounts)]
+#[instruction(root_bundle_id: u32)]
+pub struct MigrateRootBundle<'info> {
+    #[account(constraint = is_local_or_remote_owner(&signer, &state)? @ SvmError::NotOwner)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(
+        mut,
+        seeds = [b"root_bundle", state.seed.to_le_bytes().as_ref(), root_bundle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub root_bundle: AccountLoader<'info, RootBundle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_root_bundle(ctx: Context<MigrateRootBundle>, relayer_refund_leaf_count: u32) -> Result<()> {
+    let root_bundle_info = ctx.accounts.root_bundle.to_account_info();
+
+    // The legacy claimed bitmap Vec must span exactly the rest of the account data.
+    let (relayer_refund_root, slow_relay_root, claimed_bitmap) = {
+        let data = root_bundle_info.try_borrow_data()?;
+        if data.len() < RootBundle::LEGACY_CLAIMED_BITMAP_OFFSET {
+            return err!(SvmError::AccountAlreadyMigrated);
+        }
+        let mut legacy_bitmap_data = &data[RootBundle::LEGACY_CLAIMED_BITMAP_OFFSET..];
+        let claimed_bitmap =
+            Vec::<u8>::deserialize(&mut legacy_bitmap_data).map_err(|_| SvmError::AccountAlreadyMigrated)?;
+        if !legacy_bitmap_data.is_empty() {
+            return err!(SvmError::AccountAlreadyMigrated);
+        }
+        let roots = &data[DISCRIMINATOR_SIZE..RootBundle::LEGACY_CLAIMED_BITMAP_OFFSET];
+        let (relayer_refund_root, slow_relay_root) = roots.split_at(size_of::<[u8; 32]>());
+        (relayer_refund_root.try_into().unwrap(), slow_relay_root.try_into().unwrap(), claimed_bitmap)
+    };
+
+    // Leaves beyond the legacy claimed bitmap might not have been executed yet, so it must cover all the leaf IDs.
+    let relayer_refund_leaf_capacity = std::cmp::max((claimed_bitmap.len() * 8) as u32, relayer_refund_leaf_count);
+    grow_legacy_account(
+        &root_bundle_info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        RootBundle::space(relayer_refund_leaf_capacity),
+    )?;
+
+    // Legacy bundles could only execute leaves in a single transaction, so each claimed leaf has been completed. Their
+    // hashes were not recorded, so the leaf count is recorded on migration instead of being proven on close. The slow
+    // fill deadline is bounded as if the bundle was relayed now, since it has been relayed before the migration.
+    let state = &ctx.accounts.state;
+    *ctx.accounts.root_bundle.load_mut()? = RootBundle {
+        relayer_refund_root,
+        slow_relay_root,
+        payer: ctx.accounts.payer.key(),
+        relayer_refund_leaf_count,
+        slow_fill_deadline: RootBundle::slow_fill_deadline_bound(
+            &slow_relay_root,
+            get_current_time(state)?,
+            state.fill_deadline_buffer,
+        ),
+        claimed_leaf_count: claimed_bitmap.iter().map(|byte| byte.count_ones()).sum(),
+        claimed_leaf_hashes: [0u8; 32],
+        completion_target_set: 1,
+        bump: ctx.bumps.root_bundle,
+    };
+    let claimed_bitmap_range =
+        RootBundle::CLAIMED_BITMAP_OFFSET..RootBundle::CLAIMED_BITMAP_OFFSET + claimed_bitmap.len();
+    root_bundle_info.try_borrow_mut_data()?[claimed_bitmap_range].copy_from_slice(&claimed_bitmap);
+
+    Ok(())
+}
+
+fn check_legacy_account(account: &AccountInfo, discriminator: &[u8], legacy_space: usize) -> Result<()> {
+    let data = account.try_borrow_data()?;
+    if data.len() < DISCRIMINATOR_SIZE || &data[..DISCRIMINATOR_SIZE] != discriminator {
+        return err!(ErrorCode::AccountDiscriminatorMismatch);
+    }
+    if data.len() != DISCRIMINATOR_SIZE + legacy_space {
+        return err!(SvmError::AccountAlreadyMigrated);
+    }
+
+    Ok(())
+}
+
+// Tops up the rent of the legacy account from the signer and grows it to the current account space.
+fn grow_legacy_account<'info>(
+    account: &AccountInfo<'info>,
+    signer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    let rent_top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if rent_top_up > 0 {
+        let transfer_accounts = Transfer { from: signer.to_account_info(), to: account.clone() };
+        transfer(CpiContext::new(system_program.to_account_info(), transfer_accounts), rent_top_up)?;
+    }
+
+    account.realloc(space, true).map_err(Into::into)
+}
@@ -3,9 +3,14 @@ mod bundle;
 mod create_token_accounts;
 mod deposit;
 mod fill;
+mod fill_status;
 mod handle_receive_message;
 mod instruction_params;
+mod integrator;
+mod migration;
 mod refund_claims;
+mod relay_message;
+mod same_chain;
 mod slow_fill;
 mod token_bridge;
 
@@ -14,8 +19,13 @@ pub use bundle::*;
 pub use create_token_accounts::*;
 pub use deposit::*;
 pub use fill::*;
+pub use fill_status::*;
 pub use handle_receive_message::*;
 pub use instruction_params::*;
+pub use integrator::*;
+pub use migration::*;
 pub use refund_claims::*;
+pub use relay_message::*;
+pub use same_chain::*;
 pub use slow_fill::*;
 pub use token_bridge::*;
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
 
 use crate::{
     constants::DISCRIMINATOR_SIZE,
@@ -7,6 +10,7 @@ use crate::{
     error::SvmError,
     event::ClaimedRelayerRefund,
     state::{ClaimAccount, State},
+    utils::transfer_from,
 };
 
 #[derive(Accounts)]
@@ -35,6 +39,7 @@ pub struct InitializeClaimAccount<'info> {
 pub fn initialize_claim_account(ctx: Context<InitializeClaimAccount>) -> Result<()> {
     // Store the initializer so only it can receive lamports from closing the account upon claiming the refund.
     ctx.accounts.claim_account.initializer = ctx.accounts.signer.key();
+    ctx.accounts.claim_account.bump = ctx.bumps.claim_account;
 
     Ok(())
 }
@@ -48,7 +53,7 @@ pub struct ClaimRelayerRefund<'info> {
     #[account(mut, address = claim_account.initializer @ SvmError::InvalidClaimInitializer)]
     pub initializer: UncheckedAccount<'info>,
 
-    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 
     #[account(
@@ -82,7 +87,7 @@ pub struct ClaimRelayerRefund<'info> {
         mut,
         close = initializer,
         seeds = [b"claim_account", mint.key().as_ref(), refund_address.key().as_ref()],
-        bump
+        bump = claim_account.bump
     )]
     pub claim_account: Account<'info, ClaimAccount>,
 
@@ -98,7 +103,7 @@ pub fn claim_relayer_refund(ctx: Context<ClaimRelayerRefund>) -> Result<()> {
 
     // Derive the signer seeds for the state required for the transfer form vault.
     let state_seed_bytes = ctx.accounts.state.seed.to_le_bytes();
-    let seeds = &[b"state", state_seed_bytes.as_ref(), &[ctx.bumps.state]];
+    let seeds = &[b"state", state_seed_bytes.as_ref(), &[ctx.accounts.state.bump]];
     let signer_seeds = &[&seeds[..]];
 
     // Transfer the claim amount from the vault to the relayer token account.
@@ -121,6 +126,94 @@ pub fn claim_relayer_refund(ctx: Context<ClaimRelayerRefund>) -> Result<()> {
     Ok(()) // There is no need to reset the claim amount as the account will be closed at the end of instruction.
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimRelayerRefunds<'info> {
+    pub signer: Signer<'info>,
+
+    /// CHECK: This is used for claim_account PDA derivation and it is up to the caller to ensure it is valid.
+    pub refund_address: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_relayer_refunds<'c, 'info>(ctx: Context<'_, '_, 'c, 'info, ClaimRelayerRefunds<'info>>) -> Result<()>
+where
+    'c: 'info,
+{
+    // Remaining accounts must be passed in tuples of mint, vault, claim account and token account.
+    let claims = ctx.remaining_accounts.chunks_exact(4);
+    if ctx.remaining_accounts.is_empty() || !claims.remainder().is_empty() {
+        return err!(SvmError::InvalidClaimRefundAccounts);
+    }
+
+    let refund_address = ctx.accounts.refund_address.key();
+    let token_program = &ctx.accounts.token_program;
+
+    for (i, accounts) in claims.enumerate() {
+        // Applies the same checks as the account constraints in ClaimRelayerRefund.
+        let account_name = |offset: usize| format!("remaining_accounts[{}]", i * 4 + offset);
+
+        let mint =
+            InterfaceAccount::<Mint>::try_from(&accounts[0]).map_err(|e| e.with_account_name(account_name(0)))?;
+        if accounts[0].owner != &token_program.key() {
+            return Err(Error::from(ErrorCode::ConstraintMintTokenProgram).with_account_name(account_name(0)));
+        }
+
+        let vault = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])
+            .map_err(|e| e.with_account_name(account_name(1)))?;
+        let vault_address =
+            get_associated_token_address_with_program_id(&ctx.accounts.state.key(), &mint.key(), &token_program.key());
+        if vault.key() != vault_address {
+            return Err(Error::from(ErrorCode::ConstraintAssociated).with_account_name(account_name(1)));
+        }
+
+        let mut claim_account = ClaimAccount::try_from(&accounts[2], &mint.key(), &refund_address)
+            .map_err(|e| e.with_account_name(account_name(2)))?;
+        let claim_amount = claim_account.amount;
+        if claim_amount == 0 {
+            return Err(Error::from(SvmError::ZeroRefundClaim).with_account_name(account_name(2)));
+        }
+
+        // If refund_address is the same as signer this allows relayer to claim refunds on any custom token account.
+        // Otherwise this must be the associated token account of the provided refund_address.
+        let token_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[3])
+            .map_err(|e| e.with_account_name(account_name(3)))?;
+        if token_account.mint != mint.key() {
+            return Err(Error::from(ErrorCode::ConstraintTokenMint).with_account_name(account_name(3)));
+        }
+        if refund_address != ctx.accounts.signer.key()
+            && !is_valid_associated_token_account(&token_account, &mint, token_program, &refund_address)
+        {
+            return Err(Error::from(SvmError::InvalidRefundTokenAccount).with_account_name(account_name(3)));
+        }
+
+        transfer_from(
+            &vault,
+            &token_account,
+            claim_amount,
+            &ctx.accounts.state,
+            ctx.accounts.state.bump,
+            &mint,
+            token_program,
+        )?;
+
+        // Claim accounts are kept open so that they can be reused for deferred refunds in the following bundles. The
+        // initializer can close them with close_claim_account.
+        claim_account.amount = 0;
+        claim_account
+            .exit(ctx.program_id)
+            .map_err(|e| e.with_account_name(account_name(2)))?;
+
+        emit_cpi!(ClaimedRelayerRefund { l2_token_address: mint.key(), claim_amount, refund_address });
+    }
+
+    Ok(())
+}
+
 // Though claim accounts are being closed automatically when claiming the refund, there might be a scenario where
 // relayer refunds were executed with ATA after initializing the claim account. In such cases, the initializer should be
 // able to close the claim account manually.
@@ -139,7 +232,7 @@ pub struct CloseClaimAccount<'info> {
         mut,
         close = signer,
         seeds = [b"claim_account", mint.key().as_ref(), refund_address.key().as_ref()],
-        bump
+        bump = claim_account.bump
     )]
     pub claim_account: Account<'info, ClaimAccount>,
 }
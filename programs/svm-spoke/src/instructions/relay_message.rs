@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::SvmError,
+    event::{ExecutedRelayMessage, ExpiredRelayMessage},
+    state::{PendingRelayMessage, State},
+    utils::{get_current_time, hash_non_empty_message, invoke_handler},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(relay_hash: [u8; 32])]
+pub struct ExecuteRelayMessage<'info> {
+    // Funds the value_amount of the message, same as the relayer when the handler is invoked within the fill.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_relay_message", relay_hash.as_ref()],
+        bump = pending_relay_message.bump,
+        close = relayer
+    )]
+    pub pending_relay_message: Account<'info, PendingRelayMessage>,
+
+    /// CHECK: Only receives the rent of the closed pending_relay_message.
+    #[account(mut, address = pending_relay_message.relayer @ SvmError::NotRelayer)]
+    pub relayer: UncheckedAccount<'info>,
+}
+
+pub fn execute_relay_message<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteRelayMessage<'info>>,
+    relay_hash: [u8; 32],
+) -> Result<()> {
+    let message = &ctx.accounts.pending_relay_message.message;
+
+    invoke_handler(ctx.accounts.signer.as_ref(), ctx.remaining_accounts, message)?;
+
+    emit_cpi!(ExecutedRelayMessage {
+        relay_hash,
+        message_hash: hash_non_empty_message(message),
+        caller: ctx.accounts.signer.key(),
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(relay_hash: [u8; 32])]
+pub struct ClosePendingRelayMessage<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_relay_message", relay_hash.as_ref()],
+        bump = pending_relay_message.bump,
+        close = relayer
+    )]
+    pub pending_relay_message: Account<'info, PendingRelayMessage>,
+
+    /// CHECK: Only receives the rent of the closed pending_relay_message.
+    #[account(mut, address = pending_relay_message.relayer @ SvmError::NotRelayer)]
+    pub relayer: UncheckedAccount<'info>,
+}
+
+pub fn close_pending_relay_message(ctx: Context<ClosePendingRelayMessage>, relay_hash: [u8; 32]) -> Result<()> {
+    let current_time = get_current_time(&ctx.accounts.state)?;
+
+    // Executors have the execution window after the fill to execute the message, after which it can expire unexecuted.
+    if current_time <= ctx.accounts.pending_relay_message.execution_deadline {
+        return err!(SvmError::CanOnlyClosePendingRelayMessageIfExecutionDeadlinePassed);
+    }
+
+    emit_cpi!(ExpiredRelayMessage {
+        relay_hash,
+        message_hash: hash_non_empty_message(&ctx.accounts.pending_relay_message.message),
+        caller: ctx.accounts.signer.key(),
+    });
+
+    Ok(())
+}
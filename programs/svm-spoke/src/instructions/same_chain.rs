@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    common::RelayData,
+    constants::DISCRIMINATOR_SIZE,
+    error::SvmError,
+    instructions::*, // Glob import brings in the generated client modules required by the nested account structs.
+    state::{SameChainDeposit, State},
+    utils::{get_current_time, get_relay_hash, get_unsafe_deposit_id},
+};
+
+#[derive(Accounts)]
+#[instruction(params: DepositSameChainParams)]
+pub struct DepositSameChain<'info> {
+    pub deposit: Deposit<'info>,
+
+    // Seeded by the deterministic deposit ID so that the address does not depend on the state deposit counter.
+    #[account(
+        init,
+        payer = deposit.signer,
+        space = DISCRIMINATOR_SIZE + SameChainDeposit::INIT_SPACE,
+        seeds = [
+            b"same_chain_deposit",
+            deposit.state.seed.to_le_bytes().as_ref(),
+            get_unsafe_deposit_id(deposit.signer.key(), params.depositor, params.deposit_nonce).as_ref()
+        ],
+        bump
+    )]
+    pub same_chain_deposit: Account<'info, SameChainDeposit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DepositSameChainParams {
+    pub depositor: Pubkey,
+    pub recipient: Pubkey,
+    pub input_token: Pubkey,
+    pub output_token: Pubkey,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    pub destination_chain_id: u64,
+    pub exclusive_relayer: Pubkey,
+    pub deposit_nonce: u64,
+    pub quote_timestamp: u32,
+    pub fill_deadline: u32,
+    pub exclusivity_parameter: u32,
+    pub message: Vec<u8>,
+}
+
+pub fn deposit_same_chain<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositSameChain<'info>>,
+    params: DepositSameChainParams,
+) -> Result<()> {
+    let DepositSameChainParams {
+        depositor,
+        recipient,
+        input_token,
+        output_token,
+        input_amount,
+        output_amount,
+        destination_chain_id,
+        exclusive_relayer,
+        deposit_nonce,
+        quote_timestamp,
+        fill_deadline,
+        exclusivity_parameter,
+        message,
+    } = params;
+
+    let chain_id = ctx.accounts.deposit.state.chain_id;
+    if destination_chain_id != chain_id {
+        return err!(SvmError::InvalidSameChainDestination);
+    }
+
+    let signer = ctx.accounts.deposit.signer.key();
+    let deposit_id = get_unsafe_deposit_id(signer, depositor, deposit_nonce);
+    let deposit_ctx =
+        Context::new(ctx.program_id, &mut ctx.accounts.deposit, ctx.remaining_accounts, ctx.bumps.deposit);
+    let exclusivity_deadline = _deposit(
+        deposit_ctx,
+        depositor,
+        recipient,
+        input_token,
+        output_token,
+        input_amount,
+        output_amount,
+        destination_chain_id,
+        exclusive_relayer,
+        deposit_id,
+        quote_timestamp,
+        fill_deadline,
+        exclusivity_parameter,
+        message.clone(),
+        None,
+    )?;
+
+    // Record the relay hash as it would be reconstructed by the filler from the FundsDeposited event.
+    let relay_data = RelayData {
+        depositor,
+        recipient,
+        exclusive_relayer,
+        input_token,
+        output_token,
+        input_amount,
+        output_amount,
+        origin_chain_id: chain_id,
+        deposit_id,
+        fill_deadline,
+        exclusivity_deadline,
+        message,
+    };
+    let same_chain_deposit = &mut ctx.accounts.same_chain_deposit;
+    same_chain_deposit.relay_hash = get_relay_hash(&relay_data, chain_id);
+    same_chain_deposit.payer = signer;
+    same_chain_deposit.fill_deadline = fill_deadline;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(relay_hash: [u8; 32])]
+pub struct FillSameChainRelay<'info> {
+    pub fill: FillRelay<'info>,
+
+    // Relay hash is validated against the relay data in the fill_status constraint of the nested fill accounts.
+    #[account(
+        mut,
+        close = deposit_payer,
+        constraint = same_chain_deposit.relay_hash == relay_hash @ SvmError::InvalidRelayHash
+    )]
+    pub same_chain_deposit: Account<'info, SameChainDeposit>,
+
+    /// CHECK: Only receives the rent of the closed same_chain_deposit.
+    #[account(mut, address = same_chain_deposit.payer @ SvmError::InvalidSameChainDepositPayer)]
+    pub deposit_payer: UncheckedAccount<'info>,
+
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = input_mint,
+        associated_token::authority = fill.state,
+        associated_token::token_program = input_token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = fill.signer,
+        token::token_program = input_token_program
+    )]
+    pub relayer_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub input_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn fill_same_chain_relay<'info>(
+    ctx: Context<'_, '_, '_, 'info, FillSameChainRelay<'info>>,
+    relay_data: Option<RelayData>,
+    repayment_chain_id: Option<u64>,
+    repayment_address: Option<Pubkey>,
+    exclusivity_proof: Option<Vec<[u8; 32]>>,
+) -> Result<()> {
+    let state_bump = ctx.accounts.fill.state.bump;
+    let fill_ctx = Context::new(ctx.program_id, &mut ctx.accounts.fill, ctx.remaining_accounts, ctx.bumps.fill);
+    let relay_data = _fill_relay(
+        fill_ctx,
+        relay_data,
+        repayment_chain_id,
+        repayment_address,
+        exclusivity_proof,
+        FillFundingSource::RelayerTokenAccount,
+        true,
+    )?;
+
+    if ctx.accounts.input_mint.key() != relay_data.input_token {
+        return err!(SvmError::InvalidMint);
+    }
+
+    // Release the escrowed deposit input to the filler instead of refunding it in a bundle.
+    let state_seed_bytes = ctx.accounts.fill.state.seed.to_le_bytes();
+    let seeds = &[b"state", state_seed_bytes.as_ref(), &[state_bump]];
+    let signer_seeds = &[&seeds[..]];
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.vault.to_account_info(),
+        mint: ctx.accounts.input_mint.to_account_info(),
+        to: ctx.accounts.relayer_input_token_account.to_account_info(),
+        authority: ctx.accounts.fill.state.to_account_info(),
+    };
+    let cpi_context = CpiContext::new_with_signer(
+        ctx.accounts.input_token_program.to_account_info(),
+        transfer_accounts,
+        signer_seeds,
+    );
+    transfer_checked(cpi_context, relay_data.input_amount, ctx.accounts.input_mint.decimals)
+}
+
+#[derive(Accounts)]
+pub struct CloseSameChainDeposit<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+
+    #[account(mut, close = deposit_payer)]
+    pub same_chain_deposit: Account<'info, SameChainDeposit>,
+
+    /// CHECK: Only receives the rent of the closed same_chain_deposit.
+    #[account(mut, address = same_chain_deposit.payer @ SvmError::InvalidSameChainDepositPayer)]
+    pub deposit_payer: UncheckedAccount<'info>,
+}
+
+pub fn close_same_chain_deposit(ctx: Context<CloseSameChainDeposit>) -> Result<()> {
+    let current_time = get_current_time(&ctx.accounts.state)?;
+
+    // Unfilled same chain deposits are refunded to the depositor in bundles after the fill deadline.
+    if current_time <= ctx.accounts.same_chain_deposit.fill_deadline {
+        return err!(SvmError::CanOnlyCloseSameChainDepositIfFillDeadlinePassed);
+    }
+
+    Ok(())
+}
@@ -1,14 +1,24 @@
 use anchor_lang::{prelude::*, solana_program::keccak};
-use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
 
 use crate::event::{FillType, FilledRelay, RelayExecutionEventInfo, RequestedSlowFill};
 use crate::{
     common::RelayData,
     constants::DISCRIMINATOR_SIZE,
-    constraints::is_relay_hash_valid,
+    constraints::{check_associated_token_account, is_relay_hash_valid},
     error::{CommonError, SvmError},
-    state::{ExecuteSlowRelayLeafParams, FillStatus, FillStatusAccount, RequestSlowFillParams, RootBundle, State},
-    utils::{get_current_time, hash_non_empty_message, invoke_handler, verify_merkle_proof},
+    instructions::FillStatusTracker,
+    state::{
+        ExecuteSlowRelayLeafParams, FillStatus, FillStatusAccount, FillStatusBucket, PendingRelayMessage,
+        RequestSlowFillParams, RootBundle, State,
+    },
+    utils::{
+        create_associated_token_account_if_needed, get_current_time, get_relay_hash, hash_non_empty_message,
+        invoke_or_defer_handler, verify_merkle_proof,
+    },
 };
 
 #[event_cpi]
@@ -24,11 +34,12 @@ pub struct RequestSlowFill<'info> {
 
     #[account(
         seeds = [b"state", state.seed.to_le_bytes().as_ref()],
-        bump,
+        bump = state.bump,
         constraint = !state.paused_fills @ CommonError::FillsArePaused
     )]
     pub state: Account<'info, State>,
 
+    // Only used with the Pda fill status backend.
     #[account(
         init_if_needed,
         payer = signer,
@@ -40,8 +51,25 @@ pub struct RequestSlowFill<'info> {
             &relay_data.clone().unwrap_or_else(|| instruction_params.as_ref().unwrap().relay_data.clone()),
             &state) @ SvmError::InvalidRelayHash
     )]
-    pub fill_status: Account<'info, FillStatusAccount>,
+    pub fill_status: Option<Account<'info, FillStatusAccount>>,
     pub system_program: Program<'info, System>,
+
+    // Only used with the Bucketed fill status backend.
+    #[account(
+        mut,
+        seeds = [b"fill_status_bucket", state.seed.to_le_bytes().as_ref(), [relay_hash[0]].as_ref()],
+        bump = fill_status_bucket.load()?.bump,
+        constraint = is_relay_hash_valid(
+            &relay_hash,
+            &relay_data.clone().unwrap_or_else(|| instruction_params.as_ref().unwrap().relay_data.clone()),
+            &state) @ SvmError::InvalidRelayHash
+    )]
+    pub fill_status_bucket: Option<AccountLoader<'info, FillStatusBucket>>,
+
+    /// CHECK: Fills PDA of the relay, only used with the Bucketed fill status backend. It is read for relays tracked
+    /// before switching backends and only created when the fill_status_bucket is full.
+    #[account(mut, seeds = [b"fills", relay_hash.as_ref()], bump)]
+    pub fill_status_overflow: Option<UncheckedAccount<'info>>,
 }
 
 pub fn request_slow_fill(ctx: Context<RequestSlowFill>, relay_data: Option<RelayData>) -> Result<()> {
@@ -52,7 +80,8 @@ pub fn request_slow_fill(ctx: Context<RequestSlowFill>, relay_data: Option<Relay
 
     let current_time = get_current_time(state)?;
 
-    // Check if the fill is past the exclusivity window & within the fill deadline.
+    // Check if the fill is past the exclusivity window & within the fill deadline. The window applies equally to a
+    // single exclusive relayer and to an exclusive relayer set, so no slow fill can be requested until it has passed.
     if relay_data.exclusivity_deadline >= current_time {
         return err!(CommonError::NoSlowFillsInExclusivityWindow);
     }
@@ -61,14 +90,26 @@ pub fn request_slow_fill(ctx: Context<RequestSlowFill>, relay_data: Option<Relay
     }
 
     // Check the fill status is unfilled.
-    let fill_status_account = &mut ctx.accounts.fill_status;
-    if fill_status_account.status != FillStatus::Unfilled {
+    let mut fill_status = FillStatusTracker::new(
+        state,
+        &mut ctx.accounts.fill_status,
+        &ctx.accounts.fill_status_bucket,
+        &ctx.accounts.fill_status_overflow,
+        Some(ctx.accounts.signer.to_account_info()),
+        &ctx.accounts.system_program,
+        &relay_data,
+    )?;
+    if fill_status.status()? != FillStatus::Unfilled {
         return err!(CommonError::InvalidSlowFillRequest);
     }
 
-    fill_status_account.status = FillStatus::RequestedSlowFill; // Update the fill status to RequestedSlowFill
-    fill_status_account.relayer = ctx.accounts.signer.key();
-    fill_status_account.fill_deadline = relay_data.fill_deadline;
+    // Update the fill status to RequestedSlowFill
+    fill_status.set_status(
+        FillStatus::RequestedSlowFill,
+        Some(ctx.accounts.signer.key()),
+        relay_data.fill_deadline,
+        current_time,
+    )?;
 
     // Emit the RequestedSlowFill event. Empty message is not hashed and emits zeroed bytes32 for easier observability
     let message_hash = hash_non_empty_message(&relay_data.message);
@@ -141,7 +182,7 @@ pub struct ExecuteSlowRelayLeaf<'info> {
     #[account(mut, seeds = [b"instruction_params", signer.key().as_ref()], bump, close = signer)]
     pub instruction_params: Option<Account<'info, ExecuteSlowRelayLeafParams>>,
 
-    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 
     #[account(
@@ -153,10 +194,11 @@ pub struct ExecuteSlowRelayLeaf<'info> {
                 .to_le_bytes()
                 .as_ref(),
         ],
-        bump
+        bump = root_bundle.load()?.bump
     )]
-    pub root_bundle: Account<'info, RootBundle>,
+    pub root_bundle: AccountLoader<'info, RootBundle>,
 
+    // Only used with the Pda fill status backend.
     #[account(
         mut,
         seeds = [b"fills", relay_hash.as_ref()],
@@ -170,7 +212,7 @@ pub struct ExecuteSlowRelayLeaf<'info> {
                 .relay_data,
             &state) @ SvmError::InvalidRelayHash
     )]
-    pub fill_status: Account<'info, FillStatusAccount>,
+    pub fill_status: Option<Account<'info, FillStatusAccount>>,
 
     #[account(
         mint::token_program = token_program,
@@ -182,17 +224,10 @@ pub struct ExecuteSlowRelayLeaf<'info> {
     )]
     pub mint: InterfaceAccount<'info, Mint>,
 
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = slow_fill_leaf
-            .clone()
-            .unwrap_or_else(|| instruction_params.as_ref().unwrap().slow_fill_leaf.clone())
-            .relay_data
-            .recipient,
-        associated_token::token_program = token_program
-    )]
-    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Recipient's ATA for the output token. It is created if needed and validated within the instruction to
+    /// ensure tokens go to the ATA owned by the recipient.
+    #[account(mut)]
+    pub recipient_token_account: UncheckedAccount<'info>,
 
     #[account(
         mut,
@@ -204,6 +239,59 @@ pub struct ExecuteSlowRelayLeaf<'info> {
 
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+
+    // Only used with the Bucketed fill status backend.
+    #[account(
+        mut,
+        seeds = [b"fill_status_bucket", state.seed.to_le_bytes().as_ref(), [relay_hash[0]].as_ref()],
+        bump = fill_status_bucket.load()?.bump,
+        constraint = is_relay_hash_valid(
+            &relay_hash,
+            &slow_fill_leaf
+                .clone()
+                .unwrap_or_else(|| instruction_params.as_ref().unwrap().slow_fill_leaf.clone())
+                .relay_data,
+            &state) @ SvmError::InvalidRelayHash
+    )]
+    pub fill_status_bucket: Option<AccountLoader<'info, FillStatusBucket>>,
+
+    /// CHECK: Fills PDA of the relay, only used with the Bucketed fill status backend. It is read for relays tracked
+    /// before switching backends and only created when the fill_status_bucket is full.
+    #[account(mut, seeds = [b"fills", relay_hash.as_ref()], bump)]
+    pub fill_status_overflow: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Only required when the recipient_token_account does not exist yet and has to be created by the payer.
+    #[account(
+        address = slow_fill_leaf
+            .clone()
+            .unwrap_or_else(|| instruction_params.as_ref().unwrap().slow_fill_leaf.clone())
+            .relay_data
+            .recipient
+    )]
+    pub recipient: Option<UncheckedAccount<'info>>,
+
+    // Only used when deferring the message execution, in which case it is stored for execute_relay_message.
+    #[account(
+        init,
+        payer = payer,
+        space = PendingRelayMessage::space(
+            &slow_fill_leaf
+                .clone()
+                .unwrap_or_else(|| instruction_params.as_ref().unwrap().slow_fill_leaf.clone())
+                .relay_data
+                .message
+        ),
+        seeds = [b"pending_relay_message", relay_hash.as_ref()],
+        bump
+    )]
+    pub pending_relay_message: Option<Account<'info, PendingRelayMessage>>,
+
+    // Accounts below are appended so that the positions of the accounts above are unchanged for existing clients. They
+    // are only required when creating the recipient_token_account or the pending_relay_message.
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
 }
 
 pub fn execute_slow_relay_leaf<'info>(
@@ -224,7 +312,7 @@ pub fn execute_slow_relay_leaf<'info>(
         updated_output_amount: slow_fill_leaf.updated_output_amount,
     };
 
-    let root = ctx.accounts.root_bundle.slow_relay_root;
+    let root = ctx.accounts.root_bundle.load()?.slow_relay_root;
     let leaf = slow_fill.to_keccak_hash()?;
     verify_merkle_proof(root, leaf, proof)?;
 
@@ -234,14 +322,41 @@ pub fn execute_slow_relay_leaf<'info>(
     }
 
     // Check if the fill status is not filled
-    let fill_status_account = &mut ctx.accounts.fill_status;
-    if fill_status_account.status == FillStatus::Filled {
+    let mut fill_status = FillStatusTracker::new(
+        &ctx.accounts.state,
+        &mut ctx.accounts.fill_status,
+        &ctx.accounts.fill_status_bucket,
+        &ctx.accounts.fill_status_overflow,
+        ctx.accounts.payer.as_ref().map(|payer| payer.to_account_info()),
+        &ctx.accounts.system_program,
+        &relay_data,
+    )?;
+    if fill_status.status()? == FillStatus::Filled {
         return err!(CommonError::RelayFilled);
     }
 
+    // Executor pays for the recipient ATA if it does not exist yet. The rent is reported in the FilledRelay event.
+    let recipient_token_account_rent = create_associated_token_account_if_needed(
+        ctx.accounts.payer.as_ref().map(|payer| payer.as_ref()),
+        ctx.accounts.recipient_token_account.as_ref(),
+        ctx.accounts.recipient.as_ref().map(|recipient| recipient.as_ref()),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.accounts.associated_token_program.as_ref(),
+        &ctx.accounts.system_program,
+    )
+    .map_err(|e| e.with_account_name("recipient_token_account"))?;
+    check_associated_token_account(
+        ctx.accounts.recipient_token_account.as_ref(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        &relay_data.recipient,
+    )
+    .map_err(|e| e.with_account_name("recipient_token_account"))?;
+
     // Derive the signer seeds for the state
     let state_seed_bytes = ctx.accounts.state.seed.to_le_bytes();
-    let seeds = &[b"state", state_seed_bytes.as_ref(), &[ctx.bumps.state]];
+    let seeds = &[b"state", state_seed_bytes.as_ref(), &[ctx.accounts.state.bump]];
     let signer_seeds = &[&seeds[..]];
 
     // Invoke the transfer_checked instruction on the token program
@@ -256,10 +371,29 @@ pub fn execute_slow_relay_leaf<'info>(
     transfer_checked(cpi_context, slow_fill_leaf.updated_output_amount, ctx.accounts.mint.decimals)?;
 
     // Update the fill status. We don't set the relayer and fill deadline as it is set when the slow fill was requested.
-    fill_status_account.status = FillStatus::Filled;
+    fill_status.set_status(FillStatus::Filled, None, relay_data.fill_deadline, current_time)?;
 
     if !relay_data.message.is_empty() {
-        invoke_handler(ctx.accounts.signer.as_ref(), ctx.remaining_accounts, &relay_data.message)?;
+        // The payer, when provided, funds the message value and the pending_relay_message rent instead of the signer.
+        let relayer = ctx
+            .accounts
+            .payer
+            .as_ref()
+            .map_or(ctx.accounts.signer.as_ref(), |payer| payer.as_ref());
+        let deferred_relay_message = invoke_or_defer_handler(
+            relayer,
+            ctx.remaining_accounts,
+            get_relay_hash(&relay_data, ctx.accounts.state.chain_id),
+            &relay_data.message,
+            &mut ctx.accounts.pending_relay_message,
+            ctx.bumps.pending_relay_message,
+            current_time,
+        )?;
+        if let Some(deferred_relay_message) = deferred_relay_message {
+            emit_cpi!(deferred_relay_message);
+        }
+    } else if ctx.accounts.pending_relay_message.is_some() {
+        return err!(SvmError::UnexpectedPendingRelayMessage);
     }
 
     // Empty message is not hashed and emits zeroed bytes32 for easier human observability.
@@ -286,6 +420,7 @@ pub fn execute_slow_relay_leaf<'info>(
             updated_output_amount: slow_fill_leaf.updated_output_amount,
             fill_type: FillType::SlowFill,
         },
+        recipient_token_account_rent,
     });
 
     Ok(())
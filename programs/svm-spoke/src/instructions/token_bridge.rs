@@ -22,7 +22,7 @@ pub struct BridgeTokensToHubPool<'info> {
     #[account(mut, mint::token_program = token_program)]
     pub mint: InterfaceAccount<'info, Mint>,
 
-    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 
     #[account(mut, seeds = [b"transfer_liability", mint.key().as_ref()], bump)]
@@ -104,7 +104,7 @@ pub fn bridge_tokens_to_hub_pool(ctx: Context<BridgeTokensToHubPool>, amount: u6
         program: ctx.accounts.token_messenger_minter_program.to_account_info(),
     };
     let state_seed_bytes = ctx.accounts.state.seed.to_le_bytes();
-    let state_seeds: &[&[&[u8]]] = &[&[b"state", state_seed_bytes.as_ref(), &[ctx.bumps.state]]];
+    let state_seeds: &[&[&[u8]]] = &[&[b"state", state_seed_bytes.as_ref(), &[ctx.accounts.state.bump]]];
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, state_seeds);
     let params = DepositForBurnParams {
         amount,
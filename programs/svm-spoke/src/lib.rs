@@ -31,6 +31,7 @@ declare_program!(token_messenger_minter);
 pub mod common;
 pub mod constants;
 mod constraints;
+pub mod cpi_helpers;
 pub mod error;
 pub mod event;
 mod instructions;
@@ -111,6 +112,23 @@ pub mod svm_spoke {
         instructions::pause_fills(ctx, pause)
     }
 
+    /// Sets where fill statuses are tracked for fills and slow fills. Only callable by the owner.
+    ///
+    /// The Bucketed backend also reads fills PDAs, so switching from Pda to Bucketed keeps tracking relays filled
+    /// before the switch. Switching from Bucketed back to Pda is rejected, as the Pda backend does not read buckets.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that must be the owner to authorize the change.
+    /// - state (Writable): The Spoke state PDA. Seed: ["state",state.seed], where `seed` is 0 on mainnet.
+    ///
+    /// ### Parameters:
+    /// - backend: `Pda` to track each relay in its own fills PDA, `Bucketed` to track relays in shared
+    ///   fill_status_bucket accounts that do not require per-fill rent. Relays that do not fit in a full bucket are
+    ///   tracked in their fills PDA paid by the filler instead.
+    pub fn set_fill_status_backend(ctx: Context<SetFillStatusBackendState>, backend: FillStatusBackend) -> Result<()> {
+        instructions::set_fill_status_backend(ctx, backend)
+    }
+
     /// Transfers ownership of the Spoke Pool. Only callable by the current owner.
     ///
     /// ### Required Accounts:
@@ -167,7 +185,11 @@ pub mod svm_spoke {
     ///
     /// Once stored, these roots are used to execute relayer refunds, slow fills, and pool rebalancing actions.
     /// This method initializes a root_bundle PDA to store the root bundle data. The caller
-    /// of this method is responsible for paying the rent for this PDA.
+    /// of this method is responsible for paying the rent for this PDA, including the claimed bitmap that is pre-sized
+    /// from relayer_refund_leaf_capacity so that executors do not pay rent when executing relayer refund leaves within
+    /// it. Leaves with IDs beyond this capacity cannot be executed, so it must cover all the leaves. The latest fill
+    /// deadline the slow relay leaves could have is recorded from the fill deadline buffer, so that the root bundle can
+    /// be closed with close_root_bundle once it has passed and all the relayer refund leaves have been executed.
     ///
     /// ### Required Accounts:
     /// - signer (Signer): The account that must be the owner to authorize the addition of the new root bundle.
@@ -180,10 +202,14 @@ pub mod svm_spoke {
     /// ### Parameters:
     /// - relayer_refund_root: Merkle root of the relayer refund tree.
     /// - slow_relay_root: Merkle root of the slow relay tree.
+    /// - _relayer_refund_leaf_capacity: Number of leaf IDs the claimed bitmap is pre-sized for, rounded up to whole
+    ///   bytes. When relayed from the HubPool via CCTP this is set to DEFAULT_RELAYER_REFUND_LEAF_CAPACITY as the EVM
+    ///   message does not carry the leaf count.
     pub fn relay_root_bundle(
         ctx: Context<RelayRootBundle>,
         relayer_refund_root: [u8; 32],
         slow_relay_root: [u8; 32],
+        _relayer_refund_leaf_capacity: u32,
     ) -> Result<()> {
         instructions::relay_root_bundle(ctx, relayer_refund_root, slow_relay_root)
     }
@@ -208,6 +234,107 @@ pub mod svm_spoke {
         instructions::emergency_delete_root_bundle(ctx, root_bundle_id)
     }
 
+    /// Overrides the completion target of a root bundle, enabling close_root_bundle once it is reached. Only callable
+    /// by the owner.
+    ///
+    /// The slow fill deadline is bounded automatically when relaying the root bundle and the relayer refund leaves are
+    /// proven when closing it, so this is only needed when the bounded slow fill deadline does not hold, e.g. if the
+    /// fill deadline buffer on an origin chain exceeds the one on this chain. Once set, close_root_bundle checks the
+    /// executed leaves against the recorded leaf count instead of the passed leaf hashes.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that must be the owner to authorize setting the target.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - root_bundle (Writable): The root bundle PDA. Seed: ["root_bundle",state.seed,root_bundle_id].
+    ///
+    /// ### Parameters:
+    /// - root_bundle_id: Index of the root bundle.
+    /// - relayer_refund_leaf_count: Number of leaves in the relayer refund Merkle tree.
+    /// - slow_fill_deadline: Latest fill deadline among the slow relay leaves, or 0 if there are none.
+    pub fn set_root_bundle_completion_target(
+        ctx: Context<SetRootBundleCompletionTargetState>,
+        root_bundle_id: u32,
+        relayer_refund_leaf_count: u32,
+        slow_fill_deadline: u32,
+    ) -> Result<()> {
+        instructions::set_root_bundle_completion_target(
+            ctx,
+            root_bundle_id,
+            relayer_refund_leaf_count,
+            slow_fill_deadline,
+        )
+    }
+
+    /// Grows a state account created before the canonical bumps and later fields were appended to the State layout.
+    ///
+    /// Legacy state accounts cannot be deserialized by the current program, so this must be called once after upgrading
+    /// the program and before any other instruction using the state. The appended fields default to the legacy behavior
+    /// and the stored bumps are set from their canonical derivation. Permissionless as it only writes derived values.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Writable, Signer): The account that pays the rent for the additional state space.
+    /// - state (Writable): Legacy spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - system_program: The system program required for the rent top up.
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        instructions::migrate_state(ctx)
+    }
+
+    /// Grows a route account created before the canonical bump was appended to the Route layout and stores the bump.
+    /// Permissionless as it only writes derived values.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Writable, Signer): The account that pays the rent for the additional route space.
+    /// - state (Account): Spoke state PDA, already migrated. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - route (Writable): Legacy route PDA. Seed: ["route",origin_token,state.seed,destination_chain_id].
+    /// - system_program: The system program required for the rent top up.
+    ///
+    /// ### Parameters:
+    /// - origin_token: The mint of the route's origin token.
+    /// - destination_chain_id: The route's destination chain ID.
+    pub fn migrate_route(ctx: Context<MigrateRoute>, _origin_token: Pubkey, _destination_chain_id: u64) -> Result<()> {
+        instructions::migrate_route(ctx)
+    }
+
+    /// Grows a claim account created before the canonical bump was appended to the ClaimAccount layout and stores the
+    /// bump. Permissionless as it only writes derived values.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Writable, Signer): The account that pays the rent for the additional claim account space.
+    /// - mint: The mint used to derive the claim account PDA.
+    /// - refund_address: The refund address used to derive the claim account PDA.
+    /// - claim_account (Writable): Legacy claim account PDA. Seed: ["claim_account",mint,refund_address].
+    /// - system_program: The system program required for the rent top up.
+    pub fn migrate_claim_account(ctx: Context<MigrateClaimAccount>) -> Result<()> {
+        instructions::migrate_claim_account(ctx)
+    }
+
+    /// Moves the claimed bitmap of a root bundle relayed before it was stored in zero-copy account data after the
+    /// current RootBundle fields, preserving the roots and claimed leaves. Only callable by the owner.
+    ///
+    /// The hashes of the leaves executed before the migration were not recorded, so the owner records the relayer
+    /// refund leaf count of migrated root bundles, which can then be closed with close_root_bundle without proving
+    /// their leaves. The slow fill deadline is bounded by the fill deadline buffer from the migration time.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that must be the owner to authorize the migration.
+    /// - payer (Writable, Signer): The account that pays the rent for the additional root bundle space and receives the
+    ///   root bundle rent once it is closed.
+    /// - state (Account): Spoke state PDA, already migrated. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - root_bundle (Writable): Legacy root bundle PDA. Seed: ["root_bundle",state.seed,root_bundle_id].
+    /// - system_program: The system program required for the rent top up.
+    ///
+    /// ### Parameters:
+    /// - root_bundle_id: Index of the root bundle to migrate.
+    /// - relayer_refund_leaf_count: Number of leaves in the relayer refund Merkle tree of the root bundle. The claimed
+    ///   bitmap is grown to cover all their leaf IDs so that the leaves not executed yet remain executable.
+    pub fn migrate_root_bundle(
+        ctx: Context<MigrateRootBundle>,
+        _root_bundle_id: u32,
+        relayer_refund_leaf_count: u32,
+    ) -> Result<()> {
+        instructions::migrate_root_bundle(ctx, relayer_refund_leaf_count)
+    }
+
     // **************************************
     //          DEPOSIT FUNCTIONS           *
     // *************************************
@@ -230,7 +357,8 @@ pub mod svm_spoke {
     /// - state (Writable): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
     /// - route (Account): The route PDA for the particular bridged route in question. Validates a route is enabled.
     ///   Seed: ["route",input_token,state.seed,destination_chain_id].
-    /// - depositor_token_account (Writable): The depositor's ATA for the input token.
+    /// - depositor_token_account (Writable): The depositor's token account for the input token. Does not have to be an
+    ///   ATA, so PDA depositors can deposit from program controlled token accounts. See cpi_helpers for CPI usage.
     /// - vault (Writable): Programs ATA for the associated input token. This is where the depositor's assets are sent.
     ///   Authority must be the state.
     /// - mint (Account): The mint account for the input token.
@@ -250,7 +378,8 @@ pub mod svm_spoke {
     ///   deposit route from this spoke pool or this transaction will revert.
     /// - exclusive_relayer: The relayer that will be exclusively allowed to fill this deposit before the exclusivity
     ///   deadline timestamp. This must be a valid, non-zero address if the exclusivity deadline is greater than the
-    ///   current block timestamp.
+    ///   current block timestamp. To allow a set of relayers, pass the Merkle root of the set where each leaf is the
+    ///   keccak256 hash of the relayer address.
     /// - quote_timestamp: The HubPool timestamp that is used to determine the system fee paid by the depositor. This
     ///   must be set to some time between [currentTime - depositQuoteTimeBuffer, currentTime].
     /// - fill_deadline: The deadline for the relayer to fill the deposit. After this destination chain timestamp, the
@@ -290,6 +419,7 @@ pub mod svm_spoke {
             fill_deadline,
             exclusivity_parameter,
             message,
+            None,
         )
     }
 
@@ -322,6 +452,7 @@ pub mod svm_spoke {
             fill_deadline_offset,
             exclusivity_parameter,
             message,
+            None,
         )
     }
 
@@ -362,9 +493,172 @@ pub mod svm_spoke {
             fill_deadline,
             exclusivity_parameter,
             message,
+            None,
+        )
+    }
+
+    /// Equivalent to deposit, but additionally attributes the deposit to an integrator.
+    ///
+    /// This is the equivalent of appending the integrator ID to the deposit calldata on EVM spoke pools. Besides the
+    /// FundsDeposited event, this emits a companion IntegratorDeposited event with the same deposit_id, so that the
+    /// deposit volume can be attributed off-chain. Only integrators registered with register_integrator can be
+    /// attributed.
+    ///
+    /// ### Required Accounts:
+    /// - All the accounts required by deposit().
+    /// - integrator (remaining account): The registered integrator PDA. Seed: ["integrator",state.seed,integrator_id].
+    ///
+    /// ### Parameters:
+    /// - integrator_id: The identifier of the integrator that sourced this deposit.
+    /// - All other parameters and required accounts are the same as in deposit().
+    pub fn deposit_with_integrator(
+        ctx: Context<Deposit>,
+        depositor: Pubkey,
+        recipient: Pubkey,
+        input_token: Pubkey,
+        output_token: Pubkey,
+        input_amount: u64,
+        output_amount: u64,
+        destination_chain_id: u64,
+        exclusive_relayer: Pubkey,
+        quote_timestamp: u32,
+        fill_deadline: u32,
+        exclusivity_parameter: u32,
+        message: Vec<u8>,
+        integrator_id: u16,
+    ) -> Result<()> {
+        instructions::deposit(
+            ctx,
+            depositor,
+            recipient,
+            input_token,
+            output_token,
+            input_amount,
+            output_amount,
+            destination_chain_id,
+            exclusive_relayer,
+            quote_timestamp,
+            fill_deadline,
+            exclusivity_parameter,
+            message,
+            Some(integrator_id),
+        )
+    }
+
+    /// Equivalent to deposit_now, but additionally attributes the deposit to an integrator as in
+    /// deposit_with_integrator.
+    ///
+    /// ### Required Accounts:
+    /// - All the accounts required by deposit().
+    /// - integrator (remaining account): The registered integrator PDA. Seed: ["integrator",state.seed,integrator_id].
+    ///
+    /// ### Parameters:
+    /// - integrator_id: The identifier of the integrator that sourced this deposit.
+    /// - All other parameters are the same as in deposit_now().
+    pub fn deposit_now_with_integrator(
+        ctx: Context<Deposit>,
+        depositor: Pubkey,
+        recipient: Pubkey,
+        input_token: Pubkey,
+        output_token: Pubkey,
+        input_amount: u64,
+        output_amount: u64,
+        destination_chain_id: u64,
+        exclusive_relayer: Pubkey,
+        fill_deadline_offset: u32,
+        exclusivity_parameter: u32,
+        message: Vec<u8>,
+        integrator_id: u16,
+    ) -> Result<()> {
+        instructions::deposit_now(
+            ctx,
+            depositor,
+            recipient,
+            input_token,
+            output_token,
+            input_amount,
+            output_amount,
+            destination_chain_id,
+            exclusive_relayer,
+            fill_deadline_offset,
+            exclusivity_parameter,
+            message,
+            Some(integrator_id),
+        )
+    }
+
+    /// Equivalent to unsafe_deposit, but additionally attributes the deposit to an integrator as in
+    /// deposit_with_integrator.
+    ///
+    /// ### Required Accounts:
+    /// - All the accounts required by deposit().
+    /// - integrator (remaining account): The registered integrator PDA. Seed: ["integrator",state.seed,integrator_id].
+    ///
+    /// ### Parameters:
+    /// - integrator_id: The identifier of the integrator that sourced this deposit.
+    /// - All other parameters are the same as in unsafe_deposit().
+    pub fn unsafe_deposit_with_integrator(
+        ctx: Context<Deposit>,
+        depositor: Pubkey,
+        recipient: Pubkey,
+        input_token: Pubkey,
+        output_token: Pubkey,
+        input_amount: u64,
+        output_amount: u64,
+        destination_chain_id: u64,
+        exclusive_relayer: Pubkey,
+        deposit_nonce: u64,
+        quote_timestamp: u32,
+        fill_deadline: u32,
+        exclusivity_parameter: u32,
+        message: Vec<u8>,
+        integrator_id: u16,
+    ) -> Result<()> {
+        instructions::unsafe_deposit(
+            ctx,
+            depositor,
+            recipient,
+            input_token,
+            output_token,
+            input_amount,
+            output_amount,
+            destination_chain_id,
+            exclusive_relayer,
+            deposit_nonce,
+            quote_timestamp,
+            fill_deadline,
+            exclusivity_parameter,
+            message,
+            Some(integrator_id),
         )
     }
 
+    /// Deposits tokens for a same chain intent, where the deposit is filled on this chain rather than bridged.
+    ///
+    /// Equivalent to unsafe_deposit with destination_chain_id set to this chain, but additionally records the relay
+    /// hash in a same_chain_deposit escrow record. Relayers fill such deposits with fill_same_chain_relay, which releases
+    /// the escrowed input tokens from the vault to the relayer atomically instead of refunding them in a bundle. If the
+    /// deposit is not filled before its fill_deadline, the depositor is refunded in a bundle as any other expired
+    /// deposit and the escrow record can be closed with close_same_chain_deposit.
+    ///
+    /// ### Required Accounts:
+    /// - deposit: All the accounts required by deposit().
+    /// - same_chain_deposit (Writable): The escrow record PDA, created on this call and paid by the signer.
+    ///   Seed: ["same_chain_deposit",state.seed,deposit_id] where deposit_id is computed as in unsafe_deposit.
+    /// - system_program (Program): The system program.
+    ///
+    /// ### Parameters:
+    /// - params: DepositSameChainParams struct containing:
+    ///     - destination_chain_id: Must be the chain_id of this chain.
+    ///     - deposit_nonce: The nonce used to derive the deterministic deposit ID as in unsafe_deposit.
+    ///     - All other fields are the same as the parameters of deposit().
+    pub fn deposit_same_chain<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositSameChain<'info>>,
+        params: DepositSameChainParams,
+    ) -> Result<()> {
+        instructions::deposit_same_chain(ctx, params)
+    }
+
     /// Computes the deposit ID for the depositor using the provided deposit_nonce. This acts like a "view" function for
     /// off-chain actors to compute what the expected deposit ID is for a given depositor and deposit nonce will be.
     ///
@@ -381,6 +675,52 @@ pub mod svm_spoke {
         Ok(utils::get_unsafe_deposit_id(signer, depositor, deposit_nonce))
     }
 
+    /// Registers an integrator in the integrator registry. The signer becomes the integrator authority.
+    ///
+    /// Integrator IDs are claimed on a first come basis and the registered metadata is used to attribute deposits
+    /// tagged via deposit_with_integrator and its variants. The caller pays rent for the integrator PDA.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that pays for the integrator PDA and becomes its authority.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - integrator (Writable): The newly created integrator PDA. Seed: ["integrator",state.seed,integrator_id].
+    /// - system_program (Program): The system program required for account creation.
+    ///
+    /// ### Parameters:
+    /// - integrator_id: The identifier of the integrator to register.
+    /// - name: Human readable name of the integrator. Max 32 bytes.
+    /// - metadata_uri: URI pointing to off-chain integrator metadata. Max 200 bytes.
+    pub fn register_integrator(
+        ctx: Context<RegisterIntegrator>,
+        integrator_id: u16,
+        name: String,
+        metadata_uri: String,
+    ) -> Result<()> {
+        instructions::register_integrator(ctx, integrator_id, name, metadata_uri)
+    }
+
+    /// Updates the metadata of a registered integrator. Only callable by the integrator authority.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that must be the integrator authority.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - integrator (Writable): The integrator PDA to update. Seed: ["integrator",state.seed,integrator_id].
+    ///
+    /// ### Parameters:
+    /// - integrator_id: The identifier of the registered integrator.
+    /// - new_authority: The authority of the integrator after the update. Pass the current one to keep it.
+    /// - name: Human readable name of the integrator. Max 32 bytes.
+    /// - metadata_uri: URI pointing to off-chain integrator metadata. Max 200 bytes.
+    pub fn update_integrator(
+        ctx: Context<UpdateIntegrator>,
+        integrator_id: u16,
+        new_authority: Pubkey,
+        name: String,
+        metadata_uri: String,
+    ) -> Result<()> {
+        instructions::update_integrator(ctx, integrator_id, new_authority, name, metadata_uri)
+    }
+
     // **************************************
     //          RELAYER FUNCTIONS           *
     // *************************************
@@ -405,12 +745,26 @@ pub mod svm_spoke {
     /// - vault (Writable): The ATA for refunded mint. Authority must be the state.
     /// - mint (Account): The mint of the output token, sent from the relayer to the recipient.
     /// - relayer_token_account (Writable): The relayer's ATA for the input token.
-    /// - recipient_token_account (Writable): The recipient's ATA for the output token.
+    /// - recipient_token_account (Writable): The recipient's ATA for the output token. Created and paid by the signer
+    ///   if it does not exist yet, in which case the rent is reported in the FilledRelay event.
     /// - fill_status (Writable): The fill status PDA, created on this function call to track the fill status to prevent
-    ///   re-entrancy & double fills. Also used to track requested slow fills. Seed: ["fills",relay_hash].
+    ///   re-entrancy & double fills. Also used to track requested slow fills. Seed: ["fills",relay_hash]. Only used
+    ///   with the Pda fill status backend, otherwise pass this program ID to represent None.
     /// - token_program (Interface): The token program.
     /// - associated_token_program (Interface): The associated token program.
     /// - system_program (Interface): The system program.
+    /// - fill_status_bucket (Writable): The fill status bucket tracking this relay. Only used with the Bucketed fill
+    ///   status backend, otherwise pass this program ID to represent None.
+    ///   Seed: ["fill_status_bucket",state.seed,relay_hash[0]].
+    /// - fill_status_overflow (Writable): The fills PDA of the relay, only created by the signer when the bucket is
+    ///   full. Only used with the Bucketed fill status backend, otherwise pass this program ID to represent None.
+    ///   Seed: ["fills",relay_hash].
+    /// - recipient (Account): The recipient of the relay. Only required when the recipient_token_account has to be
+    ///   created, otherwise pass this program ID to represent None.
+    /// - pending_relay_message (Writable): Created to store the message for execute_relay_message instead of invoking
+    ///   the message handler within this call, emitting DeferredRelayMessage. Required when the message allows handler
+    ///   failure, optional when the message allows deferral, otherwise pass this program ID to represent None.
+    ///   Seed: ["pending_relay_message",relay_hash].
     ///
     /// ### Parameters:
     /// - _relay_hash: The hash identifying the deposit to be filled. Caller must pass this in. Computed as hash of
@@ -424,9 +778,10 @@ pub mod svm_spoke {
     ///   - output_token: The token that the caller will send to the recipient on this chain.
     ///   - input_amount: This amount, less a system fee, will be sent to the caller on their repayment chain.
     ///   - output_amount: The amount of output tokens that the caller will send to the recipient.
-    ///   - origin_chain_id: The origin chain identifier.
+    ///   - origin_chain_id: The origin chain identifier. Relays originating from this chain must be filled with
+    ///     fill_same_chain_relay so that the escrowed deposit input is released to the caller.
     ///   - exclusive_relayer: The relayer that will be exclusively allowed to fill this deposit before the
-    ///     exclusivity deadline timestamp.
+    ///     exclusivity deadline timestamp. Can also be the Merkle root of an exclusive relayer set.
     ///   - fill_deadline: The deadline for the caller to fill the deposit. After this timestamp, the deposit will be
     ///     cancelled and the depositor will be refunded on the origin chain.
     ///   - exclusivity_deadline: The deadline for the exclusive relayer to fill the deposit. After this timestamp,
@@ -436,16 +791,66 @@ pub mod svm_spoke {
     /// - repayment_chain_id: Chain of SpokePool where relayer wants to be refunded after the challenge window has
     ///   passed. Will receive input_amount of the equivalent token to input_token on the repayment chain.
     /// - repayment_address: The address of the recipient on the repayment chain that they want to be refunded to.
+    /// - exclusivity_proof: Merkle proof that the signer is a member of the exclusive relayer set committed in
+    ///   exclusive_relayer. Only evaluated within the exclusivity window when the signer is not the exclusive_relayer
+    ///   itself. Leaves are the keccak256 hash of the relayer address. Pass None when not filling as a set member.
     /// Note: relay_data, repayment_chain_id, and repayment_address are optional parameters. If None for any of these
-    /// is passed, the caller must load them via the instruction_params account.
+    /// is passed, the caller must load them via the instruction_params account. exclusivity_proof is never loaded
+    /// from the instruction_params account.
     pub fn fill_relay<'info>(
         ctx: Context<'_, '_, '_, 'info, FillRelay<'info>>,
         _relay_hash: [u8; 32],
         relay_data: Option<RelayData>,
         repayment_chain_id: Option<u64>,
         repayment_address: Option<Pubkey>,
+        exclusivity_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        instructions::fill_relay(ctx, relay_data, repayment_chain_id, repayment_address, exclusivity_proof)
+    }
+
+    /// Executes an Across+ message that was stored when filling a relay with deferred message execution.
+    ///
+    /// Relayers can defer the message execution by passing the pending_relay_message account to fill_relay or
+    /// execute_slow_relay_leaf when the depositor allowed deferral in the message, so that the fill is not limited by
+    /// the account set of the message handler. This is required for messages that allow handler failure, so that a
+    /// failing message handler cannot block the fill and the tokens are still delivered to the recipient. Anyone can
+    /// execute the stored message until it is closed, which close_pending_relay_message only allows once
+    /// PENDING_RELAY_MESSAGE_EXECUTION_WINDOW has passed since the fill. The pending relay message is closed on
+    /// execution and its rent is returned to the relayer.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that authorizes the execution. No permission requirements. Funds the value_amount
+    ///   of the message.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - pending_relay_message (Writable): The stored message to execute. Seed: ["pending_relay_message",relay_hash].
+    /// - relayer (Writable): The relayer recorded in the pending_relay_message that receives the rent.
+    /// - remaining_accounts: The message handler followed by the accounts in the message, same as for fill_relay.
+    ///
+    /// ### Parameters:
+    /// - relay_hash: The hash identifying the filled relay.
+    pub fn execute_relay_message<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteRelayMessage<'info>>,
+        relay_hash: [u8; 32],
     ) -> Result<()> {
-        instructions::fill_relay(ctx, relay_data, repayment_chain_id, repayment_address)
+        instructions::execute_relay_message(ctx, relay_hash)
+    }
+
+    /// Closes a pending relay message that was not executed within its execution window, emitting ExpiredRelayMessage.
+    ///
+    /// The recipient has already received the tokens when the relay was filled, so this only returns the rent of the
+    /// pending_relay_message to the relayer. Callable by anyone once PENDING_RELAY_MESSAGE_EXECUTION_WINDOW has passed
+    /// since the fill, regardless of the fill deadline, so that executors always have time to execute the message.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that authorizes the closure. No permission requirements.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - pending_relay_message (Writable): The stored message to close. Seed: ["pending_relay_message",relay_hash].
+    /// - relayer (Writable): The relayer recorded in the pending_relay_message that receives the rent.
+    ///
+    /// ### Parameters:
+    /// - relay_hash: The hash identifying the filled relay.
+    pub fn close_pending_relay_message(ctx: Context<ClosePendingRelayMessage>, relay_hash: [u8; 32]) -> Result<()> {
+        instructions::close_pending_relay_message(ctx, relay_hash)
     }
 
     /// Closes the FillStatusAccount PDA to reclaim relayer rent.
@@ -458,10 +863,186 @@ pub mod svm_spoke {
     /// - signer (Signer): The account that authorizes the closure. Must be the relayer in the fill_status PDA.
     /// - state (Writable): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
     /// - fill_status (Writable): The FillStatusAccount PDA to be closed.
+    /// - fill_tombstones (Writable): The fill tombstones shard recording the closed fill_status, grown by one tombstone
+    ///   paid from the closed fill_status rent. Required once initialized, otherwise pass this program ID to represent
+    ///   None. Seed: ["fill_tombstones",state.seed,fill_status[0]], where fill_status[0] is the first address byte.
     pub fn close_fill_pda(ctx: Context<CloseFillPda>) -> Result<()> {
         instructions::close_fill_pda(ctx)
     }
 
+    /// Closes multiple expired FillStatusAccount PDAs, returning the rent to their recorded relayers.
+    ///
+    /// Unlike close_fill_pda, this can be called by anyone, so rent is not stranded when relayers rotate their keys.
+    /// Fill PDAs whose fill deadline has not passed yet are skipped instead of failing the whole batch.
+    ///
+    /// ### Required Accounts:
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - remaining_accounts: Tuples of the fill_status PDA to close (Writable) and the relayer recorded in it
+    ///   (Writable) that receives the rent. Once fill tombstones are initialized, each tuple must also include the
+    ///   fill tombstones shard of the fill_status PDA (Writable), grown by one tombstone paid from the closed
+    ///   fill_status rent. Seed: ["fill_tombstones",state.seed,fill_status[0]].
+    pub fn close_fill_pdas<'c, 'info>(ctx: Context<'_, '_, 'c, 'info, CloseFillPdas<'info>>) -> Result<()>
+    where
+        'c: 'info,
+    {
+        instructions::close_fill_pdas(ctx)
+    }
+
+    /// Initializes a fill status bucket used by the Bucketed fill status backend.
+    ///
+    /// Each bucket tracks the fill status of relays whose relay hash starts with bucket_index, so up to 256 buckets
+    /// can be created per state. Buckets are shared by all relayers and are never closed: entries of relays past their
+    /// fill deadline are reused for new relays, so fills do not require per-fill rent unless the bucket is full, in
+    /// which case the relay is tracked in its fills PDA instead. Callable by anyone.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account paying for the bucket rent.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - fill_status_bucket (Writable): The bucket PDA to initialize. Seed: ["fill_status_bucket",state.seed,
+    ///   bucket_index].
+    /// - system_program (Program): The system program.
+    ///
+    /// ### Parameters:
+    /// - _bucket_index: The first byte of the relay hashes tracked by this bucket.
+    pub fn initialize_fill_status_bucket(ctx: Context<InitializeFillStatusBucket>, _bucket_index: u8) -> Result<()> {
+        instructions::initialize_fill_status_bucket(ctx)
+    }
+
+    /// Returns the fill status of a relay tracked by the Bucketed fill status backend.
+    ///
+    /// Relays past their fill deadline may have been evicted from the bucket, in which case Unfilled is returned.
+    ///
+    /// ### Required Accounts:
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - fill_status_bucket (Account): The bucket tracking the relay. Seed: ["fill_status_bucket",state.seed,
+    ///   relay_hash[0]], where relay_hash is computed from relay_data & chain_id.
+    /// - fill_status (Account): The fills PDA of the relay, read when the relay overflowed the bucket or was tracked
+    ///   before switching to the Bucketed backend. Seed: ["fills",relay_hash].
+    ///
+    /// ### Parameters:
+    /// - relay_data: Struct containing all the data needed to identify the relay. See fill_relay for more details.
+    pub fn get_bucketed_fill_status(ctx: Context<GetBucketedFillStatus>, relay_data: RelayData) -> Result<FillStatus> {
+        instructions::get_bucketed_fill_status(ctx, relay_data)
+    }
+
+    /// Initializes a fill tombstones shard recording closed fill_status PDAs. Only callable by the owner.
+    ///
+    /// Once initialized, close_fill_pda and close_fill_pdas must record a tombstone for each closed fill_status PDA so
+    /// that get_fill_status can tell closed PDAs apart from relays that were never filled. Tombstones are sharded by
+    /// the first byte of the fill_status PDA address and are never overwritten, so the owner should initialize all 256
+    /// shards together, as fill_status PDAs of uninitialized shards cannot be closed.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that must be the owner to authorize the initialization and pays for the rent.
+    /// - state (Writable): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - fill_tombstones (Writable): The fill tombstones shard to initialize.
+    ///   Seed: ["fill_tombstones",state.seed,shard_index].
+    /// - system_program (Program): The system program.
+    ///
+    /// ### Parameters:
+    /// - _shard_index: The first byte of the fill_status PDA addresses whose tombstones are recorded in this shard.
+    pub fn initialize_fill_tombstones(ctx: Context<InitializeFillTombstones>, _shard_index: u8) -> Result<()> {
+        instructions::initialize_fill_tombstones(ctx)
+    }
+
+    /// Returns the fill state of a relay tracked by the fill status backend set in the state.
+    ///
+    /// This acts like a "view" function for off-chain actors so that they don't need to derive and decode the
+    /// fill_status PDA or bucket themselves. Relays with closed fill_status PDAs are reported as closed together with
+    /// the status, relayer and fill deadline recorded in their tombstone. Relayers are not recorded in buckets, so they
+    /// are reported as default for relays tracked in a bucket.
+    ///
+    /// ### Required Accounts:
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - fill_status (Account): The fill status PDA of the relay. Seed: ["fills",relay_hash], where relay_hash is
+    ///   computed from relay_data & chain_id.
+    /// - fill_tombstones (Account): The fill tombstones shard of the fill_status PDA if it is initialized, otherwise
+    ///   pass this program ID to represent None. Seed: ["fill_tombstones",state.seed,fill_status[0]].
+    /// - fill_status_bucket (Account): The bucket tracking the relay. Only used with the Bucketed fill status backend,
+    ///   otherwise pass this program ID to represent None. Seed: ["fill_status_bucket",state.seed,relay_hash[0]].
+    ///
+    /// ### Parameters:
+    /// - relay_data: Struct containing all the data needed to identify the relay. See fill_relay for more details.
+    pub fn get_fill_status(ctx: Context<GetFillStatus>, relay_data: RelayData) -> Result<FillStatusView> {
+        instructions::get_fill_status(ctx, relay_data)
+    }
+
+    /// Fills a relay funding the output amount from the relayer refunds accrued to the signer's claim account.
+    ///
+    /// Performs the same checks as fill_relay, but instead of pulling output_amount from the relayer token account it
+    /// debits the signer's claim_account for the output token and transfers output_amount from the vault to the
+    /// recipient. This saves the relayer claiming the refund to a token account before filling. The debit is emitted
+    /// as a ClaimedRelayerRefund event next to FilledRelay.
+    ///
+    /// ### Required Accounts:
+    /// - fill: All the accounts required by fill_relay(). The relayer_token_account is not debited.
+    /// - vault (Writable): The state ATA of the output token, funding the fill.
+    /// - claim_account (Writable): The signer's claim account for the output token. Must hold at least output_amount.
+    ///   Seed: ["claim_account",mint,signer].
+    ///
+    /// ### Parameters:
+    /// - All parameters are the same as in fill_relay().
+    pub fn fill_relay_from_claim_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, FillRelayFromClaimAccount<'info>>,
+        _relay_hash: [u8; 32],
+        relay_data: Option<RelayData>,
+        repayment_chain_id: Option<u64>,
+        repayment_address: Option<Pubkey>,
+        exclusivity_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        instructions::fill_relay_from_claim_account(
+            ctx,
+            relay_data,
+            repayment_chain_id,
+            repayment_address,
+            exclusivity_proof,
+        )
+    }
+
+    /// Fills a same chain deposit made with deposit_same_chain and settles it from the deposit escrow.
+    ///
+    /// Performs the same checks and transfers as fill_relay and additionally releases the deposit input_amount from
+    /// the vault to the relayer within the same instruction, closing the same_chain_deposit escrow record. Emits a
+    /// SettledSameChainRelay event next to FilledRelay so that the fill is not also refunded in a bundle. Such fills
+    /// settle intra-chain token swaps via relayers without waiting for the bundle round trip.
+    ///
+    /// ### Required Accounts:
+    /// - fill: All the accounts required by fill_relay().
+    /// - same_chain_deposit (Writable): The escrow record created on deposit. Its relay hash must match relay_hash.
+    /// - deposit_payer (Writable): The account that paid for the escrow record, receives its rent back.
+    /// - input_mint (Account): The mint of the deposit input token.
+    /// - vault (Writable): The state ATA of the input token holding the escrowed deposit.
+    /// - relayer_input_token_account (Writable): The relayer's token account receiving the deposit input tokens.
+    /// - input_token_program (Interface): The token program of the input token.
+    ///
+    /// ### Parameters:
+    /// - All parameters are the same as in fill_relay(). The repayment_chain_id and repayment_address are only
+    ///   emitted in the FilledRelay event as the relayer is repaid from the escrow on this chain.
+    pub fn fill_same_chain_relay<'info>(
+        ctx: Context<'_, '_, '_, 'info, FillSameChainRelay<'info>>,
+        _relay_hash: [u8; 32],
+        relay_data: Option<RelayData>,
+        repayment_chain_id: Option<u64>,
+        repayment_address: Option<Pubkey>,
+        exclusivity_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        instructions::fill_same_chain_relay(ctx, relay_data, repayment_chain_id, repayment_address, exclusivity_proof)
+    }
+
+    /// Closes the escrow record of an unfilled same chain deposit to reclaim rent.
+    ///
+    /// Can be called by anyone once the deposit fill deadline has passed, as the deposit can no longer be filled and
+    /// its input is refunded to the depositor in a bundle. The rent is returned to the account that paid for it.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): Any account authorizing the closure.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - same_chain_deposit (Writable): The escrow record to be closed.
+    /// - deposit_payer (Writable): The account that paid for the escrow record, receives its rent back.
+    pub fn close_same_chain_deposit(ctx: Context<CloseSameChainDeposit>) -> Result<()> {
+        instructions::close_same_chain_deposit(ctx)
+    }
+
     /// Claims a relayer refund for the caller.
     ///
     /// In the event a relayer refund was sent to a claim account, then this function enables the relayer to claim it by
@@ -543,7 +1124,7 @@ pub mod svm_spoke {
     where
         'c: 'info,
     {
-        instructions::execute_relayer_refund_leaf(ctx, false)
+        instructions::execute_relayer_refund_leaf(ctx, RefundMode::Direct)
     }
 
     /// Similar to execute_relayer_refund_leaf, but executes in mode where refunds are allocated to claim_account PDAs.
@@ -553,7 +1134,82 @@ pub mod svm_spoke {
     where
         'c: 'info,
     {
-        instructions::execute_relayer_refund_leaf(ctx, true)
+        instructions::execute_relayer_refund_leaf(ctx, RefundMode::Deferred)
+    }
+
+    /// Similar to execute_relayer_refund_leaf, but falls back to allocating the refund to the claim_account PDA for
+    /// each relayer whose ATA is missing, closed or frozen. The refunded ATAs are passed in remaining accounts as in
+    /// execute_relayer_refund_leaf, followed by the claim_account PDAs of the relayers whose refunds are deferred, in
+    /// the order of refunds. The deferred relayers are recorded in the ExecutedRelayerRefundRoot event.
+    pub fn execute_relayer_refund_leaf_hybrid<'c, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeaf<'info>>,
+    ) -> Result<()>
+    where
+        'c: 'info,
+    {
+        instructions::execute_relayer_refund_leaf(ctx, RefundMode::Hybrid)
+    }
+
+    /// Executes a relayer refund leaf in chunks, paying out as many refunds as there are remaining accounts.
+    ///
+    /// Similar to execute_relayer_refund_leaf, but allows leaves with more refund_addresses than fit in a single
+    /// transaction to be executed across multiple transactions. The first chunk verifies the leaf, marks it as claimed
+    /// in the root bundle and books any amount_to_return. Progress is tracked in the leaf_progress PDA and each
+    /// subsequent chunk pays out the next slice of refunds of the same leaf until it completes, at which point the
+    /// leaf_progress account is closed and ExecutedRelayerRefundRoot is emitted. instruction_params must be loaded
+    /// with the same leaf and proof for every chunk.
+    ///
+    /// ### Required Accounts:
+    /// - execute: All accounts required by execute_relayer_refund_leaf.
+    /// - leaf_progress (Writable): Tracks the next refund to be paid out. Initialized by the signer of the first chunk
+    ///   and closed on completion. Seed: ["refund_leaf_progress",state.seed,root_bundle_id,leaf_id].
+    /// - leaf_progress_payer (Writable): Signer of the first chunk that receives the leaf_progress rent on completion.
+    /// - system_program: The system program required for account creation.
+    ///
+    /// Remaining accounts hold the refund token accounts of the next refunds in the leaf, in order.
+    pub fn execute_relayer_refund_leaf_chunk<'c, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeafChunk<'info>>,
+    ) -> Result<()>
+    where
+        'c: 'info,
+    {
+        instructions::execute_relayer_refund_leaf_chunk(ctx, false)
+    }
+
+    /// Similar to execute_relayer_refund_leaf_chunk, but the refunds in the chunk are allocated to claim_account PDAs.
+    pub fn execute_relayer_refund_leaf_chunk_deferred<'c, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ExecuteRelayerRefundLeafChunk<'info>>,
+    ) -> Result<()>
+    where
+        'c: 'info,
+    {
+        instructions::execute_relayer_refund_leaf_chunk(ctx, true)
+    }
+
+    /// Closes a fully executed root bundle, returning its rent to the original payer. No permission requirements.
+    ///
+    /// The root bundle can be closed once every relayer refund leaf has been executed and the slow fill deadline has
+    /// passed, so that no slow fill leaf can be executed anymore. As the leaf count is not part of the roots relayed
+    /// from the HubPool, the caller passes the hashes of all the relayer refund leaves: they must rebuild the relayer
+    /// refund root and match the hashes accumulated when executing the leaves. The slow fill deadline is recorded when
+    /// relaying the root bundle as the latest fill deadline its slow relay leaves could have.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that authorizes the closure.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - root_bundle (Writable): The root bundle PDA to be closed. Seed: ["root_bundle",state.seed,root_bundle_id].
+    /// - payer (Writable): The account that paid for relaying the root bundle and receives the rent back.
+    ///
+    /// ### Parameters:
+    /// - root_bundle_id: Index of the root bundle to be closed.
+    /// - relayer_refund_leaf_hashes: Hashes of all the leaves in the relayer refund Merkle tree, in any order. Ignored
+    ///   if the owner has recorded the leaf count with set_root_bundle_completion_target or on migration.
+    pub fn close_root_bundle(
+        ctx: Context<CloseRootBundle>,
+        root_bundle_id: u32,
+        relayer_refund_leaf_hashes: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::close_root_bundle(ctx, root_bundle_id, relayer_refund_leaf_hashes)
     }
 
     /// Bridges tokens to the Hub Pool.
@@ -657,6 +1313,32 @@ pub mod svm_spoke {
         instructions::initialize_claim_account(ctx)
     }
 
+    /// Claims relayer refunds in multiple tokens for the refund address in a single instruction.
+    ///
+    /// Similar to claim_relayer_refund, but takes the per token accounts in remaining accounts so that relayers repaid
+    /// in many tokens can claim all of them at once. Each claim amount is transferred from the vault to the token
+    /// account and one ClaimedRelayerRefund event is emitted per mint. Unlike claim_relayer_refund, the claim accounts
+    /// are not closed but reset to zero so that they can be reused for deferred refunds in following bundles.
+    ///
+    /// ### Required Accounts:
+    /// - signer (Signer): The account that authorizes the claim.
+    /// - refund_address: token account authority receiving the refunds.
+    /// - state (Account): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
+    /// - token_program (Interface): The token program of all claimed mints.
+    ///
+    /// Remaining accounts must be passed in tuples for each claimed token:
+    /// - mint: The mint account for the token being refunded.
+    /// - vault (Writable): The ATA for the refunded mint. Authority must be the state.
+    /// - claim_account (Writable): The claim account PDA. Seed: ["claim_account",mint,refund_address].
+    /// - token_account (Writable): The receiving token account for the refund. When refund_address is different from
+    ///   the signer, this must match its ATA.
+    pub fn claim_relayer_refunds<'c, 'info>(ctx: Context<'_, '_, 'c, 'info, ClaimRelayerRefunds<'info>>) -> Result<()>
+    where
+        'c: 'info,
+    {
+        instructions::claim_relayer_refunds(ctx)
+    }
+
     /// Closes a claim account for a relayer refund.
     ///
     /// This function is used to close the claim account associated with a specific mint and refund address,
@@ -692,8 +1374,15 @@ pub mod svm_spoke {
     ///   be derived from the signer's public key with seed ["instruction_params",signer].
     /// - state (Writable): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
     /// - fill_status (Writable): The fill status PDA, created on this function call. Updated to track slow fill status.
-    ///   Used to prevent double request and fill. Seed: ["fills",relay_hash].
+    ///   Used to prevent double request and fill. Seed: ["fills",relay_hash]. Only used with the Pda fill status
+    ///   backend, otherwise pass this program ID to represent None.
     /// - system_program (Interface): The system program.
+    /// - fill_status_bucket (Writable): The fill status bucket tracking this relay. Only used with the Bucketed fill
+    ///   status backend, otherwise pass this program ID to represent None.
+    ///   Seed: ["fill_status_bucket",state.seed,relay_hash[0]].
+    /// - fill_status_overflow (Writable): The fills PDA of the relay, only created by the signer when the bucket is
+    ///   full. Only used with the Bucketed fill status backend, otherwise pass this program ID to represent None.
+    ///   Seed: ["fills",relay_hash].
     ///
     /// ### Parameters:
     /// - _relay_hash: The hash identifying the deposit to be filled. Caller must pass this in. Computed as hash of
@@ -726,12 +1415,31 @@ pub mod svm_spoke {
     /// - state (Writable): Spoke state PDA. Seed: ["state",state.seed] where seed is 0 on mainnet.
     /// - root_bundle (Account): Root bundle PDA with slowRelayRoot. Seed: ["root_bundle",state.seed,root_bundle_id].
     /// - fill_status (Writable): The fill status PDA, created when slow request was made. Updated to track slow fill.
-    ///   Used to prevent double request and fill. Seed: ["fills",relay_hash].
+    ///   Used to prevent double request and fill. Seed: ["fills",relay_hash]. Only used with the Pda fill status
+    ///   backend, otherwise pass this program ID to represent None.
     /// - mint (Account): The mint account for the output token.
-    /// - recipient_token_account (Writable): The recipient's ATA for the output token.
+    /// - recipient_token_account (Writable): The recipient's ATA for the output token. Created and paid by the payer
+    ///   if it does not exist yet, in which case the rent is reported in the FilledRelay event.
     /// - vault (Writable): The ATA for refunded mint. Authority must be the state.
     /// - token_program (Interface): The token program.
     /// - system_program (Program): The system program.
+    /// - fill_status_bucket (Writable): The fill status bucket tracking this relay. Only used with the Bucketed fill
+    ///   status backend, otherwise pass this program ID to represent None.
+    ///   Seed: ["fill_status_bucket",state.seed,relay_hash[0]].
+    /// - fill_status_overflow (Writable): The fills PDA of the relay, only created by the payer when the bucket is
+    ///   full. Only used with the Bucketed fill status backend, otherwise pass this program ID to represent None.
+    ///   Seed: ["fills",relay_hash].
+    /// - recipient (Account): The recipient of the relay. Only required when the recipient_token_account has to be
+    ///   created, otherwise pass this program ID to represent None.
+    /// - pending_relay_message (Writable): Created to store the message for execute_relay_message instead of invoking
+    ///   the message handler within this call, emitting DeferredRelayMessage. Required when the message allows handler
+    ///   failure, optional when the message allows deferral, otherwise pass this program ID to represent None.
+    ///   Seed: ["pending_relay_message",relay_hash].
+    /// - associated_token_program (Program): The associated token program. Only required when the
+    ///   recipient_token_account has to be created, otherwise pass this program ID to represent None.
+    /// - payer (Writable, Signer): Pays for the recipient_token_account and pending_relay_message when they have to be
+    ///   created and funds the message value, otherwise pass this program ID to represent None and the message value
+    ///   is funded by the signer. Appended after the accounts above so that their positions are unchanged.
     ///
     /// ### Parameters:
     /// - _relay_hash: The hash identifying the deposit to be filled. Used to identify the deposit to be filled.
@@ -1,5 +1,9 @@
+use std::{cmp::Ordering, mem::size_of};
+
 use anchor_lang::prelude::*;
 
+use crate::constants::{DISCRIMINATOR_SIZE, FILL_STATUS_BUCKET_CAPACITY};
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq)]
 pub enum FillStatus {
     Unfilled,
@@ -7,6 +11,17 @@ pub enum FillStatus {
     Filled,
 }
 
+impl FillStatus {
+    // Decodes the variant index that zero-copy accounts use to store the FillStatus.
+    pub fn from_index(index: u8) -> Self {
+        match index {
+            1 => FillStatus::RequestedSlowFill,
+            2 => FillStatus::Filled,
+            _ => FillStatus::Unfilled,
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct FillStatusAccount {
@@ -14,3 +29,129 @@ pub struct FillStatusAccount {
     pub relayer: Pubkey,    // Address of the relayer that made the fill to control who can close this PDA.
     pub fill_deadline: u32, // Stores the fill deadline to control when this PDA can be safely closed.
 }
+
+// Selects where fill statuses are tracked. Pda creates a fills PDA per relay that the relayer pays rent for until it is
+// closed, while Bucketed records statuses in shared fill_status_bucket accounts keyed by the first relay hash byte and
+// only falls back to fills PDAs when the bucket is full. Bucketed also reads existing fills PDAs, so the state can
+// switch from Pda to Bucketed, but not back, as the Pda backend does not read buckets.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq)]
+pub enum FillStatusBackend {
+    Pda,
+    Bucketed,
+}
+
+// Shared fill status storage for all relays whose hash starts with the bucket index byte. Entries are only kept until
+// the relay fill deadline passes, as expired relays can no longer be filled, slow fill requested or slow filled. Relays
+// that do not fit in a full bucket overflow to their fills PDA, so a bucket can never block fills.
+#[account(zero_copy(unsafe))]
+#[repr(C)]
+pub struct FillStatusBucket {
+    pub entries: [FillStatusEntry; FILL_STATUS_BUCKET_CAPACITY],
+    pub bump: u8, // Canonical bump of this bucket PDA.
+}
+
+#[zero_copy(unsafe)]
+#[repr(C)]
+pub struct FillStatusEntry {
+    pub relay_hash: [u8; 32], // Hash of the tracked relay, zero when the entry is unused.
+    pub fill_deadline: u32,   // Entry can be reused by another relay once this deadline has passed.
+    pub status: u8,           // FillStatus of the tracked relay encoded as its variant index.
+}
+
+impl FillStatusBucket {
+    pub fn get_status(&self, relay_hash: &[u8; 32]) -> FillStatus {
+        self.entries
+            .iter()
+            .find(|entry| &entry.relay_hash == relay_hash)
+            .map_or(FillStatus::Unfilled, |entry| FillStatus::from_index(entry.status))
+    }
+
+    // Updates the entry tracking the relay or takes over an unused or expired entry if the relay is not tracked yet.
+    // Returns false without updating the bucket when all entries are held by other unexpired relays, in which case the
+    // caller tracks the relay in its fills PDA instead.
+    pub fn set_status(
+        &mut self,
+        relay_hash: &[u8; 32],
+        status: FillStatus,
+        fill_deadline: u32,
+        current_time: u32,
+    ) -> bool {
+        let index = match self.entries.iter().position(|entry| &entry.relay_hash == relay_hash) {
+            Some(index) => index,
+            None => match self
+                .entries
+                .iter()
+                .position(|entry| entry.relay_hash == [0u8; 32] || entry.fill_deadline < current_time)
+            {
+                Some(index) => index,
+                None => return false,
+            },
+        };
+
+        let entry = &mut self.entries[index];
+        entry.relay_hash = *relay_hash;
+        entry.fill_deadline = fill_deadline;
+        entry.status = status as u8;
+
+        true
+    }
+}
+
+// Tombstones of closed fills PDAs so that they can be told apart from relays that were never filled. Tombstones are
+// sharded by the first byte of the fills PDA address, which is derived from the relay hash, and are never overwritten:
+// each shard grows by one tombstone per closed fills PDA. Tombstones are not part of the struct, they occupy the
+// remaining account data sorted by the fills PDA address.
+#[account(zero_copy(unsafe))]
+#[repr(C)]
+pub struct FillTombstones {
+    pub bump: u8, // Canonical bump of this fill_tombstones shard PDA.
+}
+
+// Borsh layout of each tombstone in the fill_tombstones shard account data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct FillTombstone {
+    pub fill_status: Pubkey, // Address of the closed fills PDA.
+    pub relayer: Pubkey,     // Relayer recorded in the closed fills PDA.
+    pub fill_deadline: u32,  // Fill deadline recorded in the closed fills PDA.
+    pub status: FillStatus,  // FillStatus recorded in the closed fills PDA.
+}
+
+impl FillTombstones {
+    // Offset of the sorted tombstones within the account data.
+    pub const TOMBSTONES_OFFSET: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<FillTombstones>();
+
+    // Binary searches the sorted tombstones for the fills PDA. Returns the index of its tombstone if found, otherwise
+    // the index where its tombstone should be inserted to keep the tombstones sorted.
+    pub fn search(tombstones: &[u8], fill_status: &Pubkey) -> std::result::Result<usize, usize> {
+        let (mut low, mut high) = (0, tombstones.len() / FillTombstone::INIT_SPACE);
+        while low < high {
+            let mid = (low + high) / 2;
+            let offset = mid * FillTombstone::INIT_SPACE;
+            match tombstones[offset..offset + size_of::<Pubkey>()].cmp(fill_status.as_ref()) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    pub fn find(tombstones: &[u8], fill_status: &Pubkey) -> Result<Option<FillTombstone>> {
+        match Self::search(tombstones, fill_status) {
+            Ok(index) => {
+                let offset = index * FillTombstone::INIT_SPACE;
+                Ok(Some(FillTombstone::deserialize(&mut &tombstones[offset..offset + FillTombstone::INIT_SPACE])?))
+            }
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+// Fill state of a relay as returned by the get_fill_status view.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FillStatusView {
+    pub status: FillStatus,
+    pub relayer: Pubkey,    // Default if the relay was neither filled nor slow fill requested.
+    pub fill_deadline: u32, // Zero if the relay was neither filled nor slow fill requested.
+    pub closed: bool,       // Set if the fills PDA was closed after the fill deadline as recorded in fill_tombstones.
+}
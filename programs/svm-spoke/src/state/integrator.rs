@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SvmError;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Integrator {
+    pub authority: Pubkey, // Account that registered the integrator and can update its metadata.
+    #[max_len(32)]
+    pub name: String, // Human readable name of the integrator.
+    #[max_len(200)]
+    pub metadata_uri: String, // URI pointing to off-chain integrator metadata.
+}
+
+// This checks that the remaining account passed when tagging a deposit is the registered integrator PDA:
+// #[account(
+//     seeds = [b"integrator", state.seed.to_le_bytes().as_ref(), integrator_id.to_le_bytes().as_ref()],
+//     bump
+// )]
+// pub integrator: Account<'info, Integrator>,
+impl Integrator {
+    pub fn check_registered(account_info: &AccountInfo, state_seed: u64, integrator_id: u16) -> Result<()> {
+        let (pda_address, _) = Pubkey::find_program_address(
+            &[
+                b"integrator",
+                state_seed.to_le_bytes().as_ref(),
+                integrator_id.to_le_bytes().as_ref(),
+            ],
+            &crate::ID,
+        );
+        if account_info.key() != pda_address {
+            return Err(Error::from(SvmError::UnregisteredIntegrator).with_pubkeys((account_info.key(), pda_address)));
+        }
+
+        // The PDA is only owned by this program and holds the Integrator discriminator once registered.
+        if account_info.owner != &crate::ID
+            || Integrator::try_deserialize(&mut &account_info.data.borrow()[..]).is_err()
+        {
+            return err!(SvmError::UnregisteredIntegrator);
+        }
+
+        Ok(())
+    }
+}
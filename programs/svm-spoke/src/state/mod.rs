@@ -1,15 +1,21 @@
 pub mod fill;
 pub mod instruction_params;
+pub mod integrator;
 pub mod refund_account;
+pub mod relay_message;
 pub mod root_bundle;
 pub mod route;
+pub mod same_chain_deposit;
 pub mod state;
 pub mod transfer_liability;
 
 pub use fill::*;
 pub use instruction_params::*;
+pub use integrator::*;
 pub use refund_account::*;
+pub use relay_message::*;
 pub use root_bundle::*;
 pub use route::*;
+pub use same_chain_deposit::*;
 pub use state::*;
 pub use transfer_liability::*;
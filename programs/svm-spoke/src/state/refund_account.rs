@@ -1,3 +1,5 @@
+use std::mem::size_of;
+
 use anchor_lang::prelude::*;
 
 #[account]
@@ -5,13 +7,20 @@ use anchor_lang::prelude::*;
 pub struct ClaimAccount {
     pub amount: u64,         // Amount of tokens to be refunded to the relayer.
     pub initializer: Pubkey, // Address that initialized this PDA
+    pub bump: u8,            // Canonical bump of this claim account PDA.
+}
+
+impl ClaimAccount {
+    // Space of claim accounts created before the bump was appended. Such accounts need to be grown with
+    // migrate_claim_account.
+    pub const LEGACY_SPACE: usize = ClaimAccount::INIT_SPACE - size_of::<u8>();
 }
 
 // This implements the following Anchor account constraints when parsing remaining account as a claim account:
 // #[account(
 //     mut,
 //     seeds = [b"claim_account", mint.key().as_ref(), refund_address.key().as_ref()],
-//     bump
+//     bump = claim_account.bump
 // )]
 // pub claim_account: Account<'info, ClaimAccount>,
 // Note: Account name should be appended to any possible errors by the caller.
@@ -24,9 +33,18 @@ impl<'info> ClaimAccount {
         // Checks ownership on deserialization for the ClaimAccount.
         let claim_account: Account<'info, ClaimAccount> = Account::try_from(account_info)?;
 
-        // Checks the PDA is derived from mint and refund address keys.
-        let (pda_address, _bump) =
-            Pubkey::find_program_address(&[b"claim_account", mint.as_ref(), refund_address.as_ref()], &crate::ID);
+        // Checks the PDA is derived from mint and refund address keys. Uses the stored canonical bump to avoid the
+        // bump search in find_program_address.
+        let pda_address = Pubkey::create_program_address(
+            &[
+                b"claim_account",
+                mint.as_ref(),
+                refund_address.as_ref(),
+                &[claim_account.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| Error::from(ErrorCode::ConstraintSeeds).with_account_name("claim_account"))?;
         if account_info.key() != pda_address {
             return Err(Error::from(ErrorCode::ConstraintSeeds).with_pubkeys((claim_account.key(), pda_address)));
         }
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::DISCRIMINATOR_SIZE;
+
+// Across+ message stored at fill time for execute_relay_message, so that a failing message handler cannot block the
+// fill. Keyed by the relay hash, so its existence proves that the relay was filled with this message.
+#[account]
+#[derive(InitSpace)]
+pub struct PendingRelayMessage {
+    pub relayer: Pubkey,         // Paid the rent and receives it back when the record is closed.
+    pub execution_deadline: u32, // Record can be closed without executing the message once this has passed.
+    pub bump: u8,                // Canonical bump of this pending_relay_message PDA.
+    #[max_len(0)]
+    pub message: Vec<u8>, // Across+ message to be executed, its bytes are added to the account space on creation.
+}
+
+impl PendingRelayMessage {
+    pub fn space(message: &[u8]) -> usize {
+        DISCRIMINATOR_SIZE + Self::INIT_SPACE + message.len()
+    }
+}
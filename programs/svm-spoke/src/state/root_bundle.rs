@@ -1,10 +1,62 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::DISCRIMINATOR_SIZE;
+
+// Zero-copy so that the claimed bitmap can be read and updated without deserializing the whole account. The bitmap is
+// not part of the struct, it occupies the remaining account data that is pre-sized when relaying the root bundle. The
+// struct is packed without padding, so its bytes match the Borsh layout of the fields in the IDL and clients can decode
+// the fields with the generated account coder.
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+pub struct RootBundle {
+    pub relayer_refund_root: [u8; 32],  // Root of the relayer refund merkle tree.
+    pub slow_relay_root: [u8; 32],      // Root of the slow relay merkle tree.
+    pub payer: Pubkey,                  // Payer of the root bundle rent, refunded when the bundle is closed.
+    pub relayer_refund_leaf_count: u32, // Number of leaves in the relayer refund merkle tree, if recorded.
+    pub slow_fill_deadline: u32,        // Latest fill deadline among the slow relay leaves.
+    pub claimed_leaf_count: u32,        // Number of executed relayer refund leaves.
+    pub claimed_leaf_hashes: [u8; 32],  // XOR of the hashes of the executed relayer refund leaves.
+    pub completion_target_set: u8,      // Non-zero once relayer_refund_leaf_count above has been recorded.
+    pub bump: u8,                       // Canonical bump of this root bundle PDA.
+}
+
+impl RootBundle {
+    // Offset of the claimed bitmap within the account data.
+    pub const CLAIMED_BITMAP_OFFSET: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<RootBundle>();
+
+    // Account space required to track the claimed status of relayer refund leaf IDs below relayer_refund_leaf_capacity.
+    pub fn space(relayer_refund_leaf_capacity: u32) -> usize {
+        Self::CLAIMED_BITMAP_OFFSET + (relayer_refund_leaf_capacity as usize).div_ceil(8)
+    }
+
+    // Upper bound of the fill deadlines among the slow relay leaves of a root bundle relayed at current_time. Slow fills
+    // can only be requested for deposits made before the root bundle was relayed, whose fill deadlines are bounded by the
+    // fill deadline buffer on the origin chain. An empty slow relay root has no leaves to wait for.
+    pub fn slow_fill_deadline_bound(slow_relay_root: &[u8; 32], current_time: u32, fill_deadline_buffer: u32) -> u32 {
+        match slow_relay_root == &[0u8; 32] {
+            true => 0,
+            false => current_time.saturating_add(fill_deadline_buffer),
+        }
+    }
+
+    // Counts the executed relayer refund leaf and accumulates its hash, so that the complete set of leaves can later be
+    // proven against the relayer refund root without having recorded the leaf count.
+    pub fn record_claimed_leaf(&mut self, leaf_hash: &[u8; 32]) {
+        self.claimed_leaf_count += 1;
+        self.claimed_leaf_hashes
+            .iter_mut()
+            .zip(leaf_hash)
+            .for_each(|(acc, byte)| *acc ^= byte);
+    }
+
+    // Offset of the claimed bitmap Vec in the legacy Borsh layout that followed the relayer refund and slow relay roots.
+    pub const LEGACY_CLAIMED_BITMAP_OFFSET: usize = DISCRIMINATOR_SIZE + 2 * std::mem::size_of::<[u8; 32]>();
+}
+
 #[account]
 #[derive(InitSpace)]
-pub struct RootBundle {
-    pub relayer_refund_root: [u8; 32], // Root of the relayer refund merkle tree.
-    pub slow_relay_root: [u8; 32],     // Root of the slow relay merkle tree.
-    #[max_len(1)]
-    pub claimed_bitmap: Vec<u8>, // Dynamic sized vec to store claimed status of each relayer refund root leaf.
+pub struct RelayerRefundLeafProgress {
+    pub leaf_hash: [u8; 32], // Hash of the relayer refund leaf being executed in chunks, zero until first chunk.
+    pub next_refund_index: u32, // Index of the next refund to be paid out from the leaf.
+    pub payer: Pubkey,       // Payer of the progress rent, refunded when the leaf completes.
 }
@@ -1,7 +1,15 @@
+use std::mem::size_of;
+
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace)]
 pub struct Route {
     pub enabled: bool, // Tracks if the route is enabled.
+    pub bump: u8,      // Canonical bump of this route PDA.
+}
+
+impl Route {
+    // Space of route accounts created before the bump was appended. Such accounts need to be grown with migrate_route.
+    pub const LEGACY_SPACE: usize = Route::INIT_SPACE - size_of::<u8>();
 }
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+// Escrow record of a deposit whose destination is this chain. Only created by deposit_same_chain, so its existence
+// proves that the input tokens for the relay were deposited in the vault and can be released to the filler.
+#[account]
+#[derive(InitSpace)]
+pub struct SameChainDeposit {
+    pub relay_hash: [u8; 32], // Hash of the relay data, computed with this chain as the destination.
+    pub payer: Pubkey,        // Receives the rent back when the record is closed.
+    pub fill_deadline: u32,   // Record can be closed without a fill once this has passed.
+}
@@ -1,4 +1,8 @@
-pub fn is_claimed(claimed_bitmap: &Vec<u8>, index: u32) -> bool {
+use anchor_lang::prelude::*;
+
+use crate::error::SvmError;
+
+pub fn is_claimed(claimed_bitmap: &[u8], index: u32) -> bool {
     let byte_index = (index / 8) as usize; // Index of the byte in the array
     if byte_index >= claimed_bitmap.len() {
         return false; // Out of bounds, treat as not claimed
@@ -9,12 +13,13 @@ pub fn is_claimed(claimed_bitmap: &Vec<u8>, index: u32) -> bool {
     (claimed_byte & mask) == mask
 }
 
-pub fn set_claimed(claimed_bitmap: &mut Vec<u8>, index: u32) {
+// The bitmap is sized by the caller to cover the index, so indexes beyond its capacity cannot be claimed.
+pub fn set_claimed(claimed_bitmap: &mut [u8], index: u32) -> Result<()> {
     let byte_index = (index / 8) as usize; // Index of the byte in the array
     if byte_index >= claimed_bitmap.len() {
-        let new_size = byte_index + 1;
-        claimed_bitmap.resize(new_size, 0); // Resize the Vec if necessary
+        return err!(SvmError::ClaimedBitmapIndexOutOfRange);
     }
     let bit_in_byte_index = (index % 8) as usize; // Index of the bit within the byte
     claimed_bitmap[byte_index] |= 1 << bit_in_byte_index;
+    Ok(())
 }
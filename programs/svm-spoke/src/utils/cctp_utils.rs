@@ -56,9 +56,10 @@ pub fn decode_solidity_bool(data: &[u8; 32]) -> Result<bool> {
     }
 }
 
-pub fn get_self_authority_pda() -> Pubkey {
-    let (pda_address, _bump) = Pubkey::find_program_address(&[b"self_authority"], &SvmSpoke::id());
-    pda_address
+pub fn get_self_authority_pda(bump: u8) -> Result<Pubkey> {
+    // Bump is stored in the state at initialization, so this avoids the bump search in find_program_address.
+    Pubkey::create_program_address(&[b"self_authority", &[bump]], &SvmSpoke::id())
+        .map_err(|_| Error::from(ErrorCode::ConstraintSeeds).with_account_name("self_authority"))
 }
 
 pub fn decode_solidity_uint32(data: &[u8; 32]) -> Result<u32> {
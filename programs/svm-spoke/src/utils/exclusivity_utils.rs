@@ -0,0 +1,23 @@
+use anchor_lang::{prelude::*, solana_program::keccak};
+
+use crate::utils::process_proof;
+
+// Leaf of the exclusive relayer set merkle tree. Hashing the relayer key ensures that a leaf can never be confused with
+// a single exclusive relayer address that is compared directly against the signer.
+pub fn get_exclusive_relayer_leaf(relayer: &Pubkey) -> [u8; 32] {
+    keccak::hash(relayer.as_ref()).to_bytes()
+}
+
+// The exclusive_relayer field in relay data holds either a single relayer address or the root of a merkle tree built
+// from get_exclusive_relayer_leaf of all relayers in the exclusivity set. The proof is only evaluated when the relayer
+// does not directly match the exclusive_relayer field.
+pub fn is_exclusive_relayer(exclusive_relayer: &Pubkey, relayer: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    if exclusive_relayer == relayer {
+        return true;
+    }
+    if proof.is_empty() {
+        return false;
+    }
+
+    process_proof(proof, &get_exclusive_relayer_leaf(relayer)) == exclusive_relayer.to_bytes()
+}
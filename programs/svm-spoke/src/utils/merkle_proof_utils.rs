@@ -35,6 +35,26 @@ pub fn process_proof(proof: &[[u8; 32]], leaf: &[u8; 32]) -> [u8; 32] {
     computed_hash
 }
 
+// Computes the root of the Merkle tree built from the given sorted and deduplicated leaf hashes the same way as the
+// MerkleTree from @uma/common used to build the bundle trees: the last node of an odd layer is promoted to the next one.
+// The root of an empty tree is zero, matching EMPTY_MERKLE_ROOT.
+pub fn get_merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut layer = leaves.to_vec();
+    if layer.is_empty() {
+        return [0u8; 32];
+    }
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => commutative_keccak256(a, b),
+                _ => pair[0],
+            })
+            .collect();
+    }
+    layer[0]
+}
+
 // See https://github.com/OpenZeppelin/openzeppelin-contracts/blob/master/contracts/utils/cryptography/Hashes.sol
 fn commutative_keccak256(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
     if a < b {
@@ -0,0 +1,243 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+use multicall_handler::{handler_signer_seeds, program_allowlist_address, BalanceInjection, CompiledIx};
+
+use crate::{error::AcrossPlusError, utils::AcrossPlusMessage};
+
+// Can be used in place of the multicall handler signer PDA when building instructions, so that callers don't need to
+// derive it for the signer scope. It is substituted with the derived handler signer when compiling the message.
+pub const HANDLER_SIGNER_PLACEHOLDER: Pubkey = Pubkey::new_from_array([0xff; 32]);
+
+#[derive(Default)]
+pub struct MulticallMessageOptions {
+    pub value_amount: u64,              // Lamports sent by the relayer to the first message account.
+    pub signer_scope: Option<[u8; 32]>, // Salt selecting the scoped handler signer. None for the global handler signer.
+    pub program_allowlist: bool,        // Passes the program allowlist, required by deployments enforcing it.
+}
+
+pub struct CompiledMulticallMessage {
+    pub message: Vec<u8>, // Serialized Across+ message to be used as the deposit message.
+    pub remaining_accounts: Vec<AccountMeta>, // Fill accounts: the handler followed by the message accounts.
+}
+
+// Off-chain helper compiling standard instructions into the deposit message that is executed by the multicall handler.
+// Accounts are deduplicated and writable accounts are ordered before read-only ones as expected by invoke_handler.
+pub fn compile_multicall_message(
+    instructions: &[Instruction],
+    options: &MulticallMessageOptions,
+) -> Result<CompiledMulticallMessage> {
+    let (handler_signer, _) =
+        Pubkey::find_program_address(&handler_signer_seeds(&options.signer_scope), &multicall_handler::ID);
+    let substitute = |key: &Pubkey| match *key == HANDLER_SIGNER_PLACEHOLDER {
+        true => handler_signer,
+        false => *key,
+    };
+
+    // Deduplicate accounts in the order of their first appearance, applying the highest required write privilege.
+    let mut keys: Vec<(Pubkey, bool)> = Vec::new();
+    let mut add_key = |key: Pubkey, is_writable: bool| match keys.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, writable)) => *writable |= is_writable,
+        None => keys.push((key, is_writable)),
+    };
+    for instruction in instructions {
+        add_key(instruction.program_id, false);
+        for account_meta in &instruction.accounts {
+            let key = substitute(&account_meta.pubkey);
+
+            // The handler does not pass on any signer privileges, except for its own handler signer PDA.
+            if account_meta.is_signer && key != handler_signer {
+                return Err(error!(AcrossPlusError::UnsupportedMessageSigner).with_account_name(key.to_string()));
+            }
+            add_key(key, account_meta.is_writable);
+        }
+    }
+    if options.program_allowlist {
+        add_key(program_allowlist_address(), false);
+    }
+
+    // Compiled instructions reference the accounts by u8 indexes.
+    if keys.len() > u8::MAX as usize {
+        return err!(AcrossPlusError::TooManyMessageAccounts);
+    }
+    let (writable_keys, read_only_keys): (Vec<_>, Vec<_>) = keys.into_iter().partition(|(_, writable)| *writable);
+    let accounts: Vec<Pubkey> = writable_keys
+        .iter()
+        .chain(&read_only_keys)
+        .map(|(key, _)| *key)
+        .collect();
+
+    let index_of = |key: &Pubkey| accounts.iter().position(|account| account == key).unwrap_or_default() as u8;
+    let compiled_ixs: Vec<CompiledIx> = instructions
+        .iter()
+        .map(|instruction| CompiledIx {
+            program_id_index: index_of(&instruction.program_id),
+            account_key_indexes: instruction
+                .accounts
+                .iter()
+                .map(|account_meta| index_of(&substitute(&account_meta.pubkey)))
+                .collect(),
+            data: instruction.data.clone(),
+        })
+        .collect();
+
+    let mut handler_message = compiled_ixs.try_to_vec()?;
+    if options.signer_scope.is_some() {
+        // Optional fields preceding the signer scope are encoded as unused.
+        Vec::<BalanceInjection>::new().serialize(&mut handler_message)?;
+        None::<Pubkey>.serialize(&mut handler_message)?;
+        options.signer_scope.serialize(&mut handler_message)?;
+    }
+
+    let remaining_accounts = std::iter::once(AccountMeta::new_readonly(multicall_handler::ID, false))
+        .chain(writable_keys.iter().map(|(key, _)| AccountMeta::new(*key, false)))
+        .chain(
+            read_only_keys
+                .iter()
+                .map(|(key, _)| AccountMeta::new_readonly(*key, false)),
+        )
+        .collect();
+    let message = AcrossPlusMessage {
+        handler: multicall_handler::ID,
+        read_only_len: read_only_keys.len() as u8,
+        value_amount: options.value_amount,
+        accounts,
+        handler_message,
+        allow_handler_failure: false,
+        compute_units: 0,
+        allow_deferral: false,
+    };
+
+    Ok(CompiledMulticallMessage { message: message.try_to_vec()?, remaining_accounts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler_signer(signer_scope: &Option<[u8; 32]>) -> Pubkey {
+        Pubkey::find_program_address(&handler_signer_seeds(signer_scope), &multicall_handler::ID).0
+    }
+
+    fn decode(compiled: &CompiledMulticallMessage) -> (AcrossPlusMessage, Vec<CompiledIx>) {
+        let message = AcrossPlusMessage::try_from_message(&compiled.message).unwrap();
+        let compiled_ixs = Vec::<CompiledIx>::deserialize(&mut &message.handler_message[..]).unwrap();
+        (message, compiled_ixs)
+    }
+
+    #[test]
+    fn deduplicates_accounts_and_orders_writable_first() {
+        let (program, mint, source, destination) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let transfer_ix = Instruction::new_with_bytes(
+            program,
+            &[1],
+            vec![
+                AccountMeta::new(source, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new_readonly(HANDLER_SIGNER_PLACEHOLDER, true),
+            ],
+        );
+        // The mint is read-only in the transfer, but writable in the burn, so it must be promoted to writable.
+        let burn_ix = Instruction::new_with_bytes(
+            program,
+            &[2],
+            vec![
+                AccountMeta::new(source, false),
+                AccountMeta::new(mint, false),
+                AccountMeta::new_readonly(HANDLER_SIGNER_PLACEHOLDER, true),
+            ],
+        );
+
+        let options = MulticallMessageOptions { value_amount: 7, ..Default::default() };
+        let compiled = compile_multicall_message(&[transfer_ix, burn_ix], &options).unwrap();
+        let (message, compiled_ixs) = decode(&compiled);
+
+        let handler_signer = handler_signer(&None);
+        let expected_accounts = vec![source, mint, destination, program, handler_signer];
+        assert_eq!(message.handler, multicall_handler::ID);
+        assert_eq!(message.accounts, expected_accounts);
+        assert_eq!(message.read_only_len, 2);
+        assert_eq!(message.value_amount, 7);
+
+        assert_eq!(compiled_ixs.len(), 2);
+        assert_eq!((compiled_ixs[0].program_id_index, compiled_ixs[0].data.clone()), (3, vec![1]));
+        assert_eq!(compiled_ixs[0].account_key_indexes, vec![0, 1, 2, 4]);
+        assert_eq!((compiled_ixs[1].program_id_index, compiled_ixs[1].data.clone()), (3, vec![2]));
+        assert_eq!(compiled_ixs[1].account_key_indexes, vec![0, 1, 4]);
+
+        let remaining_keys: Vec<(Pubkey, bool)> = compiled
+            .remaining_accounts
+            .iter()
+            .map(|account_meta| (account_meta.pubkey, account_meta.is_writable))
+            .collect();
+        let mut expected_keys = vec![(multicall_handler::ID, false)];
+        expected_keys.extend(expected_accounts.iter().enumerate().map(|(i, key)| (*key, i < 3)));
+        assert_eq!(remaining_keys, expected_keys);
+        assert!(compiled
+            .remaining_accounts
+            .iter()
+            .all(|account_meta| !account_meta.is_signer));
+    }
+
+    #[test]
+    fn encodes_the_scoped_handler_signer() {
+        let signer_scope = Some([7; 32]);
+        let program = Pubkey::new_unique();
+        let instruction = Instruction::new_with_bytes(
+            program,
+            &[],
+            vec![AccountMeta::new_readonly(HANDLER_SIGNER_PLACEHOLDER, true)],
+        );
+
+        let options = MulticallMessageOptions { signer_scope, ..Default::default() };
+        let compiled = compile_multicall_message(&[instruction], &options).unwrap();
+        let (message, _) = decode(&compiled);
+
+        let scoped_handler_signer = handler_signer(&signer_scope);
+        assert_ne!(scoped_handler_signer, handler_signer(&None));
+        assert_eq!(message.accounts, vec![program, scoped_handler_signer]);
+
+        // Unused balance injections and fallback recipient precede the signer scope.
+        let mut handler_message = &message.handler_message[..];
+        let _: Vec<CompiledIx> = AnchorDeserialize::deserialize(&mut handler_message).unwrap();
+        let _: Vec<BalanceInjection> = AnchorDeserialize::deserialize(&mut handler_message).unwrap();
+        let fallback_recipient: Option<Pubkey> = AnchorDeserialize::deserialize(&mut handler_message).unwrap();
+        let decoded_scope: Option<[u8; 32]> = AnchorDeserialize::deserialize(&mut handler_message).unwrap();
+        assert_eq!((fallback_recipient, decoded_scope), (None, signer_scope));
+        assert!(handler_message.is_empty());
+    }
+
+    #[test]
+    fn passes_the_program_allowlist() {
+        let (program, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = Instruction::new_with_bytes(program, &[], vec![AccountMeta::new(destination, false)]);
+
+        let options = MulticallMessageOptions { program_allowlist: true, ..Default::default() };
+        let compiled = compile_multicall_message(&[instruction], &options).unwrap();
+        let (message, compiled_ixs) = decode(&compiled);
+
+        assert_eq!(message.accounts, vec![destination, program, program_allowlist_address()]);
+        assert_eq!(message.read_only_len, 2);
+        assert_eq!((compiled_ixs[0].program_id_index, compiled_ixs[0].account_key_indexes.clone()), (1, vec![0]));
+    }
+
+    #[test]
+    fn rejects_foreign_signers() {
+        let instruction =
+            Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![AccountMeta::new(Pubkey::new_unique(), true)]);
+
+        let result = compile_multicall_message(&[instruction], &MulticallMessageOptions::default());
+        assert_eq!(result.err(), Some(AcrossPlusError::UnsupportedMessageSigner.into()));
+    }
+
+    #[test]
+    fn rejects_too_many_accounts() {
+        let accounts = (0..u8::MAX)
+            .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+            .collect();
+        let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts);
+
+        let result = compile_multicall_message(&[instruction], &MulticallMessageOptions::default());
+        assert_eq!(result.err(), Some(AcrossPlusError::TooManyMessageAccounts.into()));
+    }
+}
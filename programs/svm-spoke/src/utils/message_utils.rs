@@ -1,20 +1,140 @@
 use anchor_lang::{
     prelude::*,
-    solana_program::{instruction::Instruction, keccak, program::invoke, system_instruction},
+    solana_program::{
+        compute_units::sol_remaining_compute_units, instruction::Instruction, keccak, program::invoke,
+        system_instruction,
+    },
 };
 
-use crate::{constants::DISCRIMINATOR_SIZE, error::AcrossPlusError};
+use crate::{
+    constants::{DISCRIMINATOR_SIZE, PENDING_RELAY_MESSAGE_EXECUTION_WINDOW},
+    error::{AcrossPlusError, SvmError},
+    event::DeferredRelayMessage,
+    state::PendingRelayMessage,
+};
 
 // Sha256(global:handle_v3_across_message)[..8];
 const HANDLE_V3_ACROSS_MESSAGE_DISCRIMINATOR: [u8; 8] = (0x838d3447103bc45c_u64).to_be_bytes();
 
-#[derive(AnchorDeserialize)]
+// Versioned messages start with this prefix followed by the version byte. Legacy messages start with the handler key
+// instead, so a legacy message is only parsed as such if its handler key starts with the same bytes when it does not
+// parse as a versioned message.
+pub const ACROSS_PLUS_MESSAGE_PREFIX: [u8; 7] = *b"across+";
+pub const ACROSS_PLUS_MESSAGE_V2: u8 = 2;
+
+// Legacy messages are borsh encoded without the prefix. Fields introduced with versioned messages are skipped and keep
+// their default values, so that existing deposits are handled the same way as before.
+#[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct AcrossPlusMessage {
     pub handler: Pubkey,
     pub read_only_len: u8,
     pub value_amount: u64,
     pub accounts: Vec<Pubkey>,
     pub handler_message: Vec<u8>,
+    // When set, the handler is not invoked within the fill, but the message is stored for execute_relay_message
+    // instead.
+    #[borsh_skip]
+    pub allow_handler_failure: bool,
+    // Compute units that must remain before invoking the handler. Zero when not declared.
+    #[borsh_skip]
+    pub compute_units: u32,
+    // Set when the depositor allows relayers to defer the handler to execute_relay_message, so legacy messages are
+    // always executed within the fill.
+    #[borsh_skip]
+    pub allow_deferral: bool,
+}
+
+// Body of the version 2 message following the prefix and version byte.
+#[derive(AnchorDeserialize)]
+struct AcrossPlusMessageV2 {
+    handler: Pubkey,
+    read_only_len: u8,
+    value_amount: u64,
+    accounts: Vec<Pubkey>,
+    handler_message: Vec<u8>,
+    allow_handler_failure: bool,
+    compute_units: u32,
+    allow_deferral: bool,
+}
+
+impl AcrossPlusMessage {
+    pub fn try_from_message(message: &[u8]) -> Result<Self> {
+        match message.strip_prefix(&ACROSS_PLUS_MESSAGE_PREFIX) {
+            // Legacy handler keys can also start with the prefix, so fall back to the legacy format when the message
+            // is not a valid versioned message. Only exact legacy encodings are accepted here, so that malformed
+            // versioned messages are still rejected with the versioned error.
+            Some(data) => Self::try_from_versioned(data)
+                .or_else(|error| AcrossPlusMessage::try_from_slice(message).map_err(|_| error)),
+            None => Self::try_from_legacy(message),
+        }
+    }
+
+    fn try_from_legacy(message: &[u8]) -> Result<Self> {
+        AcrossPlusMessage::deserialize(&mut &message[..]).map_err(|_| AcrossPlusError::MessageDidNotDeserialize.into())
+    }
+
+    fn try_from_versioned(data: &[u8]) -> Result<Self> {
+        match data {
+            [ACROSS_PLUS_MESSAGE_V2, data @ ..] => Self::try_from_v2(data),
+            _ => err!(AcrossPlusError::UnsupportedMessageVersion),
+        }
+    }
+
+    fn try_from_v2(data: &[u8]) -> Result<Self> {
+        // Unlike legacy messages, trailing bytes are rejected so that they cannot be mistaken for future fields.
+        let message =
+            AcrossPlusMessageV2::try_from_slice(data).map_err(|_| AcrossPlusError::MalformedVersionedMessage)?;
+
+        Ok(AcrossPlusMessage {
+            handler: message.handler,
+            read_only_len: message.read_only_len,
+            value_amount: message.value_amount,
+            accounts: message.accounts,
+            handler_message: message.handler_message,
+            allow_handler_failure: message.allow_handler_failure,
+            compute_units: message.compute_units,
+            allow_deferral: message.allow_deferral,
+        })
+    }
+}
+
+// Invokes the message handler within the fill, unless the relayer passed the pending_relay_message account. In that
+// case the message is stored to be executed separately with execute_relay_message, so that the fill is not limited by
+// the handler's account set. Relayers can only defer messages where the depositor opted in to deferral. Messages
+// that allow handler failure must always be deferred, so that tokens are delivered to the recipient even if the
+// handler fails. Returns the DeferredRelayMessage event for the caller to emit when the message is deferred.
+pub fn invoke_or_defer_handler<'info>(
+    relayer: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    relay_hash: [u8; 32],
+    message: &Vec<u8>,
+    pending_relay_message: &mut Option<Account<'info, PendingRelayMessage>>,
+    pending_relay_message_bump: Option<u8>,
+    current_time: u32,
+) -> Result<Option<DeferredRelayMessage>> {
+    let across_plus_message = AcrossPlusMessage::try_from_message(message)?;
+    let allow_handler_failure = across_plus_message.allow_handler_failure;
+    let allow_deferral = across_plus_message.allow_deferral;
+
+    match (allow_handler_failure, allow_deferral, pending_relay_message) {
+        (false, _, None) => invoke_handler(relayer, remaining_accounts, message).map(|_| None),
+        (true, _, None) => err!(SvmError::MissingPendingRelayMessage),
+        (false, false, Some(_)) => err!(SvmError::UnexpectedPendingRelayMessage),
+        (_, _, Some(pending_relay_message)) => {
+            let execution_deadline = current_time.saturating_add(PENDING_RELAY_MESSAGE_EXECUTION_WINDOW);
+            pending_relay_message.relayer = relayer.key();
+            pending_relay_message.execution_deadline = execution_deadline;
+            pending_relay_message.bump = pending_relay_message_bump.unwrap_or_default();
+            pending_relay_message.message = message.clone();
+            Ok(Some(DeferredRelayMessage {
+                relay_hash,
+                message_hash: hash_non_empty_message(message),
+                relayer: relayer.key(),
+                execution_deadline,
+                allow_handler_failure,
+            }))
+        }
+    }
 }
 
 pub fn invoke_handler<'info>(
@@ -22,8 +142,7 @@ pub fn invoke_handler<'info>(
     remaining_accounts: &[AccountInfo<'info>],
     message: &Vec<u8>,
 ) -> Result<()> {
-    let message =
-        AcrossPlusMessage::deserialize(&mut &message[..]).map_err(|_| AcrossPlusError::MessageDidNotDeserialize)?;
+    let message = AcrossPlusMessage::try_from_message(message)?;
 
     // First remaining account is the handler and the rest are accounts to be passed to the message handler.
     let message_accounts_len = message.accounts.len();
@@ -88,6 +207,11 @@ pub fn invoke_handler<'info>(
 
     let instruction = Instruction { program_id: message.handler, accounts, data };
 
+    // Fail early with a dedicated error if the relayer did not provide the compute budget declared in the message.
+    if sol_remaining_compute_units() < message.compute_units as u64 {
+        return err!(AcrossPlusError::InsufficientComputeUnits);
+    }
+
     invoke(&instruction, account_infos)?;
 
     Ok(())
@@ -1,7 +1,10 @@
 pub mod bitmap_utils;
 pub mod cctp_utils;
 pub mod deposit_utils;
+pub mod exclusivity_utils;
 pub mod merkle_proof_utils;
+#[cfg(not(target_os = "solana"))]
+pub mod message_compiler;
 pub mod message_utils;
 pub mod testable_utils;
 pub mod transfer_utils;
@@ -9,7 +12,10 @@ pub mod transfer_utils;
 pub use bitmap_utils::*;
 pub use cctp_utils::*;
 pub use deposit_utils::*;
+pub use exclusivity_utils::*;
 pub use merkle_proof_utils::*;
+#[cfg(not(target_os = "solana"))]
+pub use message_compiler::*;
 pub use message_utils::*;
 pub use testable_utils::*;
 pub use transfer_utils::*;
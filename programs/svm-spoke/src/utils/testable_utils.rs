@@ -4,7 +4,7 @@ use crate::state::State;
 
 #[derive(Accounts)]
 pub struct SetCurrentTime<'info> {
-    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump)]
+    #[account(mut, seeds = [b"state", state.seed.to_le_bytes().as_ref()], bump = state.bump)]
     pub state: Account<'info, State>,
 
     pub signer: Signer<'info>,
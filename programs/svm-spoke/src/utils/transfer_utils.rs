@@ -1,11 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
 
 use crate::State;
 
 pub fn transfer_from<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
-    to: &InterfaceAccount<'info, TokenAccount>,
+    to: &impl ToAccountInfo<'info>,
     amount: u64,
     state: &Account<'info, State>,
     state_bump: u8,
@@ -27,3 +30,41 @@ pub fn transfer_from<'info>(
 
     transfer_checked(cpi_context, amount, mint.decimals)
 }
+
+// Creates the authority's associated token account if it does not exist yet and returns the rent paid by the payer for
+// it (0 if the account already existed). The payer, authority and associated token program are only needed for the
+// creation, so when any of them is not provided this fails the same way as Anchor's account constraints on an
+// uninitialized token account.
+pub fn create_associated_token_account_if_needed<'info>(
+    payer: Option<&AccountInfo<'info>>,
+    associated_token: &AccountInfo<'info>,
+    authority: Option<&AccountInfo<'info>>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    associated_token_program: Option<&Program<'info, AssociatedToken>>,
+    system_program: &Program<'info, System>,
+) -> Result<u64> {
+    if associated_token.owner != &System::id() {
+        return Ok(0);
+    }
+    let (Some(payer), Some(authority), Some(associated_token_program)) = (payer, authority, associated_token_program)
+    else {
+        return err!(ErrorCode::AccountNotInitialized);
+    };
+
+    // Track the payer balance as the associated token account might have been prefunded with some lamports.
+    let payer_lamports_before = payer.lamports();
+
+    let cpi_accounts = associated_token::Create {
+        payer: payer.to_account_info(),
+        associated_token: associated_token.to_account_info(),
+        authority: authority.to_account_info(),
+        mint: mint.to_account_info(),
+        system_program: system_program.to_account_info(),
+        token_program: token_program.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(associated_token_program.to_account_info(), cpi_accounts);
+    associated_token::create_idempotent(cpi_ctx)?;
+
+    Ok(payer_lamports_before.saturating_sub(payer.lamports()))
+}
@@ -53,8 +53,11 @@ pub mod test {
 
     pub fn test_set_claimed(ctx: Context<UpdateBitmap>, index: u32) -> Result<()> {
         let bitmap_account = &mut ctx.accounts.bitmap_account; // Change to mutable reference
-        set_claimed(&mut bitmap_account.claimed_bitmap, index);
-        Ok(())
+        let byte_index = (index / 8) as usize;
+        if byte_index >= bitmap_account.claimed_bitmap.len() {
+            bitmap_account.claimed_bitmap.resize(byte_index + 1, 0); // Bitmap utils expect a pre-sized bitmap.
+        }
+        set_claimed(&mut bitmap_account.claimed_bitmap, index)
     }
 
     #[derive(Accounts)]
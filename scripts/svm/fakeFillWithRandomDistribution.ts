@@ -183,11 +183,11 @@ async function fillV3RelayToRandom(): Promise<void> {
   );
 
   // Prepare fill instruction as we will need to use Address Lookup Table (ALT).
-  const fillV3RelayValues: FillDataValues = [relayHash, relayData, repaymentChain, repaymentAddress];
+  const fillV3RelayValues: FillDataValues = [relayHash, relayData, repaymentChain, repaymentAddress, null];
   if (bufferParams) {
     await loadFillRelayParams(program, signer, fillV3RelayValues[1], fillV3RelayValues[2], fillV3RelayValues[3]);
   }
-  const fillV3RelayParams: FillDataParams = bufferParams ? [fillV3RelayValues[0], null, null, null] : fillV3RelayValues;
+  const fillV3RelayParams: FillDataParams = bufferParams ? [fillV3RelayValues[0], null, null, null, null] : fillV3RelayValues;
   const [instructionParams] = bufferParams
     ? PublicKey.findProgramAddressSync(
         [Buffer.from("instruction_params"), signer.publicKey.toBuffer()],
@@ -67,6 +67,7 @@ async function queryFills(): Promise<void> {
         { Property: "updatedMessageHash", Value: event.data.relayExecutionInfo.updatedMessageHash.toString() },
         { Property: "updatedOutputAmount", Value: event.data.relayExecutionInfo.updatedOutputAmount.toString() },
         { Property: "fillType", Value: event.data.relayExecutionInfo.fillType },
+        { Property: "recipientTokenAccountRent", Value: event.data.recipientTokenAccountRent.toString() },
       ]);
     });
   } catch (error) {
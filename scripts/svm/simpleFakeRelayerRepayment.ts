@@ -183,7 +183,8 @@ async function testBundleLogic(): Promise<void> {
     { property: "Signer", value: signer.publicKey.toString() },
   ]);
 
-  const relayRootBundleTx = await (program.methods.relayRootBundle(Array.from(root), Array.from(root)) as any)
+  // Claimed bitmap capacity only needs to cover the single leaf in the tree.
+  const relayRootBundleTx = await (program.methods.relayRootBundle(Array.from(root), Array.from(root), 1) as any)
     .accounts({
       state: statePda,
       rootBundle: rootBundle,
@@ -156,7 +156,7 @@ async function fillRelay(): Promise<void> {
     TOKEN_PROGRAM_ID
   );
 
-  const fillDataValues: FillDataValues = [Array.from(relayHashUint8Array), relayData, chainId, signer.publicKey];
+  const fillDataValues: FillDataValues = [Array.from(relayHashUint8Array), relayData, chainId, signer.publicKey, null];
 
   const fillAccounts = {
     state: statePda,
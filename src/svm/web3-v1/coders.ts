@@ -1,8 +1,9 @@
-import { BorshAccountsCoder } from "@coral-xyz/anchor";
+import { BN, BorshAccountsCoder } from "@coral-xyz/anchor";
 import { IdlCoder } from "@coral-xyz/anchor/dist/cjs/coder/borsh/idl";
-import { IdlTypeDef } from "@coral-xyz/anchor/dist/cjs/idl";
+import { IdlField, IdlTypeDef } from "@coral-xyz/anchor/dist/cjs/idl";
 import * as borsh from "@coral-xyz/borsh";
 import {
+  AccountMeta,
   CompiledInstruction,
   Message,
   MessageAccountKeys,
@@ -13,7 +14,7 @@ import {
 } from "@solana/web3.js";
 import bs58 from "bs58";
 import { Layout } from "buffer-layout";
-import { AcrossPlusMessage } from "../../types/svm";
+import { AcrossPlusMessage, BalanceInjection, MulticallAssertion } from "../../types/svm";
 
 /**
  * Extended Anchor accounts coder to handle large account data.
@@ -52,7 +53,11 @@ class UnsignedCompiledKeys {
     this.payer = payer;
   }
 
-  static compileUnsigned(instructions: TransactionInstruction[], payer?: PublicKey): UnsignedCompiledKeys {
+  static compileUnsigned(
+    instructions: TransactionInstruction[],
+    payer?: PublicKey,
+    readOnlyKeys: PublicKey[] = []
+  ): UnsignedCompiledKeys {
     const keyModeMap: KeyModeMap = new Map();
     const getOrInsertDefault = (pubkey: PublicKey): { isWritable: boolean } => {
       const address = pubkey.toBase58();
@@ -79,6 +84,8 @@ class UnsignedCompiledKeys {
       }
     }
 
+    for (const key of readOnlyKeys) getOrInsertDefault(key);
+
     return new UnsignedCompiledKeys(keyModeMap, payer);
   }
 
@@ -109,8 +116,12 @@ class UnsignedCompiledKeys {
  * https://github.com/solana-labs/solana-web3.js/blob/v1.95.3/src/message/legacy.ts
  */
 class UnsignedMessage extends Message {
-  static compileUnsigned(instructions: TransactionInstruction[], payer?: PublicKey): Message {
-    const compiledKeys = UnsignedCompiledKeys.compileUnsigned(instructions, payer);
+  static compileUnsigned(
+    instructions: TransactionInstruction[],
+    payer?: PublicKey,
+    readOnlyKeys?: PublicKey[]
+  ): Message {
+    const compiledKeys = UnsignedCompiledKeys.compileUnsigned(instructions, payer, readOnlyKeys);
     const [header, staticAccountKeys] = compiledKeys.getMessageComponents();
     const accountKeys = new MessageAccountKeys(staticAccountKeys);
     const compiledInstructions = accountKeys.compileInstructions(instructions).map(
@@ -137,13 +148,55 @@ export class MulticallHandlerCoder {
 
   private readonly layout: Layout;
 
-  constructor(instructions: TransactionInstruction[], payerKey?: PublicKey) {
+  private readonly balanceInjections: BalanceInjection[];
+
+  private readonly fallbackRecipient?: PublicKey;
+
+  private readonly signerScope?: number[];
+
+  private readonly assertions: MulticallAssertion[];
+
+  constructor(
+    instructions: TransactionInstruction[],
+    payerKey?: PublicKey,
+    options: {
+      balanceInjections?: BalanceInjection[];
+      fallbackRecipient?: PublicKey;
+      signerScope?: Uint8Array; // Salt selecting the scoped handler signer.
+      assertions?: MulticallAssertion[];
+      programAllowlist?: PublicKey; // Passed as an additional account, required by deployments enforcing the allowlist.
+    } = {}
+  ) {
     // Compile transaction message and keys.
-    this.compiledMessage = UnsignedMessage.compileUnsigned(instructions, payerKey);
+    const readOnlyKeys = options.programAllowlist ? [options.programAllowlist] : [];
+    this.compiledMessage = UnsignedMessage.compileUnsigned(instructions, payerKey, readOnlyKeys);
+    this.balanceInjections = options.balanceInjections ?? [];
+    this.fallbackRecipient = options.fallbackRecipient;
+    this.signerScope = options.signerScope && Array.from(options.signerScope);
+    this.assertions = options.assertions ?? [];
 
-    // Setup the layout for the encoder.
-    const fieldLayouts = [IdlCoder.fieldLayout(MulticallHandlerCoder.coderArg, MulticallHandlerCoder.coderTypes)];
-    this.layout = borsh.struct(fieldLayouts);
+    // Setup the layout for the encoder. Optional trailing fields are only encoded up to the last used one, so that the
+    // encoding of messages without them is unchanged.
+    const optionalArgs = [
+      { arg: MulticallHandlerCoder.balanceInjectionsArg, used: this.balanceInjections.length > 0 },
+      { arg: MulticallHandlerCoder.fallbackRecipientArg, used: this.fallbackRecipient !== undefined },
+      { arg: MulticallHandlerCoder.signerScopeArg, used: this.signerScope !== undefined },
+      { arg: MulticallHandlerCoder.assertionsArg, used: this.assertions.length > 0 },
+    ];
+    const optionalArgsLen = optionalArgs.map(({ used }) => used).lastIndexOf(true) + 1;
+    const fieldArgs = [MulticallHandlerCoder.coderArg, ...optionalArgs.slice(0, optionalArgsLen).map(({ arg }) => arg)];
+    this.layout = borsh.struct(fieldArgs.map((arg) => IdlCoder.fieldLayout(arg, MulticallHandlerCoder.coderTypes)));
+  }
+
+  // Derives the program allowlist PDA restricting the programs that can be invoked by the handler.
+  static programAllowlist(handlerProgramId: PublicKey) {
+    return PublicKey.findProgramAddressSync([Buffer.from("program_allowlist")], handlerProgramId)[0];
+  }
+
+  // Derives the handler signer PDA, optionally scoped by the salt chosen by the depositor.
+  static handlerSigner(handlerProgramId: PublicKey, signerScope?: Uint8Array) {
+    const seeds = signerScope ? [Buffer.from("handler_signer"), signerScope] : [Buffer.from("handler_signer")];
+    return PublicKey.findProgramAddressSync(seeds, handlerProgramId)[0];
   }
 
   private static coderArg = {
@@ -157,7 +210,132 @@ export class MulticallHandlerCoder {
     },
   };
 
+  private static balanceInjectionsArg = {
+    name: "balanceInjections",
+    type: {
+      vec: {
+        defined: {
+          name: "balanceInjection",
+        },
+      },
+    },
+  };
+
+  private static fallbackRecipientArg: IdlField = {
+    name: "fallbackRecipient",
+    type: {
+      option: "pubkey",
+    },
+  };
+
+  private static signerScopeArg: IdlField = {
+    name: "signerScope",
+    type: {
+      option: {
+        array: ["u8", 32],
+      },
+    },
+  };
+
+  private static assertionsArg = {
+    name: "assertions",
+    type: {
+      vec: {
+        defined: {
+          name: "assertion",
+        },
+      },
+    },
+  };
+
   private static coderTypes: IdlTypeDef[] = [
+    {
+      name: "assertion",
+      type: {
+        kind: "struct",
+        fields: [
+          {
+            name: "afterIxIndex",
+            type: "u8",
+          },
+          {
+            name: "kind",
+            type: {
+              defined: {
+                name: "assertionKind",
+              },
+            },
+          },
+        ],
+      },
+    },
+    {
+      name: "assertionKind",
+      type: {
+        kind: "enum",
+        variants: [
+          {
+            name: "minTokenBalance",
+            fields: [
+              {
+                name: "tokenAccountIndex",
+                type: "u8",
+              },
+              {
+                name: "minAmount",
+                type: "u64",
+              },
+            ],
+          },
+          {
+            name: "accountOwner",
+            fields: [
+              {
+                name: "accountIndex",
+                type: "u8",
+              },
+              {
+                name: "owner",
+                type: "pubkey",
+              },
+            ],
+          },
+          {
+            name: "minLamports",
+            fields: [
+              {
+                name: "accountIndex",
+                type: "u8",
+              },
+              {
+                name: "minLamports",
+                type: "u64",
+              },
+            ],
+          },
+        ],
+      },
+    },
+    {
+      name: "balanceInjection",
+      type: {
+        kind: "struct",
+        fields: [
+          {
+            name: "ixIndex",
+            type: "u8",
+          },
+          {
+            name: "tokenAccountIndex",
+            type: "u8",
+          },
+          {
+            name: "offset",
+            type: "u16",
+          },
+        ],
+      },
+    },
     {
       name: "compiledIx",
       type: {
@@ -198,13 +376,53 @@ export class MulticallHandlerCoder {
     });
   }
 
+  // Resolves the index of the account in the compiled message keys that are passed to the handler.
+  private accountIndex(account: PublicKey) {
+    const index = this.compiledMessage.accountKeys.findIndex((key) => key.equals(account));
+    if (index === -1) throw new Error(`Account ${account} not in instructions`);
+    return index;
+  }
+
+  private encodeAssertion(assertion: MulticallAssertion) {
+    const { afterIxIndex } = assertion;
+    if ("minTokenBalance" in assertion) {
+      const { tokenAccount, minAmount } = assertion.minTokenBalance;
+      const tokenAccountIndex = this.accountIndex(tokenAccount);
+      return { afterIxIndex, kind: { minTokenBalance: { tokenAccountIndex, minAmount } } };
+    }
+    if ("accountOwner" in assertion) {
+      const { account, owner } = assertion.accountOwner;
+      return { afterIxIndex, kind: { accountOwner: { accountIndex: this.accountIndex(account), owner } } };
+    }
+    const { account, minLamports } = assertion.minLamports;
+    return { afterIxIndex, kind: { minLamports: { accountIndex: this.accountIndex(account), minLamports } } };
+  }
+
   encode() {
     const buffer = Buffer.alloc(1280);
-    const len = this.layout.encode({ compiledIxs: this.compiledMessage.compiledInstructions }, buffer);
+    const balanceInjections = this.balanceInjections.map(({ ixIndex, tokenAccount, offset }) => {
+      return { ixIndex, tokenAccountIndex: this.accountIndex(tokenAccount), offset };
+    });
+    const assertions = this.assertions.map((assertion) => this.encodeAssertion(assertion));
+    const len = this.layout.encode(
+      {
+        compiledIxs: this.compiledMessage.compiledInstructions,
+        balanceInjections,
+        fallbackRecipient: this.fallbackRecipient ?? null,
+        signerScope: this.signerScope ?? null,
+        assertions,
+      },
+      buffer
+    );
     return buffer.slice(0, len);
   }
+
 }
 
+// Versioned Across+ messages start with this prefix followed by the version byte.
+export const ACROSS_PLUS_MESSAGE_PREFIX = Buffer.from("across+");
+export const ACROSS_PLUS_MESSAGE_V2 = 2;
+
 /**
  * Helper to encode Across+ messages.
  */
@@ -255,13 +473,118 @@ export class AcrossPlusMessageCoder {
         ],
       },
     },
+    {
+      name: "acrossPlusMessageV2",
+      type: {
+        kind: "struct",
+        fields: [
+          {
+            name: "handler",
+            type: "pubkey",
+          },
+          {
+            name: "readOnlyLen",
+            type: "u8",
+          },
+          {
+            name: "valueAmount",
+            type: "u64",
+          },
+          {
+            name: "accounts",
+            type: {
+              vec: "pubkey",
+            },
+          },
+          {
+            name: "handlerMessage",
+            type: "bytes",
+          },
+          {
+            name: "allowHandlerFailure",
+            type: "bool",
+          },
+          {
+            name: "computeUnits",
+            type: "u32",
+          },
+          {
+            name: "allowDeferral",
+            type: "bool",
+          },
+        ],
+      },
+    },
   ];
 
   encode() {
+    if (this.acrossPlusMessage.version === ACROSS_PLUS_MESSAGE_V2) return this.encodeV2();
+
     const fieldLayouts = [IdlCoder.fieldLayout(AcrossPlusMessageCoder.coderArg, AcrossPlusMessageCoder.coderTypes)];
     const layout = borsh.struct(fieldLayouts);
     const buffer = Buffer.alloc(12800);
     const len = layout.encode({ message: this.acrossPlusMessage }, buffer);
     return buffer.slice(0, len);
   }
+
+  private encodeV2() {
+    const coderArg = { name: "message", type: { defined: { name: "acrossPlusMessageV2" } } };
+    const layout = borsh.struct([IdlCoder.fieldLayout(coderArg, AcrossPlusMessageCoder.coderTypes)]);
+    const buffer = Buffer.alloc(12800);
+    const message = {
+      ...this.acrossPlusMessage,
+      allowHandlerFailure: this.acrossPlusMessage.allowHandlerFailure ?? false,
+      computeUnits: this.acrossPlusMessage.computeUnits ?? 0,
+      allowDeferral: this.acrossPlusMessage.allowDeferral ?? false,
+    };
+    const len = layout.encode({ message }, buffer);
+    return Buffer.concat([ACROSS_PLUS_MESSAGE_PREFIX, Buffer.from([ACROSS_PLUS_MESSAGE_V2]), buffer.slice(0, len)]);
+  }
+}
+
+// Can be used in place of the multicall handler signer PDA when building instructions, so that callers don't need to
+// derive it for the signer scope. It is substituted with the derived handler signer when compiling the message.
+export const HANDLER_SIGNER_PLACEHOLDER = new PublicKey(Buffer.alloc(32, 0xff));
+
+/**
+ * Compiles standard instructions into the deposit message that is executed by the multicall handler. Accounts are
+ * deduplicated and writable accounts are ordered before read-only ones as expected by the spoke when invoking the
+ * handler. Returns the encoded Across+ message and the remaining accounts to be passed when filling the relay. This
+ * mirrors compile_multicall_message in the svm-spoke program crate.
+ */
+export function compileMulticallMessage(
+  handlerProgramId: PublicKey,
+  instructions: TransactionInstruction[],
+  options: { valueAmount?: BN; signerScope?: Uint8Array; programAllowlist?: boolean } = {}
+) {
+  const handlerSigner = MulticallHandlerCoder.handlerSigner(handlerProgramId, options.signerScope);
+  const substitutedInstructions = instructions.map((ix) => {
+    const keys = ix.keys.map((accountMeta) => {
+      const pubkey = accountMeta.pubkey.equals(HANDLER_SIGNER_PLACEHOLDER) ? handlerSigner : accountMeta.pubkey;
+      // The handler does not pass on any signer privileges, except for its own handler signer PDA.
+      if (accountMeta.isSigner && !pubkey.equals(handlerSigner)) {
+        throw new Error(`Only the handler signer can sign message instructions, got ${pubkey.toBase58()}`);
+      }
+      return { ...accountMeta, pubkey };
+    });
+    return new TransactionInstruction({ programId: ix.programId, keys, data: ix.data });
+  });
+
+  const multicallHandlerCoder = new MulticallHandlerCoder(substitutedInstructions, undefined, {
+    signerScope: options.signerScope,
+    programAllowlist: options.programAllowlist ? MulticallHandlerCoder.programAllowlist(handlerProgramId) : undefined,
+  });
+  const message = new AcrossPlusMessageCoder({
+    handler: handlerProgramId,
+    readOnlyLen: multicallHandlerCoder.readOnlyLen,
+    valueAmount: options.valueAmount ?? new BN(0),
+    accounts: multicallHandlerCoder.compiledMessage.accountKeys,
+    handlerMessage: multicallHandlerCoder.encode(),
+  }).encode();
+  const remainingAccounts: AccountMeta[] = [
+    { pubkey: handlerProgramId, isSigner: false, isWritable: false },
+    ...multicallHandlerCoder.compiledKeyMetas,
+  ];
+
+  return { message, remainingAccounts, multicallHandlerCoder };
 }
@@ -1,4 +1,5 @@
 import { BN } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
 import { ethers } from "ethers";
 import { RelayerRefundLeaf, RelayerRefundLeafSolana, SlowFillLeaf } from "../../types/svm";
 import { serialize } from "borsh";
@@ -220,3 +221,10 @@ export function slowFillHashFn(slowFillLeaf: SlowFillLeaf): string {
 
   return ethers.utils.keccak256(contentToHash);
 }
+
+/**
+ * Hash function for exclusive relayer set leaves. The root of the set is passed as the deposit exclusive_relayer.
+ */
+export function exclusiveRelayerHashFn(relayer: PublicKey): string {
+  return ethers.utils.keccak256(relayer.toBuffer());
+}
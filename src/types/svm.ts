@@ -90,9 +90,9 @@ export type DepositDataValues = [
 /**
  * Fill Data Interfaces
  */
-export type FillDataValues = [number[], RelayData, BN, PublicKey];
+export type FillDataValues = [number[], RelayData, BN, PublicKey, number[][] | null];
 
-export type FillDataParams = [number[], RelayData | null, BN | null, PublicKey | null];
+export type FillDataParams = [number[], RelayData | null, BN | null, PublicKey | null, number[][] | null];
 
 /**
  * Request Slow Fill Data Interfaces
@@ -117,8 +117,32 @@ export type AcrossPlusMessage = {
   valueAmount: BN;
   accounts: PublicKey[];
   handlerMessage: Buffer;
+  allowHandlerFailure?: boolean; // Defers handler execution so that fills can't be blocked (versioned message only).
+  version?: number; // Encodes the versioned message envelope when set to 2, otherwise the legacy format is used.
+  computeUnits?: number; // Compute units that must remain before invoking the handler (versioned message only).
+  allowDeferral?: boolean; // Lets relayers defer handler execution to executeRelayMessage (versioned message only).
 };
 
+/**
+ * Multicall Handler Balance Injection Interface
+ */
+export type BalanceInjection = {
+  ixIndex: number; // Index of the instruction whose data is updated before the call.
+  tokenAccount: PublicKey; // Token account whose current balance is written into the instruction data.
+  offset: number; // Offset in the instruction data where the balance is written as little-endian u64.
+};
+
+/**
+ * Multicall Handler Assertion Interface
+ */
+export type MulticallAssertion = {
+  afterIxIndex: number; // Index of the instruction after which the assertion is checked.
+} & (
+  | { minTokenBalance: { tokenAccount: PublicKey; minAmount: BN } }
+  | { accountOwner: { account: PublicKey; owner: PublicKey } }
+  | { minLamports: { account: PublicKey; minLamports: BN } }
+);
+
 /**
  * Event Type Interface
  */
@@ -0,0 +1,225 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { MockAmm } from "../../target/types/mock_amm";
+import { SpokePeriphery } from "../../target/types/spoke_periphery";
+import { readEventsUntilFound } from "../../src/svm/web3-v1";
+import { common } from "./SvmSpoke.common";
+
+const { provider, connection, program, owner, seedBalance, initializeState, depositData } = common;
+const { createRoutePda, getVaultAta, assert, assertSE } = common;
+
+describe("spoke_periphery.swap_and_bridge", () => {
+  anchor.setProvider(provider);
+
+  const peripheryProgram = anchor.workspace.SpokePeriphery as Program<SpokePeriphery>;
+  const mockAmmProgram = anchor.workspace.MockAmm as Program<MockAmm>;
+
+  const depositor = Keypair.generate();
+  const payer = (anchor.AnchorProvider.env().wallet as anchor.Wallet).payer;
+  const tokenDecimals = 6;
+  const routeChainId = new BN(1);
+  const poolBalance = 1_000_000_000;
+
+  let state: PublicKey, seed: BN, route: PublicKey, vault: PublicKey;
+  let swapToken: PublicKey, inputToken: PublicKey, depositorSwapTA: PublicKey, depositorInputTA: PublicKey;
+  let swapAuthority: PublicKey, swapTokenAccount: PublicKey, swapOutputTokenAccount: PublicKey;
+  let poolAuthority: PublicKey, poolInputTA: PublicKey, poolOutputTA: PublicKey;
+  let swapAndBridgeAccounts: any;
+
+  // Encodes the mock AMM swap of swap tokens held by the periphery swap authority into the input token.
+  const getSwapInstruction = async (amountIn: number, amountOut: number) => {
+    return mockAmmProgram.methods
+      .swap(new BN(amountIn), new BN(amountOut))
+      .accounts({
+        authority: swapAuthority,
+        inputMint: swapToken,
+        outputMint: inputToken,
+        userInputTokenAccount: swapTokenAccount,
+        userOutputTokenAccount: swapOutputTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .instruction();
+  };
+
+  const getSwapAndDepositData = (
+    swapTokenAmount: number,
+    minExpectedInputTokenAmount: number,
+    exchangeData: Buffer
+  ) => ({
+    swapTokenAmount: new BN(swapTokenAmount),
+    minExpectedInputTokenAmount: new BN(minExpectedInputTokenAmount),
+    exchangeData,
+    depositData: {
+      recipient: depositData.recipient,
+      outputToken: depositData.outputToken,
+      outputAmount: depositData.outputAmount,
+      destinationChainId: routeChainId,
+      exclusiveRelayer: depositData.exclusiveRelayer,
+      quoteTimestamp: depositData.quoteTimestamp,
+      fillDeadline: depositData.fillDeadline,
+      exclusivityParameter: depositData.exclusivityParameter,
+      message: depositData.message,
+    },
+  });
+
+  const swapAndBridge = async (swapTokenAmount: number, minExpectedInputTokenAmount: number, swapIx: any) => {
+    return peripheryProgram.methods
+      .swapAndBridge(getSwapAndDepositData(swapTokenAmount, minExpectedInputTokenAmount, swapIx.data))
+      .accounts(swapAndBridgeAccounts)
+      .remainingAccounts(swapIx.keys.map((key: any) => ({ ...key, isSigner: false })))
+      .signers([depositor])
+      .rpc();
+  };
+
+  before("Funds depositor wallet", async () => {
+    await connection.requestAirdrop(depositor.publicKey, 10_000_000_000); // 10 SOL
+  });
+
+  beforeEach(async () => {
+    ({ state, seed } = await initializeState());
+
+    swapToken = await createMint(connection, payer, owner, owner, tokenDecimals);
+    inputToken = await createMint(connection, payer, owner, owner, tokenDecimals);
+
+    route = createRoutePda(inputToken, seed, routeChainId);
+    vault = await getVaultAta(inputToken, state);
+    await program.methods
+      .setEnableRoute(inputToken, routeChainId, true)
+      .accounts({
+        signer: owner,
+        payer: owner,
+        state,
+        route,
+        vault,
+        originTokenMint: inputToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    depositorSwapTA = (await getOrCreateAssociatedTokenAccount(connection, payer, swapToken, depositor.publicKey))
+      .address;
+    await mintTo(connection, payer, swapToken, depositorSwapTA, owner, seedBalance);
+    depositorInputTA = getAssociatedTokenAddressSync(inputToken, depositor.publicKey);
+
+    [swapAuthority] = PublicKey.findProgramAddressSync([Buffer.from("swap_authority")], peripheryProgram.programId);
+    swapTokenAccount = getAssociatedTokenAddressSync(swapToken, swapAuthority, true);
+    swapOutputTokenAccount = getAssociatedTokenAddressSync(inputToken, swapAuthority, true);
+
+    // Seed the mock AMM pool with input tokens so it can return swap output.
+    [poolAuthority] = PublicKey.findProgramAddressSync([Buffer.from("pool_authority")], mockAmmProgram.programId);
+    poolInputTA = (await getOrCreateAssociatedTokenAccount(connection, payer, swapToken, poolAuthority, true)).address;
+    poolOutputTA = (await getOrCreateAssociatedTokenAccount(connection, payer, inputToken, poolAuthority, true))
+      .address;
+    await mintTo(connection, payer, inputToken, poolOutputTA, owner, poolBalance);
+
+    const [spokeEventAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("__event_authority")],
+      program.programId
+    );
+    swapAndBridgeAccounts = {
+      depositor: depositor.publicKey,
+      swapAuthority,
+      exchange: mockAmmProgram.programId,
+      swapTokenMint: swapToken,
+      inputTokenMint: inputToken,
+      depositorSwapTokenAccount: depositorSwapTA,
+      depositorInputTokenAccount: depositorInputTA,
+      swapTokenAccount,
+      swapOutputTokenAccount,
+      spokeState: state,
+      spokeRoute: route,
+      spokeVault: vault,
+      spokeEventAuthority,
+      spokeProgram: program.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      program: peripheryProgram.programId,
+    };
+  });
+
+  it("Swaps and deposits the swap output", async () => {
+    const swapTokenAmount = 500_000;
+    const swapOutputAmount = 490_000;
+    const swapIx = await getSwapInstruction(swapTokenAmount, swapOutputAmount);
+
+    const tx = await swapAndBridge(swapTokenAmount, swapOutputAmount, swapIx);
+
+    const depositorSwapAccount = await getAccount(connection, depositorSwapTA);
+    assertSE(depositorSwapAccount.amount, seedBalance - swapTokenAmount, "Depositor should send the swap tokens");
+    const poolInputAccount = await getAccount(connection, poolInputTA);
+    assertSE(poolInputAccount.amount, swapTokenAmount, "Pool should receive the swap tokens");
+    const vaultAccount = await getAccount(connection, vault);
+    assertSE(vaultAccount.amount, swapOutputAmount, "Vault should receive the swap output");
+    const depositorInputAccount = await getAccount(connection, depositorInputTA);
+    assertSE(depositorInputAccount.amount, 0, "Depositor should not keep any input tokens");
+
+    const spokeEvents = await readEventsUntilFound(connection, tx, [program]);
+    const depositEvent = spokeEvents.find((event) => event.name === "fundsDeposited")?.data;
+    assertSE(depositEvent.depositor, depositor.publicKey, "Depositor should match");
+    assertSE(depositEvent.inputToken, inputToken, "Input token should match");
+    assertSE(depositEvent.inputAmount, swapOutputAmount, "Input amount should be the swap output");
+    assertSE(depositEvent.destinationChainId, routeChainId, "Destination chain ID should match");
+
+    const peripheryEvents = await readEventsUntilFound(connection, tx, [peripheryProgram]);
+    const swapEvent = peripheryEvents.find((event) => event.name === "swapBeforeBridge")?.data;
+    assertSE(swapEvent.exchange, mockAmmProgram.programId, "Exchange should match");
+    assertSE(swapEvent.swapToken, swapToken, "Swap token should match");
+    assertSE(swapEvent.acrossInputToken, inputToken, "Across input token should match");
+    assertSE(swapEvent.swapTokenAmount, swapTokenAmount, "Swap token amount should match");
+    assertSE(swapEvent.acrossInputAmount, swapOutputAmount, "Across input amount should match");
+  });
+
+  it("Refunds swap tokens not consumed by the exchange", async () => {
+    const swapTokenAmount = 500_000;
+    const consumedAmount = 400_000;
+    const swapOutputAmount = 390_000;
+    const swapIx = await getSwapInstruction(consumedAmount, swapOutputAmount);
+
+    await swapAndBridge(swapTokenAmount, swapOutputAmount, swapIx);
+
+    const depositorSwapAccount = await getAccount(connection, depositorSwapTA);
+    assertSE(depositorSwapAccount.amount, seedBalance - consumedAmount, "Depositor should be refunded the leftover");
+    const swapTokenAccountData = await getAccount(connection, swapTokenAccount);
+    assertSE(swapTokenAccountData.amount, 0, "Periphery should not keep swap tokens");
+    const vaultAccount = await getAccount(connection, vault);
+    assertSE(vaultAccount.amount, swapOutputAmount, "Vault should receive the swap output");
+  });
+
+  it("Fails when the swap output is below the minimum expected input amount", async () => {
+    const swapTokenAmount = 500_000;
+    const swapOutputAmount = 490_000;
+    const swapIx = await getSwapInstruction(swapTokenAmount, swapOutputAmount);
+
+    try {
+      await swapAndBridge(swapTokenAmount, swapOutputAmount + 1, swapIx);
+      assert.fail("Swap and bridge should have failed due to slippage");
+    } catch (err: any) {
+      assert.include(err.toString(), "MinimumExpectedInputAmount", "Expected MinimumExpectedInputAmount error");
+    }
+  });
+
+  it("Fails when the exchange is the periphery program", async () => {
+    const swapIx = await getSwapInstruction(500_000, 490_000);
+    swapAndBridgeAccounts.exchange = peripheryProgram.programId;
+
+    try {
+      await swapAndBridge(500_000, 490_000, swapIx);
+      assert.fail("Swap and bridge should have failed due to invalid exchange");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidExchange", "Expected InvalidExchange error");
+    }
+  });
+});
@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { AnchorProvider, BN, Wallet, web3 } from "@coral-xyz/anchor";
 import {
   createMint,
+  freezeAccount,
   getAssociatedTokenAddressSync,
   getOrCreateAssociatedTokenAccount,
   mintTo,
@@ -23,6 +24,7 @@ import { common } from "./SvmSpoke.common";
 import { buildRelayerRefundMerkleTree, randomBigInt, readEvents, readProgramEvents } from "./utils";
 
 const { provider, program, owner, initializeState, connection, chainId, assertSE } = common;
+const { relayerRefundLeafCapacity, setCurrentTime } = common;
 
 describe("svm_spoke.bundle", () => {
   anchor.setProvider(provider);
@@ -98,7 +100,7 @@ describe("svm_spoke.bundle", () => {
     };
     try {
       await program.methods
-        .relayRootBundle(relayerRefundRootArray, slowRelayRootArray)
+        .relayRootBundle(relayerRefundRootArray, slowRelayRootArray, relayerRefundLeafCapacity)
         .accounts(relayRootBundleAccounts)
         .signers([nonOwner])
         .rpc();
@@ -110,7 +112,7 @@ describe("svm_spoke.bundle", () => {
     // Relay root bundle as owner
     relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
     await program.methods
-      .relayRootBundle(relayerRefundRootArray, slowRelayRootArray)
+      .relayRootBundle(relayerRefundRootArray, slowRelayRootArray, relayerRefundLeafCapacity)
       .accounts(relayRootBundleAccounts)
       .rpc();
 
@@ -148,7 +150,7 @@ describe("svm_spoke.bundle", () => {
       program: program.programId,
     };
     await program.methods
-      .relayRootBundle(relayerRefundRootArray2, slowRelayRootArray2)
+      .relayRootBundle(relayerRefundRootArray2, slowRelayRootArray2, relayerRefundLeafCapacity)
       .accounts(relayRootBundleAccounts)
       .rpc();
 
@@ -172,7 +174,7 @@ describe("svm_spoke.bundle", () => {
     // Relay root bundle as owner
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
     const tx = await program.methods
-      .relayRootBundle(relayerRefundRootArray, slowRelayRootArray)
+      .relayRootBundle(relayerRefundRootArray, slowRelayRootArray, relayerRefundLeafCapacity)
       .accounts(relayRootBundleAccounts)
       .rpc();
 
@@ -218,7 +220,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
       { pubkey: relayerTB, isWritable: true, isSigner: false },
@@ -355,7 +360,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
@@ -488,7 +496,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
@@ -583,7 +594,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
@@ -687,7 +701,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
@@ -749,7 +766,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
@@ -811,7 +831,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [{ pubkey: relayerTA, isWritable: true, isSigner: false }];
 
@@ -902,7 +925,7 @@ describe("svm_spoke.bundle", () => {
 
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
     await program.methods
-      .relayRootBundle(relayerRefundRootArray, slowRelayRootArray)
+      .relayRootBundle(relayerRefundRootArray, slowRelayRootArray, relayerRefundLeafCapacity)
       .accounts(relayRootBundleAccounts)
       .rpc();
 
@@ -972,7 +995,7 @@ describe("svm_spoke.bundle", () => {
       program: program.programId,
     };
     await program.methods
-      .relayRootBundle(newRelayerRefundRootArray, newSlowRelayRootArray)
+      .relayRootBundle(newRelayerRefundRootArray, newSlowRelayRootArray, relayerRefundLeafCapacity)
       .accounts(newRelayRootBundleAccounts)
       .rpc();
 
@@ -1057,7 +1080,10 @@ describe("svm_spoke.bundle", () => {
 
       // Relay root bundle
       const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-      await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+      await program.methods
+        .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+        .accounts(relayRootBundleAccounts)
+        .rpc();
 
       // Verify valid leaf
       const proofAsNumbers = proof.map((p) => Array.from(p));
@@ -1130,7 +1156,7 @@ describe("svm_spoke.bundle", () => {
       instructions.push(executeInstruction);
 
       // Execute using ALT.
-      await sendTransactionWithLookupTable(
+      const { txSignature } = await sendTransactionWithLookupTable(
         connection,
         instructions,
         (anchor.AnchorProvider.env().wallet as anchor.Wallet).payer
@@ -1150,6 +1176,16 @@ describe("svm_spoke.bundle", () => {
       refundBalances.forEach((balance, i) => {
         assertSE(balance, refundAmounts[i].toString(), `Refund account ${i} balance should match refund amount`);
       });
+
+      return txSignature;
+    };
+
+    const getComputeUnitsConsumed = async (txSignature: string) => {
+      const tx = await connection.getTransaction(txSignature, {
+        maxSupportedTransactionVersion: 0,
+        commitment: "confirmed",
+      });
+      return tx?.meta?.computeUnitsConsumed ?? 0;
     };
 
     it("Execute Max Refunds to Token Accounts", async () => {
@@ -1173,6 +1209,431 @@ describe("svm_spoke.bundle", () => {
 
       await executeMaxRefunds({ solanaDistributions, deferredRefunds: true, atomicAccountCreation: false });
     });
+
+    it("Benchmarks compute units per refund to Claim Accounts", async () => {
+      // Stored claim account bumps avoid the bump search in find_program_address for each refund.
+      const maxMarginalComputeUnits = 5_000;
+      const solanaDistributions = 28;
+
+      const singleRefundTx = await executeMaxRefunds({
+        solanaDistributions: 1,
+        deferredRefunds: true,
+        atomicAccountCreation: false,
+      });
+      const maxRefundsTx = await executeMaxRefunds({
+        solanaDistributions,
+        deferredRefunds: true,
+        atomicAccountCreation: false,
+      });
+
+      const singleRefundUnits = await getComputeUnitsConsumed(singleRefundTx);
+      const maxRefundsUnits = await getComputeUnitsConsumed(maxRefundsTx);
+      const marginalUnits = (maxRefundsUnits - singleRefundUnits) / (solanaDistributions - 1);
+      console.log(`Compute units per refund to claim accounts: ${marginalUnits.toFixed(0)}`);
+      assert.isAbove(singleRefundUnits, 0, "Compute units should be reported for the single refund transaction");
+      assert.isBelow(marginalUnits, maxMarginalComputeUnits, "Compute units per refund should stay below the budget");
+    });
+  });
+
+  it("Closes fully executed root bundle to the original payer", async () => {
+    // Odd leaf count so that rebuilding the relayer refund root on close also promotes the last node of a layer.
+    const relayerRefundLeaves: RelayerRefundLeafType[] = [0, 1, 2].map((leafId) => ({
+      isSolana: true,
+      leafId: new BN(leafId),
+      chainId: chainId,
+      amountToReturn: new BN(0),
+      mintPublicKey: mint,
+      refundAddresses: [relayerA.publicKey],
+      refundAmounts: [new BN(100000)],
+    }));
+    const merkleTree = new MerkleTree<RelayerRefundLeafType>(relayerRefundLeaves, relayerRefundHashFn);
+    const root = merkleTree.getRoot();
+
+    const stateAccountData = await program.account.state.fetch(state);
+    const rootBundleId = stateAccountData.rootBundleId;
+    const rootBundleIdBuffer = Buffer.alloc(4);
+    rootBundleIdBuffer.writeUInt32LE(rootBundleId);
+    const seeds = [Buffer.from("root_bundle"), seed.toArrayLike(Buffer, "le", 8), rootBundleIdBuffer];
+    const [rootBundle] = PublicKey.findProgramAddressSync(seeds, program.programId);
+
+    // Relay root bundle with the rent paid by another account than the owner.
+    const relayRootBundleAccounts = {
+      state,
+      rootBundle,
+      signer: owner,
+      payer: nonOwner.publicKey,
+      program: program.programId,
+    };
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(crypto.randomBytes(32)), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .signers([nonOwner])
+      .rpc();
+    let rootBundleData = await program.account.rootBundle.fetch(rootBundle);
+    assertSE(rootBundleData.payer, nonOwner.publicKey, "Root bundle payer should be recorded");
+    assertSE(
+      rootBundleData.slowFillDeadline,
+      stateAccountData.currentTime + stateAccountData.fillDeadlineBuffer,
+      "Slow fill deadline should be bounded by the fill deadline buffer"
+    );
+
+    // Leaf count is proven by passing the hashes of all the relayer refund leaves.
+    const leafHashes = relayerRefundLeaves.map((leaf) => Array.from(ethers.utils.arrayify(relayerRefundHashFn(leaf))));
+    const closeRootBundleAccounts = { signer: relayerA.publicKey, state, rootBundle, payer: nonOwner.publicKey };
+    const closeRootBundle = (relayerRefundLeafHashes = leafHashes) =>
+      program.methods
+        .closeRootBundle(rootBundleId, relayerRefundLeafHashes)
+        .accounts(closeRootBundleAccounts)
+        .signers([relayerA])
+        .rpc();
+
+    try {
+      await closeRootBundle([Array.from(crypto.randomBytes(32))]);
+      assert.fail("Closing should fail when the leaf hashes do not rebuild the relayer refund root");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidMerkleProof", "Expected invalid Merkle proof error");
+    }
+
+    try {
+      await closeRootBundle();
+      assert.fail("Closing should fail before all refund leaves are executed");
+    } catch (err: any) {
+      assert.include(err.toString(), "RootBundleRefundsNotExecuted", "Expected refunds not executed error");
+    }
+
+    // Only the owner can override the completion target.
+    try {
+      await program.methods
+        .setRootBundleCompletionTarget(rootBundleId, 0, 0)
+        .accounts({ signer: nonOwner.publicKey, state, rootBundle, program: program.programId })
+        .signers([nonOwner])
+        .rpc();
+      assert.fail("Non-owner should not be able to set the completion target");
+    } catch (err: any) {
+      assert.include(err.toString(), "NotOwner", "Expected owner check error");
+    }
+
+    for (const [i, leaf] of relayerRefundLeaves.entries()) {
+      // Executed leaves that are only a subset of all the leaves cannot be passed off as the complete set.
+      if (i > 0) {
+        try {
+          await closeRootBundle(leafHashes.slice(0, i));
+          assert.fail("Closing should fail when passing only the executed leaf hashes");
+        } catch (err: any) {
+          assert.include(err.toString(), "InvalidMerkleProof", "Expected invalid Merkle proof error");
+        }
+      }
+
+      // Nodes other than leaves can rebuild the root, but they do not match the hashes of the executed leaves.
+      if (i === 1) {
+        try {
+          await closeRootBundle([Array.from(root)]);
+          assert.fail("Closing should fail when passing the root as the only leaf");
+        } catch (err: any) {
+          assert.include(err.toString(), "RootBundleRefundsNotExecuted", "Expected refunds not executed error");
+        }
+      }
+
+      const proofAsNumbers = merkleTree.getProof(leaf).map((p) => Array.from(p));
+      await loadExecuteRelayerRefundLeafParams(
+        program,
+        owner,
+        rootBundleId,
+        leaf as RelayerRefundLeafSolana,
+        proofAsNumbers
+      );
+      await program.methods
+        .executeRelayerRefundLeaf()
+        .accounts({
+          signer: owner,
+          state,
+          rootBundle,
+          vault,
+          mint,
+          transferLiability,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: web3.SystemProgram.programId,
+          program: program.programId,
+        })
+        .remainingAccounts([{ pubkey: relayerTA, isWritable: true, isSigner: false }])
+        .rpc();
+    }
+    rootBundleData = await program.account.rootBundle.fetch(rootBundle);
+    assertSE(rootBundleData.claimedLeafCount, 3, "Claimed leaf count should be incremented");
+
+    try {
+      await closeRootBundle();
+      assert.fail("Closing should fail before the slow fill deadline has passed");
+    } catch (err: any) {
+      assert.include(
+        err.toString(),
+        "CanOnlyCloseRootBundleIfSlowFillDeadlinePassed",
+        "Expected slow fill deadline error"
+      );
+    }
+
+    // All slow fill leaves have expired.
+    await setCurrentTime(program, state, relayerA, new BN(rootBundleData.slowFillDeadline + 1));
+
+    // Rent can only be returned to the original payer.
+    try {
+      await program.methods
+        .closeRootBundle(rootBundleId, leafHashes)
+        .accounts({ ...closeRootBundleAccounts, payer: relayerA.publicKey })
+        .signers([relayerA])
+        .rpc();
+      assert.fail("Closing should fail when refunding rent to another account");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidRootBundlePayer", "Expected invalid payer error");
+    }
+
+    const rootBundleLamports = (await connection.getAccountInfo(rootBundle))!.lamports;
+    const iPayerLamports = await connection.getBalance(nonOwner.publicKey);
+    const tx = await closeRootBundle();
+
+    assert.isNull(await connection.getAccountInfo(rootBundle), "Root bundle should be closed");
+    const fPayerLamports = await connection.getBalance(nonOwner.publicKey);
+    assertSE(fPayerLamports - iPayerLamports, rootBundleLamports, "Payer should receive the root bundle rent");
+
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    const event = events.find((event) => event.name === "closedRootBundle")?.data;
+    assertSE(event.rootBundleId, rootBundleId, "rootBundleId should match");
+    assertSE(event.payer, nonOwner.publicKey, "payer should match");
+    assertSE(event.caller, relayerA.publicKey, "caller should match");
+  });
+
+  it("Executes relayer refund leaf in chunks", async () => {
+    const relayerC = Keypair.generate();
+    const relayerTC = (await getOrCreateAssociatedTokenAccount(connection, payer, mint, relayerC.publicKey)).address;
+    const refundAmounts = [new BN(300000), new BN(200000), new BN(100000)];
+    const relayerRefundLeaves: RelayerRefundLeafType[] = [
+      {
+        isSolana: true,
+        leafId: new BN(0),
+        chainId: chainId,
+        amountToReturn: new BN(0),
+        mintPublicKey: mint,
+        refundAddresses: [relayerA.publicKey, relayerB.publicKey, relayerC.publicKey],
+        refundAmounts,
+      },
+    ];
+    const merkleTree = new MerkleTree<RelayerRefundLeafType>(relayerRefundLeaves, relayerRefundHashFn);
+    const root = merkleTree.getRoot();
+    const leaf = relayerRefundLeaves[0] as RelayerRefundLeafSolana;
+    const proofAsNumbers = merkleTree.getProof(relayerRefundLeaves[0]).map((p) => Array.from(p));
+
+    const stateAccountData = await program.account.state.fetch(state);
+    const rootBundleId = stateAccountData.rootBundleId;
+    const rootBundleIdBuffer = Buffer.alloc(4);
+    rootBundleIdBuffer.writeUInt32LE(rootBundleId);
+    const seeds = [Buffer.from("root_bundle"), seed.toArrayLike(Buffer, "le", 8), rootBundleIdBuffer];
+    const [rootBundle] = PublicKey.findProgramAddressSync(seeds, program.programId);
+
+    const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
+
+    const leafIdBuffer = Buffer.alloc(4);
+    leafIdBuffer.writeUInt32LE(leaf.leafId.toNumber());
+    const [leafProgress] = PublicKey.findProgramAddressSync(
+      [Buffer.from("refund_leaf_progress"), seed.toArrayLike(Buffer, "le", 8), rootBundleIdBuffer, leafIdBuffer],
+      program.programId
+    );
+    const executeRelayerRefundLeafAccounts = {
+      signer: owner,
+      state,
+      rootBundle,
+      vault,
+      mint,
+      transferLiability,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      program: program.programId,
+    };
+    const executeChunk = async (refundTokenAccounts: PublicKey[], leafProgressPayer = owner) => {
+      await loadExecuteRelayerRefundLeafParams(program, owner, rootBundleId, leaf, proofAsNumbers);
+      return program.methods
+        .executeRelayerRefundLeafChunk()
+        .accounts({
+          execute: executeRelayerRefundLeafAccounts,
+          leafProgress,
+          leafProgressPayer,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .remainingAccounts(refundTokenAccounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+        .rpc();
+    };
+
+    const iRelayerABal = (await connection.getTokenAccountBalance(relayerTA)).value.amount;
+    const iRelayerBBal = (await connection.getTokenAccountBalance(relayerTB)).value.amount;
+
+    // First chunk pays out the first two refunds and claims the leaf.
+    let tx = await executeChunk([relayerTA, relayerTB]);
+    let events = await readEventsUntilFound(connection, tx, [program]);
+    let event = events.find((event) => event.name === "executedRelayerRefundChunk")?.data;
+    assertSE(event.rootBundleId, rootBundleId, "rootBundleId should match");
+    assertSE(event.startIndex, 0, "startIndex should match");
+    assertSE(event.refundAddresses.length, 2, "First chunk should pay out two refunds");
+    assert.isFalse(event.completed, "Leaf should not be completed after the first chunk");
+
+    assert.isUndefined(
+      events.find((event) => event.name === "executedRelayerRefundRoot"),
+      "Leaf should not be reported as executed before it completes"
+    );
+
+    const leafProgressData = await program.account.relayerRefundLeafProgress.fetch(leafProgress);
+    assertSE(leafProgressData.nextRefundIndex, 2, "Progress should point to the third refund");
+    assertSE(leafProgressData.payer, owner, "Progress payer should be the signer of the first chunk");
+    let rootBundleData = await program.account.rootBundle.fetch(rootBundle);
+    assertSE(rootBundleData.claimedLeafCount, 0, "Leaf should not be counted before it completes");
+
+    // The leaf in progress cannot be executed again in a single transaction.
+    try {
+      await loadExecuteRelayerRefundLeafParams(program, owner, rootBundleId, leaf, proofAsNumbers);
+      await program.methods
+        .executeRelayerRefundLeaf()
+        .accounts(executeRelayerRefundLeafAccounts)
+        .remainingAccounts(
+          [relayerTA, relayerTB, relayerTC].map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .rpc();
+      assert.fail("Leaf in progress should not be executed again");
+    } catch (err: any) {
+      assert.include(err.toString(), "Leaf already claimed!", "Expected claimed leaf error");
+    }
+
+    // The progress rent can only be refunded to the payer of the first chunk.
+    try {
+      await executeChunk([relayerTC], relayerA.publicKey);
+      assert.fail("Leaf progress rent should not be refunded to another account");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidRefundLeafProgressPayer", "Expected InvalidRefundLeafProgressPayer error");
+    }
+
+    // Second chunk pays out the remaining refund and completes the leaf.
+    tx = await executeChunk([relayerTC]);
+    events = await readEventsUntilFound(connection, tx, [program]);
+    event = events.find((event) => event.name === "executedRelayerRefundChunk")?.data;
+    assertSE(event.startIndex, 2, "startIndex should match");
+    assertSE(event.refundAddresses[0], relayerC.publicKey, "Refund address should match");
+    assert.isTrue(event.completed, "Leaf should be completed after the last chunk");
+
+    const rootEvent = events.find((event) => event.name === "executedRelayerRefundRoot")?.data;
+    assertSE(rootEvent.rootBundleId, rootBundleId, "rootBundleId should match");
+    assertSE(rootEvent.leafId, leaf.leafId, "leafId should match");
+    assertSE(rootEvent.refundAddresses.length, 3, "All refund addresses of the leaf should be reported");
+    assert.isFalse(rootEvent.deferredRefunds, "Refunds should not be deferred");
+
+    assert.isNull(await connection.getAccountInfo(leafProgress), "Leaf progress should be closed");
+    rootBundleData = await program.account.rootBundle.fetch(rootBundle);
+    assertSE(rootBundleData.claimedLeafCount, 1, "Leaf should be counted once completed");
+
+    const fRelayerABal = (await connection.getTokenAccountBalance(relayerTA)).value.amount;
+    const fRelayerBBal = (await connection.getTokenAccountBalance(relayerTB)).value.amount;
+    const fRelayerCBal = (await connection.getTokenAccountBalance(relayerTC)).value.amount;
+    assertSE(BigInt(fRelayerABal) - BigInt(iRelayerABal), refundAmounts[0], "Relayer A bal");
+    assertSE(BigInt(fRelayerBBal) - BigInt(iRelayerBBal), refundAmounts[1], "Relayer B bal");
+    assertSE(fRelayerCBal, refundAmounts[2], "Relayer C bal");
+
+    // Completed leaf cannot be executed again in chunks.
+    try {
+      await executeChunk([relayerTA]);
+      assert.fail("Completed leaf should not be executed again");
+    } catch (err: any) {
+      assert.include(err.toString(), "Leaf already claimed!", "Expected claimed leaf error");
+    }
+  });
+
+  it("Executes relayer refund leaf in hybrid mode deferring unusable token accounts", async () => {
+    // Relayer D has no ATA and relayer E has a frozen ATA, so both refunds are deferred to claim accounts.
+    const relayerD = Keypair.generate();
+    const relayerE = Keypair.generate();
+    const relayerTD = getAssociatedTokenAddressSync(mint, relayerD.publicKey);
+    const relayerTE = (await getOrCreateAssociatedTokenAccount(connection, payer, mint, relayerE.publicKey)).address;
+    await freezeAccount(connection, payer, relayerTE, mint, payer);
+    const [claimAccountD] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim_account"), mint.toBuffer(), relayerD.publicKey.toBuffer()],
+      program.programId
+    );
+    const [claimAccountE] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim_account"), mint.toBuffer(), relayerE.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods.initializeClaimAccount().accounts({ mint, refundAddress: relayerD.publicKey }).rpc();
+    await program.methods.initializeClaimAccount().accounts({ mint, refundAddress: relayerE.publicKey }).rpc();
+
+    const refundAmounts = [new BN(300000), new BN(200000), new BN(100000)];
+    const relayerRefundLeaves: RelayerRefundLeafType[] = [
+      {
+        isSolana: true,
+        leafId: new BN(0),
+        chainId: chainId,
+        amountToReturn: new BN(0),
+        mintPublicKey: mint,
+        refundAddresses: [relayerA.publicKey, relayerD.publicKey, relayerE.publicKey],
+        refundAmounts,
+      },
+    ];
+    const merkleTree = new MerkleTree<RelayerRefundLeafType>(relayerRefundLeaves, relayerRefundHashFn);
+    const root = merkleTree.getRoot();
+    const leaf = relayerRefundLeaves[0] as RelayerRefundLeafSolana;
+    const proofAsNumbers = merkleTree.getProof(relayerRefundLeaves[0]).map((p) => Array.from(p));
+
+    const stateAccountData = await program.account.state.fetch(state);
+    const rootBundleId = stateAccountData.rootBundleId;
+    const rootBundleIdBuffer = Buffer.alloc(4);
+    rootBundleIdBuffer.writeUInt32LE(rootBundleId);
+    const seeds = [Buffer.from("root_bundle"), seed.toArrayLike(Buffer, "le", 8), rootBundleIdBuffer];
+    const [rootBundle] = PublicKey.findProgramAddressSync(seeds, program.programId);
+
+    const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
+
+    const executeRelayerRefundLeafAccounts = {
+      signer: owner,
+      state,
+      rootBundle,
+      vault,
+      mint,
+      transferLiability,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      program: program.programId,
+    };
+    const iRelayerABal = (await connection.getTokenAccountBalance(relayerTA)).value.amount;
+
+    // Claim accounts of the deferred refunds follow the refund token accounts.
+    await loadExecuteRelayerRefundLeafParams(program, owner, rootBundleId, leaf, proofAsNumbers);
+    const tx = await program.methods
+      .executeRelayerRefundLeafHybrid()
+      .accounts(executeRelayerRefundLeafAccounts)
+      .remainingAccounts(
+        [relayerTA, relayerTD, relayerTE, claimAccountD, claimAccountE].map((pubkey) => ({
+          pubkey,
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .rpc();
+
+    const fRelayerABal = (await connection.getTokenAccountBalance(relayerTA)).value.amount;
+    assertSE(BigInt(fRelayerABal) - BigInt(iRelayerABal), refundAmounts[0], "Relayer A should be paid directly");
+    const claimAccountDData = await program.account.claimAccount.fetch(claimAccountD);
+    assertSE(claimAccountDData.amount, refundAmounts[1], "Relayer D refund should be deferred");
+    const claimAccountEData = await program.account.claimAccount.fetch(claimAccountE);
+    assertSE(claimAccountEData.amount, refundAmounts[2], "Relayer E refund should be deferred");
+
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    const event = events.find((event) => event.name === "executedRelayerRefundRoot")?.data;
+    assert.isFalse(event.deferredRefunds, "deferredRefunds should be false");
+    assertSE(event.deferredRefundAddresses.length, 2, "Two refunds should be deferred");
+    assertSE(event.deferredRefundAddresses[0], relayerD.publicKey, "Relayer D should be deferred");
+    assertSE(event.deferredRefundAddresses[1], relayerE.publicKey, "Relayer E should be deferred");
   });
 
   it("Increments pending amount to HubPool", async () => {
@@ -1200,7 +1661,10 @@ describe("svm_spoke.bundle", () => {
       const seeds = [Buffer.from("root_bundle"), seed.toArrayLike(Buffer, "le", 8), rootBundleIdBuffer];
       const [rootBundle] = PublicKey.findProgramAddressSync(seeds, program.programId);
       let relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-      await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+      await program.methods
+        .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+        .accounts(relayRootBundleAccounts)
+        .rpc();
       const proofAsNumbers = proof.map((p) => Array.from(p));
       const executeRelayerRefundLeafAccounts = {
         state: state,
@@ -1248,7 +1712,7 @@ describe("svm_spoke.bundle", () => {
     const relayerRefundAmount = new BN(100000);
 
     // Generate 10 sequential leaves. This exceeds 1 claimed bitmap byte so we can test claiming lower index after
-    // higher index does not shrink root_bundle account size.
+    // higher index within the pre-sized claimed bitmap.
     const numberOfRefunds = 10;
     for (let i = 0; i < numberOfRefunds; i++) {
       relayerRefundLeaves.push({
@@ -1276,7 +1740,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [{ pubkey: relayerTA, isWritable: true, isSigner: false }];
 
@@ -1319,6 +1786,77 @@ describe("svm_spoke.bundle", () => {
     assert.strictEqual(BigInt(fRelayerABal) - BigInt(iRelayerABal), BigInt(totalRefund), "Relayer A bal");
   });
 
+  it("Executes leaves within the pre-sized claimed bitmap only", async () => {
+    // Leaf ID 8 does not fit in the single claimed bitmap byte of the root bundle relayed below.
+    const relayerRefundLeaves: RelayerRefundLeafType[] = [0, 8].map((leafId) => ({
+      isSolana: true,
+      leafId: new BN(leafId),
+      chainId: chainId,
+      amountToReturn: new BN(0),
+      mintPublicKey: mint,
+      refundAddresses: [relayerA.publicKey],
+      refundAmounts: [new BN(100000)],
+    }));
+    const merkleTree = new MerkleTree<RelayerRefundLeafType>(relayerRefundLeaves, relayerRefundHashFn);
+    const root = merkleTree.getRoot();
+    const proof = relayerRefundLeaves.map((leaf) => merkleTree.getProof(leaf).map((p) => Array.from(p)));
+
+    const stateAccountData = await program.account.state.fetch(state);
+    const rootBundleIdBuffer = Buffer.alloc(4);
+    rootBundleIdBuffer.writeUInt32LE(stateAccountData.rootBundleId);
+    const seeds = [Buffer.from("root_bundle"), seed.toArrayLike(Buffer, "le", 8), rootBundleIdBuffer];
+    const [rootBundle] = PublicKey.findProgramAddressSync(seeds, program.programId);
+
+    const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), 8)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
+    const initialRootBundleSize = (await connection.getAccountInfo(rootBundle))!.data.length;
+
+    const executeRelayerRefundLeafAccounts = {
+      state,
+      rootBundle,
+      signer: owner,
+      vault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      mint,
+      transferLiability,
+      systemProgram: web3.SystemProgram.programId,
+      program: program.programId,
+    };
+    const remainingAccounts = [{ pubkey: relayerTA, isWritable: true, isSigner: false }];
+    const executeLeaf = async (index: number) => {
+      const leaf = relayerRefundLeaves[index] as RelayerRefundLeafSolana;
+      await loadExecuteRelayerRefundLeafParams(program, owner, stateAccountData.rootBundleId, leaf, proof[index]);
+      await program.methods
+        .executeRelayerRefundLeaf()
+        .accounts(executeRelayerRefundLeafAccounts)
+        .remainingAccounts(remainingAccounts)
+        .rpc();
+    };
+
+    await executeLeaf(0);
+    assertSE(
+      (await connection.getAccountInfo(rootBundle))!.data.length,
+      initialRootBundleSize,
+      "Executing a leaf should not reallocate the root bundle"
+    );
+
+    // Executors never pay rent, so leaves beyond the capacity declared on relay cannot be executed.
+    try {
+      await executeLeaf(1);
+      assert.fail("Leaf beyond the claimed bitmap capacity should not be executed");
+    } catch (err: any) {
+      assert.include(err.toString(), "ClaimedBitmapIndexOutOfRange", "Expected ClaimedBitmapIndexOutOfRange error");
+    }
+    assertSE(
+      (await connection.getAccountInfo(rootBundle))!.data.length,
+      initialRootBundleSize,
+      "Executing a leaf beyond the capacity should not reallocate the root bundle"
+    );
+  });
+
   it("Invalid Merkle Leaf should fail", async () => {
     // Create invalid leaf with missing refund amount for the second relayer.
     const relayerRefundLeaves: RelayerRefundLeafType[] = [];
@@ -1350,7 +1888,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
@@ -1440,7 +1981,10 @@ describe("svm_spoke.bundle", () => {
 
       // Relay root bundle
       const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-      await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+      await program.methods
+        .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+        .accounts(relayRootBundleAccounts)
+        .rpc();
 
       // Pass refund addresses in remaining accounts.
       const remainingAccounts = [
@@ -1527,7 +2071,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [{ pubkey: relayerTA, isWritable: true, isSigner: false }];
 
@@ -1619,7 +2166,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
@@ -1710,7 +2260,10 @@ describe("svm_spoke.bundle", () => {
 
     // Relay root bundle
     let relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     const remainingAccounts = [
       { pubkey: relayerTA, isWritable: true, isSigner: false },
@@ -1822,7 +2375,10 @@ describe("svm_spoke.bundle", () => {
 
       // Relay root bundle
       const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-      await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+      await program.methods
+        .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+        .accounts(relayRootBundleAccounts)
+        .rpc();
 
       // Verify valid leaf
       const proofAsNumbers = proof.map((p) => Array.from(p));
@@ -0,0 +1,127 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID, createMint, getAccount, mintTo } from "@solana/spl-token";
+import { PublicKey } from "@solana/web3.js";
+import { ExampleIntegrator } from "../../target/types/example_integrator";
+import { readEventsUntilFound } from "../../src/svm/web3-v1";
+import { common } from "./SvmSpoke.common";
+
+const { provider, connection, program, owner, seedBalance, initializeState, depositData } = common;
+const { createRoutePda, getVaultAta, assert, assertSE } = common;
+
+describe("svm_spoke.cpi_deposit", () => {
+  anchor.setProvider(provider);
+
+  const integratorProgram = anchor.workspace.ExampleIntegrator as Program<ExampleIntegrator>;
+
+  const payer = (anchor.AnchorProvider.env().wallet as anchor.Wallet).payer;
+  const tokenDecimals = 6;
+  const routeChainId = new BN(1);
+
+  let state: PublicKey, seed: BN, route: PublicKey, vault: PublicKey, inputToken: PublicKey;
+  let vaultAuthority: PublicKey, vaultTokenAccount: PublicKey;
+  let depositFromVaultAccounts: any;
+
+  const vaultDepositParams = () => ({
+    recipient: depositData.recipient,
+    outputToken: depositData.outputToken,
+    inputAmount: depositData.inputAmount,
+    outputAmount: depositData.outputAmount,
+    destinationChainId: routeChainId,
+    exclusiveRelayer: depositData.exclusiveRelayer,
+    quoteTimestamp: depositData.quoteTimestamp,
+    fillDeadline: depositData.fillDeadline,
+    exclusivityParameter: depositData.exclusivityParameter,
+    message: depositData.message,
+  });
+
+  beforeEach(async () => {
+    ({ state, seed } = await initializeState());
+
+    inputToken = await createMint(connection, payer, owner, owner, tokenDecimals);
+    route = createRoutePda(inputToken, seed, routeChainId);
+    vault = await getVaultAta(inputToken, state);
+    await program.methods
+      .setEnableRoute(inputToken, routeChainId, true)
+      .accounts({
+        signer: owner,
+        payer: owner,
+        state,
+        route,
+        vault,
+        originTokenMint: inputToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // The integrator holds deposited tokens in a PDA token account that is not an ATA.
+    [vaultAuthority] = PublicKey.findProgramAddressSync([Buffer.from("vault_authority")], integratorProgram.programId);
+    [vaultTokenAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_token_account"), inputToken.toBuffer()],
+      integratorProgram.programId
+    );
+    const initializeVaultAccounts = {
+      signer: owner,
+      vaultAuthority,
+      mint: inputToken,
+      vaultTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+    await integratorProgram.methods.initializeVault().accounts(initializeVaultAccounts).rpc();
+    await mintTo(connection, payer, inputToken, vaultTokenAccount, owner, seedBalance);
+
+    const [spokeEventAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("__event_authority")],
+      program.programId
+    );
+    depositFromVaultAccounts = {
+      vaultAuthority,
+      mint: inputToken,
+      vaultTokenAccount,
+      spokeState: state,
+      spokeRoute: route,
+      spokeVault: vault,
+      spokeEventAuthority,
+      spokeProgram: program.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  });
+
+  it("PDA controlled vault deposits via CPI", async () => {
+    const tx = await integratorProgram.methods
+      .depositFromVault(vaultDepositParams())
+      .accounts(depositFromVaultAccounts)
+      .rpc();
+
+    const vaultTokenAccountData = await getAccount(connection, vaultTokenAccount);
+    assertSE(
+      vaultTokenAccountData.amount,
+      seedBalance - depositData.inputAmount.toNumber(),
+      "Integrator vault balance should be reduced by the deposited amount"
+    );
+    const vaultAccount = await getAccount(connection, vault);
+    assertSE(vaultAccount.amount, depositData.inputAmount, "Spoke vault should receive the deposited amount");
+
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    const event = events.find((event) => event.name === "fundsDeposited")?.data;
+    assertSE(event.depositor, vaultAuthority, "Depositor should be the integrator vault authority PDA");
+    assertSE(event.inputToken, inputToken, "Input token should match");
+    assertSE(event.inputAmount, depositData.inputAmount, "Input amount should match");
+    assertSE(event.destinationChainId, routeChainId, "Destination chain ID should match");
+  });
+
+  it("Fails to deposit more than the vault balance via CPI", async () => {
+    try {
+      await integratorProgram.methods
+        .depositFromVault({ ...vaultDepositParams(), inputAmount: new BN(seedBalance + 1) })
+        .accounts(depositFromVaultAccounts)
+        .rpc();
+      assert.fail("Deposit should have failed due to insufficient funds");
+    } catch (err: any) {
+      assert.include(err.toString(), "insufficient funds", "Expected insufficient funds error");
+    }
+  });
+});
@@ -16,6 +16,7 @@ import {
   ExtensionType,
   TOKEN_2022_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
+  createAccount,
   createApproveCheckedInstruction,
   createEnableCpiGuardInstruction,
   createMint,
@@ -230,6 +231,149 @@ describe("svm_spoke.deposit", () => {
     assertSE(u8Array32ToBigNumber(event.depositId), BigNumber.from(2), `depositId should recover to 2`);
   });
 
+  const registerIntegrator = async (integratorId: number) => {
+    const integratorIdBuffer = Buffer.alloc(2);
+    integratorIdBuffer.writeUInt16LE(integratorId);
+    const integrator = PublicKey.findProgramAddressSync(
+      [Buffer.from("integrator"), seed.toArrayLike(Buffer, "le", 8), integratorIdBuffer],
+      program.programId
+    )[0];
+    await program.methods
+      .registerIntegrator(integratorId, "Test Integrator", "https://example.com/integrator.json")
+      .accounts({ signer: owner, state, integrator, program: program.programId })
+      .rpc();
+    return integrator;
+  };
+
+  const integratorDeposit = async (depositIx: anchor.web3.TransactionInstruction) => {
+    const approveIx = await createApproveCheckedInstruction(
+      depositAccounts.depositorTokenAccount,
+      depositAccounts.mint,
+      depositAccounts.state,
+      depositor.publicKey,
+      BigInt(depositData.inputAmount.toString()),
+      tokenDecimals,
+      undefined,
+      tokenProgram
+    );
+    return sendAndConfirmTransaction(connection, new Transaction().add(approveIx, depositIx), [payer, depositor]);
+  };
+
+  const assertIntegratorDeposited = async (tx: string, integratorId: number) => {
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    const depositEvent = events.find((event) => event.name === "fundsDeposited")?.data;
+    const integratorEvent = events.find((event) => event.name === "integratorDeposited")?.data;
+    assert.isDefined(depositEvent, "FundsDeposited event should be emitted");
+    assert.isDefined(integratorEvent, "IntegratorDeposited event should be emitted");
+
+    assertSE(integratorEvent.depositId, depositEvent.depositId, "Deposit ID should match FundsDeposited event");
+    assertSE(integratorEvent.integratorId, integratorId, "Integrator ID should match");
+    assertSE(integratorEvent.depositor, depositor.publicKey, "Depositor should match");
+  };
+
+  it("Emits IntegratorDeposited companion event for deposits tagged with an integrator", async () => {
+    const integratorId = 42;
+    const integrator = await registerIntegrator(integratorId);
+    const depositDataValues = Object.values(depositData) as DepositDataValues;
+    const depositIx = await program.methods
+      .depositWithIntegrator(...depositDataValues, integratorId)
+      .accounts(depositAccounts)
+      .remainingAccounts([{ pubkey: integrator, isWritable: false, isSigner: false }])
+      .instruction();
+    const tx = await integratorDeposit(depositIx);
+
+    await assertIntegratorDeposited(tx, integratorId);
+  });
+
+  it("Emits IntegratorDeposited companion event for integrator tagged deposit_now and unsafe_deposit", async () => {
+    const integratorId = 43;
+    const integrator = await registerIntegrator(integratorId);
+    const integratorAccounts = [{ pubkey: integrator, isWritable: false, isSigner: false }];
+
+    const depositNowIx = await program.methods
+      .depositNowWithIntegrator(
+        depositData.depositor!,
+        depositData.recipient!,
+        depositData.inputToken!,
+        depositData.outputToken!,
+        depositData.inputAmount,
+        depositData.outputAmount,
+        depositData.destinationChainId,
+        depositData.exclusiveRelayer!,
+        60,
+        0,
+        depositData.message,
+        integratorId
+      )
+      .accounts(depositAccounts)
+      .remainingAccounts(integratorAccounts)
+      .instruction();
+    await assertIntegratorDeposited(await integratorDeposit(depositNowIx), integratorId);
+
+    const unsafeDepositIx = await program.methods
+      .unsafeDepositWithIntegrator(
+        depositData.depositor!,
+        depositData.recipient!,
+        depositData.inputToken!,
+        depositData.outputToken!,
+        depositData.inputAmount,
+        depositData.outputAmount,
+        depositData.destinationChainId,
+        depositData.exclusiveRelayer!,
+        new BN(1),
+        depositData.quoteTimestamp.toNumber(),
+        depositData.fillDeadline.toNumber(),
+        depositData.exclusivityParameter.toNumber(),
+        depositData.message,
+        integratorId
+      )
+      .accounts(depositAccounts)
+      .remainingAccounts(integratorAccounts)
+      .instruction();
+    await assertIntegratorDeposited(await integratorDeposit(unsafeDepositIx), integratorId);
+  });
+
+  it("Cannot tag deposits with an unregistered integrator", async () => {
+    const registeredIntegrator = await registerIntegrator(44);
+    const depositDataValues = Object.values(depositData) as DepositDataValues;
+
+    // The registry PDA of another integrator ID cannot be used to attribute the deposit.
+    try {
+      const depositIx = await program.methods
+        .depositWithIntegrator(...depositDataValues, 45)
+        .accounts(depositAccounts)
+        .remainingAccounts([{ pubkey: registeredIntegrator, isWritable: false, isSigner: false }])
+        .instruction();
+      await integratorDeposit(depositIx);
+      assert.fail("Deposit tagged with an unregistered integrator should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "UnregisteredIntegrator", "Expected UnregisteredIntegrator error");
+    }
+
+    // The integrator PDA must be passed to attribute the deposit.
+    try {
+      const depositIx = await program.methods
+        .depositWithIntegrator(...depositDataValues, 44)
+        .accounts(depositAccounts)
+        .instruction();
+      await integratorDeposit(depositIx);
+      assert.fail("Deposit tagged without the integrator account should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "AccountNotEnoughKeys", "Expected AccountNotEnoughKeys error");
+    }
+  });
+
+  it("Does not emit IntegratorDeposited event for untagged deposits", async () => {
+    const depositDataValues = Object.values(depositData) as DepositDataValues;
+    const tx = await approvedDeposit(depositDataValues);
+
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    assert.isUndefined(
+      events.find((event) => event.name === "integratorDeposited"),
+      "IntegratorDeposited event should not be emitted"
+    );
+  });
+
   it("Deposit with deadline before current time succeeds", async () => {
     const currentTime = await getCurrentTime(program, state);
 
@@ -713,6 +857,73 @@ describe("svm_spoke.deposit", () => {
     }
   });
 
+  it("Deposits from a depositor token account that is not an ATA", async () => {
+    // Program controlled depositors hold tokens in arbitrary token accounts, so the depositor ATA is not required.
+    const depositorTokenAccount = await createAccount(
+      connection,
+      payer,
+      inputToken,
+      depositor.publicKey,
+      Keypair.generate(),
+      undefined,
+      tokenProgram
+    );
+    await mintTo(connection, payer, inputToken, depositorTokenAccount, owner, seedBalance, [], undefined, tokenProgram);
+
+    const depositDataValues = Object.values(depositData) as DepositDataValues;
+    await approvedDeposit(depositDataValues, { ...depositAccounts, depositorTokenAccount });
+
+    const depositorAccount = await getAccount(connection, depositorTokenAccount, undefined, tokenProgram);
+    assertSE(
+      depositorAccount.amount,
+      seedBalance - depositData.inputAmount.toNumber(),
+      "Depositor's non-ATA balance should be reduced by the deposited amount"
+    );
+    const vaultAccount = await getAccount(connection, vault, undefined, tokenProgram);
+    assertSE(vaultAccount.amount, depositData.inputAmount, "Vault balance should be increased by the deposited amount");
+  });
+
+  it("Fails to deposit from a token account not owned by the depositor", async () => {
+    // Delegating to the state PDA is not enough, the pulled token account must still be owned by the depositor.
+    const otherOwner = Keypair.generate();
+    const otherTokenAccount = await createAccount(
+      connection,
+      payer,
+      inputToken,
+      otherOwner.publicKey,
+      Keypair.generate(),
+      undefined,
+      tokenProgram
+    );
+    await mintTo(connection, payer, inputToken, otherTokenAccount, owner, seedBalance, [], undefined, tokenProgram);
+    const approveIx = await createApproveCheckedInstruction(
+      otherTokenAccount,
+      inputToken,
+      state,
+      otherOwner.publicKey,
+      BigInt(depositData.inputAmount.toString()),
+      tokenDecimals,
+      undefined,
+      tokenProgram
+    );
+    const depositDataValues = Object.values(depositData) as DepositDataValues;
+    const depositIx = await program.methods
+      .deposit(...depositDataValues)
+      .accounts({ ...depositAccounts, depositorTokenAccount: otherTokenAccount })
+      .instruction();
+
+    try {
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, depositIx), [
+        payer,
+        depositor,
+        otherOwner,
+      ]);
+      assert.fail("Deposit from a token account not owned by the depositor should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "ConstraintTokenOwner", "Expected ConstraintTokenOwner error");
+    }
+  });
+
   describe("codama client and solana kit", () => {
     it("Deposit with with solana kit and codama client", async () => {
       // typescript is not happy with the depositData object
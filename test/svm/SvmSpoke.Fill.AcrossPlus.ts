@@ -1,8 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
-import { BN, Program } from "@coral-xyz/anchor";
+import { BN, BorshCoder, EventParser, Program } from "@coral-xyz/anchor";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   createMint,
   getOrCreateAssociatedTokenAccount,
   mintTo,
@@ -12,6 +13,7 @@ import {
   createAssociatedTokenAccountInstruction,
   getMinimumBalanceForRentExemptAccount,
   createApproveCheckedInstruction,
+  createBurnCheckedInstruction,
 } from "@solana/spl-token";
 import {
   PublicKey,
@@ -21,6 +23,7 @@ import {
   sendAndConfirmTransaction,
   Transaction,
   ComputeBudgetProgram,
+  BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
 } from "@solana/web3.js";
 import {
   calculateRelayHashUint8Array,
@@ -29,12 +32,17 @@ import {
   sendTransactionWithLookupTable,
   loadFillRelayParams,
   intToU8Array32,
+  readEventsUntilFound,
+  ACROSS_PLUS_MESSAGE_PREFIX,
+  ACROSS_PLUS_MESSAGE_V2,
+  compileMulticallMessage,
+  HANDLER_SIGNER_PLACEHOLDER,
 } from "../../src/svm/web3-v1";
 import { MulticallHandler } from "../../target/types/multicall_handler";
 import { common } from "./SvmSpoke.common";
-import { FillDataParams, FillDataValues } from "../../src/types/svm";
+import { AcrossPlusMessage, FillDataParams, FillDataValues, MulticallAssertion } from "../../src/types/svm";
 const { provider, connection, program, owner, chainId, seedBalance } = common;
-const { initializeState, assertSE } = common;
+const { initializeState, setCurrentTime, assertSE, assert } = common;
 
 describe("svm_spoke.fill.across_plus", () => {
   anchor.setProvider(provider);
@@ -53,6 +61,7 @@ describe("svm_spoke.fill.across_plus", () => {
 
   const relayAmount = 500000;
   const mintDecimals = 6;
+  const executionWindow = 86400; // Matches PENDING_RELAY_MESSAGE_EXECUTION_WINDOW.
   let relayData: any; // reused relay data for all tests.
   let accounts: any; // Store accounts to simplify contract interactions.
 
@@ -97,7 +106,7 @@ describe("svm_spoke.fill.across_plus", () => {
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
 
     // Prepare fill instruction.
-    const fillV3RelayValues: FillDataValues = [relayHash, relayData, new BN(1), relayer.publicKey];
+    const fillV3RelayValues: FillDataValues = [relayHash, relayData, new BN(1), relayer.publicKey, null];
     if (bufferParams) {
       await loadFillRelayParams(program, relayer, fillV3RelayValues[1], fillV3RelayValues[2], fillV3RelayValues[3]);
       [accounts.instructionParams] = PublicKey.findProgramAddressSync(
@@ -106,7 +115,7 @@ describe("svm_spoke.fill.across_plus", () => {
       );
     }
     const fillV3RelayParams: FillDataParams = bufferParams
-      ? [fillV3RelayValues[0], null, null, null]
+      ? [fillV3RelayValues[0], null, null, null, null]
       : fillV3RelayValues;
     const fillIx = await program.methods
       .fillRelay(...fillV3RelayParams)
@@ -201,6 +210,608 @@ describe("svm_spoke.fill.across_plus", () => {
     );
   });
 
+  it("Injects the handler token balance into the invoked message call", async () => {
+    const iHandlerBal = (await getAccount(connection, handlerATA)).amount;
+
+    // Transfer amount is a placeholder that gets replaced with the handler balance before the call.
+    const transferIx = createTransferCheckedInstruction(
+      handlerATA,
+      mint,
+      finalRecipientATA,
+      handlerSigner,
+      0,
+      mintDecimals
+    );
+    const transferAmountOffset = 1; // TransferChecked data starts with 1 byte instruction discriminator.
+    const multicallHandlerCoder = new MulticallHandlerCoder([transferIx], undefined, {
+      balanceInjections: [{ ixIndex: 0, tokenAccount: handlerATA, offset: transferAmountOffset }],
+    });
+
+    const encodedMessage = new AcrossPlusMessageCoder({
+      handler: handlerProgram.programId,
+      readOnlyLen: multicallHandlerCoder.readOnlyLen,
+      valueAmount: new BN(0),
+      accounts: multicallHandlerCoder.compiledMessage.accountKeys,
+      handlerMessage: multicallHandlerCoder.encode(),
+    }).encode();
+    updateRelayData({ ...relayData, message: encodedMessage });
+
+    const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+    await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+
+    assertSE(
+      (await getAccount(connection, finalRecipientATA)).amount,
+      iHandlerBal + BigInt(relayAmount),
+      "Final recipient should receive the full handler balance"
+    );
+    assertSE((await getAccount(connection, handlerATA)).amount, 0, "Handler balance should be forwarded");
+  });
+
+  describe("Leftover handler balances", async () => {
+    // Encodes a message that only forwards half of the relayed tokens to the final recipient.
+    const encodePartialTransfer = (extraInstructions: TransactionInstruction[] = [], fallbackRecipient?: PublicKey) => {
+      const transferIx = createTransferCheckedInstruction(
+        handlerATA,
+        mint,
+        finalRecipientATA,
+        handlerSigner,
+        relayAmount / 2,
+        mintDecimals
+      );
+      const multicallHandlerCoder = new MulticallHandlerCoder([transferIx, ...extraInstructions], undefined, {
+        fallbackRecipient,
+      });
+      const encodedMessage = new AcrossPlusMessageCoder({
+        handler: handlerProgram.programId,
+        readOnlyLen: multicallHandlerCoder.readOnlyLen,
+        valueAmount: new BN(0),
+        accounts: multicallHandlerCoder.compiledMessage.accountKeys,
+        handlerMessage: multicallHandlerCoder.encode(),
+      }).encode();
+      updateRelayData({ ...relayData, message: encodedMessage });
+      return multicallHandlerCoder;
+    };
+
+    const fillAndAssertDrained = async (multicallHandlerCoder: MulticallHandlerCoder, iHandlerBal: bigint) => {
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        iHandlerBal + BigInt(relayAmount),
+        "Final recipient should receive the leftover handler balance"
+      );
+      assertSE((await getAccount(connection, handlerATA)).amount, 0, "Handler balance should be drained");
+    };
+
+    it("Drains leftover tokens as a message step", async () => {
+      const iHandlerBal = (await getAccount(connection, handlerATA)).amount;
+
+      const drainIx = await handlerProgram.methods
+        .drainLeftoverTokens(null)
+        .accounts({ handlerSigner, recipient: finalRecipient })
+        .remainingAccounts([
+          { pubkey: handlerATA, isSigner: false, isWritable: true },
+          { pubkey: mint, isSigner: false, isWritable: false },
+          { pubkey: finalRecipientATA, isSigner: false, isWritable: true },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ])
+        .instruction();
+      const multicallHandlerCoder = encodePartialTransfer([drainIx]);
+
+      await fillAndAssertDrained(multicallHandlerCoder, iHandlerBal);
+    });
+
+    it("Drains leftover tokens to the fallback recipient", async () => {
+      const iHandlerBal = (await getAccount(connection, handlerATA)).amount;
+
+      const multicallHandlerCoder = encodePartialTransfer([], finalRecipient);
+
+      await fillAndAssertDrained(multicallHandlerCoder, iHandlerBal);
+    });
+
+    it("Skips draining tokens when the fallback recipient token account is not passed", async () => {
+      const iHandlerBal = (await getAccount(connection, handlerATA)).amount;
+      const fallbackRecipient = Keypair.generate().publicKey;
+      const fallbackRecipientATA = getAssociatedTokenAddressSync(mint, fallbackRecipient);
+
+      const multicallHandlerCoder = encodePartialTransfer([], fallbackRecipient);
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      const tx = await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+
+      // The fill succeeds and the leftover tokens stay with the handler instead of reverting the whole fill.
+      assertSE(
+        (await getAccount(connection, handlerATA)).amount,
+        iHandlerBal + BigInt(relayAmount / 2),
+        "Leftover handler balance should not be drained"
+      );
+      const logs = (await connection.getTransaction(tx, { commitment: "confirmed", maxSupportedTransactionVersion: 0 }))
+        ?.meta?.logMessages;
+      const eventParser = new EventParser(handlerProgram.programId, new BorshCoder(handlerProgram.idl));
+      const event = Array.from(eventParser.parseLogs(logs ?? [])).find(
+        (event) => event.name === "leftoverTokensNotDrained"
+      )?.data;
+      assert.isDefined(event, "LeftoverTokensNotDrained event should be emitted");
+      assertSE(event.tokenAccount, handlerATA, "Token account should match");
+      assertSE(event.recipientTokenAccount, fallbackRecipientATA, "Recipient token account should match");
+      assertSE(event.amount, iHandlerBal + BigInt(relayAmount / 2), "Amount should match");
+    });
+  });
+
+  describe("Scoped handler signers", async () => {
+    let signerScope: Uint8Array, scopedHandlerSigner: PublicKey, scopedHandlerATA: PublicKey;
+
+    beforeEach(async () => {
+      signerScope = Keypair.generate().publicKey.toBytes(); // Can be any salt chosen by the depositor.
+      scopedHandlerSigner = MulticallHandlerCoder.handlerSigner(handlerProgram.programId, signerScope);
+      scopedHandlerATA = (await getOrCreateAssociatedTokenAccount(connection, payer, mint, scopedHandlerSigner, true))
+        .address;
+
+      // Relay tokens to the scoped handler signer.
+      updateRelayData({ ...relayData, recipient: scopedHandlerSigner });
+      accounts.recipientTokenAccount = scopedHandlerATA;
+    });
+
+    // Encodes a message transferring all relayed tokens from the scoped handler ATA to the final recipient.
+    const encodeScopedTransfer = (messageSignerScope?: Uint8Array) => {
+      const transferIx = createTransferCheckedInstruction(
+        scopedHandlerATA,
+        mint,
+        finalRecipientATA,
+        scopedHandlerSigner,
+        relayAmount,
+        mintDecimals
+      );
+      const multicallHandlerCoder = new MulticallHandlerCoder([transferIx], undefined, {
+        signerScope: messageSignerScope,
+      });
+      const encodedMessage = new AcrossPlusMessageCoder({
+        handler: handlerProgram.programId,
+        readOnlyLen: multicallHandlerCoder.readOnlyLen,
+        valueAmount: new BN(0),
+        accounts: multicallHandlerCoder.compiledMessage.accountKeys,
+        handlerMessage: multicallHandlerCoder.encode(),
+      }).encode();
+      updateRelayData({ ...relayData, message: encodedMessage });
+      accounts.recipientTokenAccount = scopedHandlerATA;
+      return multicallHandlerCoder;
+    };
+
+    it("Forwards tokens signed by the scoped handler signer", async () => {
+      const multicallHandlerCoder = encodeScopedTransfer(signerScope);
+
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount,
+        "Final recipient's balance should be increased by the relay amount"
+      );
+      assertSE(
+        (await getAccount(connection, scopedHandlerATA)).amount,
+        0,
+        "Scoped handler balance should be forwarded"
+      );
+    });
+
+    it("Cannot move scoped handler tokens without the signer scope", async () => {
+      const multicallHandlerCoder = encodeScopedTransfer();
+
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      try {
+        await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+        assert.fail("Fill should have failed without the scoped handler signature");
+      } catch (err: any) {
+        assert.include(err.toString(), "unauthorized signer", "Expected unauthorized signer error");
+      }
+    });
+
+    it("Cannot move scoped handler tokens with a different signer scope", async () => {
+      const multicallHandlerCoder = encodeScopedTransfer(Keypair.generate().publicKey.toBytes());
+
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      try {
+        await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+        assert.fail("Fill should have failed with the handler signer of another scope");
+      } catch (err: any) {
+        assert.include(err.toString(), "unauthorized signer", "Expected unauthorized signer error");
+      }
+    });
+  });
+
+  describe("Message assertions", async () => {
+    // Encodes a message transferring all relayed tokens to the final recipient followed by the assertions.
+    const encodeTransferWithAssertions = (assertions: MulticallAssertion[]) => {
+      const transferIx = createTransferCheckedInstruction(
+        handlerATA,
+        mint,
+        finalRecipientATA,
+        handlerSigner,
+        relayAmount,
+        mintDecimals
+      );
+      const multicallHandlerCoder = new MulticallHandlerCoder([transferIx], undefined, { assertions });
+      const encodedMessage = new AcrossPlusMessageCoder({
+        handler: handlerProgram.programId,
+        readOnlyLen: multicallHandlerCoder.readOnlyLen,
+        valueAmount: new BN(0),
+        accounts: multicallHandlerCoder.compiledMessage.accountKeys,
+        handlerMessage: multicallHandlerCoder.encode(),
+      }).encode();
+      updateRelayData({ ...relayData, message: encodedMessage });
+      return multicallHandlerCoder;
+    };
+
+    const fillExpectingError = async (multicallHandlerCoder: MulticallHandlerCoder, expectedError: string) => {
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      try {
+        await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+        assert.fail("Fill should have failed");
+      } catch (err: any) {
+        assert.include(err.toString(), expectedError, `Expected ${expectedError} error`);
+      }
+    };
+
+    it("Fills when all assertions pass", async () => {
+      const multicallHandlerCoder = encodeTransferWithAssertions([
+        { afterIxIndex: 0, minTokenBalance: { tokenAccount: finalRecipientATA, minAmount: new BN(relayAmount) } },
+        { afterIxIndex: 0, accountOwner: { account: finalRecipientATA, owner: TOKEN_PROGRAM_ID } },
+        { afterIxIndex: 0, minLamports: { account: finalRecipientATA, minLamports: new BN(1) } },
+      ]);
+
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount,
+        "Final recipient's balance should be increased by the relay amount"
+      );
+    });
+
+    it("Fails the fill when the token balance is below the minimum", async () => {
+      const minAmount = new BN(relayAmount + 1);
+      const multicallHandlerCoder = encodeTransferWithAssertions([
+        { afterIxIndex: 0, minTokenBalance: { tokenAccount: finalRecipientATA, minAmount } },
+      ]);
+
+      await fillExpectingError(multicallHandlerCoder, "TokenBalanceBelowMinimum");
+    });
+
+    it("Fails the fill when the account owner does not match", async () => {
+      const multicallHandlerCoder = encodeTransferWithAssertions([
+        { afterIxIndex: 0, accountOwner: { account: finalRecipientATA, owner: TOKEN_2022_PROGRAM_ID } },
+      ]);
+
+      await fillExpectingError(multicallHandlerCoder, "AccountOwnerMismatch");
+    });
+
+    it("Fails the fill when the lamports are below the minimum", async () => {
+      const lamports = await connection.getBalance(finalRecipientATA);
+      const multicallHandlerCoder = encodeTransferWithAssertions([
+        { afterIxIndex: 0, minLamports: { account: finalRecipientATA, minLamports: new BN(lamports + 1) } },
+      ]);
+
+      await fillExpectingError(multicallHandlerCoder, "LamportsBelowMinimum");
+    });
+  });
+
+  describe("Program allowlist", async () => {
+    const programAllowlist = MulticallHandlerCoder.programAllowlist(handlerProgram.programId);
+
+    const setAllowedProgram = (program: PublicKey, allowed: boolean, signer?: Keypair) => {
+      const method = handlerProgram.methods
+        .setAllowedProgram(program, allowed)
+        .accounts({ signer: signer?.publicKey ?? owner });
+      return signer ? method.signers([signer]).rpc() : method.rpc();
+    };
+
+    // Encodes a message transferring all relayed tokens to the final recipient that opts in to the program allowlist.
+    const encodeAllowlistedTransfer = (passProgramAllowlist = true) => {
+      const transferIx = createTransferCheckedInstruction(
+        handlerATA,
+        mint,
+        finalRecipientATA,
+        handlerSigner,
+        relayAmount,
+        mintDecimals
+      );
+      const multicallHandlerCoder = new MulticallHandlerCoder([transferIx], undefined, {
+        programAllowlist: passProgramAllowlist ? programAllowlist : undefined,
+      });
+      const encodedMessage = new AcrossPlusMessageCoder({
+        handler: handlerProgram.programId,
+        readOnlyLen: multicallHandlerCoder.readOnlyLen,
+        valueAmount: new BN(0),
+        accounts: multicallHandlerCoder.compiledMessage.accountKeys,
+        handlerMessage: multicallHandlerCoder.encode(),
+      }).encode();
+      updateRelayData({ ...relayData, message: encodedMessage });
+      return multicallHandlerCoder;
+    };
+
+    before("Initializes the program allowlist by the upgrade authority", async () => {
+      const [programData] = PublicKey.findProgramAddressSync(
+        [handlerProgram.programId.toBuffer()],
+        BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+      );
+      await handlerProgram.methods
+        .initializeProgramAllowlist()
+        .accounts({ signer: owner, programData })
+        .rpc();
+      await setAllowedProgram(TOKEN_PROGRAM_ID, true);
+    });
+
+    it("Forwards tokens when the invoked program is allowed", async () => {
+      const multicallHandlerCoder = encodeAllowlistedTransfer();
+
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount,
+        "Final recipient's balance should be increased by the relay amount"
+      );
+    });
+
+    it("Rejects invoking programs outside the allowlist", async () => {
+      await setAllowedProgram(TOKEN_PROGRAM_ID, false);
+      const multicallHandlerCoder = encodeAllowlistedTransfer();
+
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      try {
+        await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+        assert.fail("Fill should have failed for the program outside the allowlist");
+      } catch (err: any) {
+        assert.include(err.toString(), "ProgramNotAllowed", "Expected ProgramNotAllowed error");
+      } finally {
+        await setAllowedProgram(TOKEN_PROGRAM_ID, true);
+      }
+    });
+
+    it("Does not restrict messages omitting the optional program allowlist", async () => {
+      // Deployments built without the program-allowlist feature only enforce the allowlist when passed in the message,
+      // so that messages encoded before it was initialized keep working.
+      await setAllowedProgram(TOKEN_PROGRAM_ID, false);
+      const multicallHandlerCoder = encodeAllowlistedTransfer(false);
+
+      try {
+        const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+        await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+      } finally {
+        await setAllowedProgram(TOKEN_PROGRAM_ID, true);
+      }
+
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount,
+        "Final recipient's balance should be increased by the relay amount"
+      );
+    });
+
+    it("Only the allowlist owner can update the allowlist", async () => {
+      try {
+        await setAllowedProgram(TOKEN_2022_PROGRAM_ID, true, relayer);
+        assert.fail("Non-owner should not be able to update the allowlist");
+      } catch (err: any) {
+        assert.include(err.toString(), "NotProgramAllowlistOwner", "Expected NotProgramAllowlistOwner error");
+      }
+    });
+  });
+
+  describe("Versioned message format", async () => {
+    // Encodes a versioned message transferring all tokens from the handler to the final recipient.
+    const encodeVersionedTransfer = (overrides: Partial<AcrossPlusMessage> = {}) => {
+      const transferIx = createTransferCheckedInstruction(
+        handlerATA,
+        mint,
+        finalRecipientATA,
+        handlerSigner,
+        relayData.outputAmount,
+        mintDecimals
+      );
+      const multicallHandlerCoder = new MulticallHandlerCoder([transferIx]);
+      const encodedMessage = new AcrossPlusMessageCoder({
+        handler: handlerProgram.programId,
+        readOnlyLen: multicallHandlerCoder.readOnlyLen,
+        valueAmount: new BN(0),
+        accounts: multicallHandlerCoder.compiledMessage.accountKeys,
+        handlerMessage: multicallHandlerCoder.encode(),
+        version: ACROSS_PLUS_MESSAGE_V2,
+        computeUnits: 50_000,
+        ...overrides,
+      }).encode();
+      updateRelayData({ ...relayData, message: encodedMessage });
+      return multicallHandlerCoder;
+    };
+
+    const fillExpectingError = async (multicallHandlerCoder: MulticallHandlerCoder, expectedError: string) => {
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      try {
+        await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+        assert.fail("Fill should have failed");
+      } catch (err: any) {
+        assert.include(err.toString(), expectedError, `Expected ${expectedError} error`);
+      }
+    };
+
+    it("Forwards tokens to the final recipient with a versioned message", async () => {
+      const multicallHandlerCoder = encodeVersionedTransfer();
+      assertSE(
+        relayData.message.subarray(0, ACROSS_PLUS_MESSAGE_PREFIX.length),
+        ACROSS_PLUS_MESSAGE_PREFIX,
+        "Versioned message should start with the prefix"
+      );
+
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount,
+        "Final recipient's balance should be increased by the relay amount"
+      );
+    });
+
+    it("Rejects unsupported message versions", async () => {
+      const multicallHandlerCoder = encodeVersionedTransfer();
+      const message = Buffer.from(relayData.message);
+      message[ACROSS_PLUS_MESSAGE_PREFIX.length] = ACROSS_PLUS_MESSAGE_V2 + 1;
+      updateRelayData({ ...relayData, message });
+
+      await fillExpectingError(multicallHandlerCoder, "UnsupportedMessageVersion");
+    });
+
+    it("Rejects versioned messages with trailing bytes", async () => {
+      const multicallHandlerCoder = encodeVersionedTransfer();
+      updateRelayData({ ...relayData, message: Buffer.concat([relayData.message, Buffer.from([0])]) });
+
+      await fillExpectingError(multicallHandlerCoder, "MalformedVersionedMessage");
+    });
+
+    it("Parses legacy messages whose handler key starts with the versioned prefix", async () => {
+      const handler = new PublicKey(
+        Buffer.concat([ACROSS_PLUS_MESSAGE_PREFIX, Buffer.from([ACROSS_PLUS_MESSAGE_V2]), Buffer.alloc(24, 1)])
+      );
+      const multicallHandlerCoder = encodeVersionedTransfer({ handler, version: undefined });
+
+      // The legacy handler is parsed and checked against the passed handler instead of failing as a versioned message.
+      await fillExpectingError(multicallHandlerCoder, "InvalidMessageHandler");
+    });
+
+    it("Rejects fills without the declared compute units", async () => {
+      const multicallHandlerCoder = encodeVersionedTransfer({ computeUnits: 1_400_000 });
+
+      await fillExpectingError(multicallHandlerCoder, "InsufficientComputeUnits");
+    });
+  });
+
+  describe("Compiled multicall messages", async () => {
+    // Fills the relay passing the remaining accounts exactly as returned by the message compiler.
+    const fillCompiledMessage = async (
+      { message, remainingAccounts }: ReturnType<typeof compileMulticallMessage>
+    ) => {
+      const recipientTokenAccount = accounts.recipientTokenAccount;
+      updateRelayData({ ...relayData, message });
+      accounts.recipientTokenAccount = recipientTokenAccount;
+
+      const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
+      const approveIx = await createApproveCheckedInstruction(
+        relayerATA,
+        mint,
+        state,
+        relayer.publicKey,
+        BigInt(relayAmount),
+        mintDecimals
+      );
+      const fillIx = await program.methods
+        .fillRelay(relayHash, relayData, new BN(1), relayer.publicKey, null)
+        .accounts(accounts)
+        .remainingAccounts(remainingAccounts)
+        .instruction();
+      const tx = new Transaction().add(approveIx, fillIx);
+      await sendAndConfirmTransaction(connection, tx, [relayer]);
+    };
+
+    it("Fills with deduplicated accounts ordered writable first", async () => {
+      const iHandlerBal = (await getAccount(connection, handlerATA)).amount;
+
+      // The mint is read-only in the transfer, but writable in the burn, so it must be promoted to writable.
+      const transferIx = createTransferCheckedInstruction(
+        handlerATA,
+        mint,
+        finalRecipientATA,
+        HANDLER_SIGNER_PLACEHOLDER,
+        relayAmount / 2,
+        mintDecimals
+      );
+      const burnIx = createBurnCheckedInstruction(
+        handlerATA,
+        mint,
+        HANDLER_SIGNER_PLACEHOLDER,
+        relayAmount / 2,
+        mintDecimals
+      );
+      const compiledMessage = compileMulticallMessage(handlerProgram.programId, [transferIx, burnIx]);
+
+      const messageKeyMetas = compiledMessage.remainingAccounts.slice(1);
+      const messageKeys = messageKeyMetas.map(({ pubkey }) => pubkey.toBase58());
+      assertSE(new Set(messageKeys).size, messageKeys.length, "Message accounts should be deduplicated");
+      assertSE(
+        messageKeys,
+        [handlerATA, mint, finalRecipientATA, TOKEN_PROGRAM_ID, handlerSigner].map((key) => key.toBase58()),
+        "Writable accounts should be ordered before read-only ones"
+      );
+      assertSE(
+        messageKeyMetas.map(({ isWritable }) => isWritable),
+        [true, true, true, false, false],
+        "Account write privileges should match"
+      );
+      assert.notInclude(messageKeys, HANDLER_SIGNER_PLACEHOLDER.toBase58(), "Placeholder should be substituted");
+
+      await fillCompiledMessage(compiledMessage);
+
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount / 2,
+        "Final recipient should receive the transferred amount"
+      );
+      assertSE((await getAccount(connection, handlerATA)).amount, iHandlerBal, "Handler should burn the rest");
+    });
+
+    it("Substitutes the scoped handler signer", async () => {
+      const signerScope = Keypair.generate().publicKey.toBytes();
+      const scopedHandlerSigner = MulticallHandlerCoder.handlerSigner(handlerProgram.programId, signerScope);
+      const scopedHandlerATA = getAssociatedTokenAddressSync(mint, scopedHandlerSigner, true);
+      await getOrCreateAssociatedTokenAccount(connection, payer, mint, scopedHandlerSigner, true);
+      updateRelayData({ ...relayData, recipient: scopedHandlerSigner });
+      accounts.recipientTokenAccount = scopedHandlerATA;
+
+      const transferIx = createTransferCheckedInstruction(
+        scopedHandlerATA,
+        mint,
+        finalRecipientATA,
+        HANDLER_SIGNER_PLACEHOLDER,
+        relayAmount,
+        mintDecimals
+      );
+      const compiledMessage = compileMulticallMessage(handlerProgram.programId, [transferIx], { signerScope });
+      const messageKeys = compiledMessage.remainingAccounts.map(({ pubkey }) => pubkey.toBase58());
+      assert.include(messageKeys, scopedHandlerSigner.toBase58(), "Scoped handler signer should be substituted");
+
+      await fillCompiledMessage(compiledMessage);
+
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount,
+        "Final recipient's balance should be increased by the relay amount"
+      );
+      assertSE(
+        (await getAccount(connection, scopedHandlerATA)).amount,
+        0,
+        "Scoped handler balance should be forwarded"
+      );
+    });
+
+    it("Rejects compiling instructions signed by other accounts", async () => {
+      const transferIx = createTransferCheckedInstruction(
+        handlerATA,
+        mint,
+        finalRecipientATA,
+        relayer.publicKey,
+        relayAmount,
+        mintDecimals
+      );
+
+      assert.throws(
+        () => compileMulticallMessage(handlerProgram.programId, [transferIx]),
+        "Only the handler signer can sign message instructions"
+      );
+    });
+  });
+
   describe("Max token distributions within invoked message call", async () => {
     const fillTokenDistributions = async (numberOfDistributions: number, bufferParams = false) => {
       const iRelayerBal = (await getAccount(connection, relayerATA)).amount;
@@ -384,4 +995,202 @@ describe("svm_spoke.fill.across_plus", () => {
       "Recipient's balance should be increased by the relay amount"
     );
   });
+
+  describe("Deferred message execution", async () => {
+    // Encodes a message transferring the given amount from the handler to the final recipient with deferred execution.
+    const encodeDeferredTransfer = (
+      amount: BN,
+      optIn: Partial<AcrossPlusMessage> = { version: ACROSS_PLUS_MESSAGE_V2, allowHandlerFailure: true }
+    ) => {
+      const transferIx = createTransferCheckedInstruction(
+        handlerATA,
+        mint,
+        finalRecipientATA,
+        handlerSigner,
+        BigInt(amount.toString()),
+        mintDecimals
+      );
+      const multicallHandlerCoder = new MulticallHandlerCoder([transferIx]);
+      const encodedMessage = new AcrossPlusMessageCoder({
+        handler: handlerProgram.programId,
+        readOnlyLen: multicallHandlerCoder.readOnlyLen,
+        valueAmount: new BN(0),
+        accounts: multicallHandlerCoder.compiledMessage.accountKeys,
+        handlerMessage: multicallHandlerCoder.encode(),
+        ...optIn,
+      }).encode();
+      updateRelayData({ ...relayData, message: encodedMessage });
+
+      const relayHash = calculateRelayHashUint8Array(relayData, chainId);
+      const [pendingRelayMessage] = PublicKey.findProgramAddressSync(
+        [Buffer.from("pending_relay_message"), relayHash],
+        program.programId
+      );
+      accounts.pendingRelayMessage = pendingRelayMessage;
+      return { multicallHandlerCoder, relayHash: Array.from(relayHash), pendingRelayMessage };
+    };
+
+    const executeRelayMessage = (
+      relayHash: number[],
+      multicallHandlerCoder: MulticallHandlerCoder,
+      executor: Keypair = relayer
+    ) =>
+      program.methods
+        .executeRelayMessage(relayHash)
+        .accounts({ state, signer: executor.publicKey, relayer: relayer.publicKey })
+        .remainingAccounts([
+          { pubkey: handlerProgram.programId, isSigner: false, isWritable: false },
+          ...multicallHandlerCoder.compiledKeyMetas,
+        ])
+        .signers([executor])
+        .rpc();
+
+    it("Executes the deferred message by a separate executor", async () => {
+      const { multicallHandlerCoder, relayHash, pendingRelayMessage } = encodeDeferredTransfer(relayData.outputAmount, {
+        version: ACROSS_PLUS_MESSAGE_V2,
+        allowDeferral: true,
+      });
+
+      // Relayer fills without passing the message handler accounts.
+      const approveIx = await createApproveCheckedInstruction(
+        relayerATA,
+        mint,
+        state,
+        relayer.publicKey,
+        BigInt(relayAmount),
+        mintDecimals
+      );
+      const fillIx = await program.methods
+        .fillRelay(relayHash, relayData, new BN(1), relayer.publicKey, null)
+        .accounts(accounts)
+        .instruction();
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+      assertSE((await getAccount(connection, finalRecipientATA)).amount, 0, "Message should not be executed yet");
+
+      const pendingRelayMessageAccount = await program.account.pendingRelayMessage.fetch(pendingRelayMessage);
+      assertSE(pendingRelayMessageAccount.relayer, relayer.publicKey, "Relayer should match");
+      assertSE(pendingRelayMessageAccount.message, relayData.message, "Message should match");
+
+      // Any executor can provide the message handler accounts, while the rent is still returned to the relayer.
+      const executor = Keypair.generate();
+      await connection.confirmTransaction(await connection.requestAirdrop(executor.publicKey, 1_000_000_000));
+      const iRelayerLamports = await connection.getBalance(relayer.publicKey);
+      const pendingRelayMessageRent = await connection.getBalance(pendingRelayMessage);
+      await executeRelayMessage(relayHash, multicallHandlerCoder, executor);
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount,
+        "Final recipient's balance should be increased by the relay amount"
+      );
+      assertSE(
+        await connection.getBalance(relayer.publicKey),
+        iRelayerLamports + pendingRelayMessageRent,
+        "Relayer should receive the pending relay message rent"
+      );
+    });
+
+    it("Cannot defer a message that did not opt in to deferral", async () => {
+      const { multicallHandlerCoder } = encodeDeferredTransfer(relayData.outputAmount, {});
+
+      // Relayer passes the pending relay message although the depositor expects the message to be executed in the fill.
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      try {
+        await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+        assert.fail("Fill should have failed when deferring a message that did not opt in");
+      } catch (err: any) {
+        assert.include(err.toString(), "UnexpectedPendingRelayMessage", "Expected UnexpectedPendingRelayMessage error");
+      }
+    });
+
+    it("Defers the message execution after delivering tokens", async () => {
+      const { multicallHandlerCoder, relayHash, pendingRelayMessage } = encodeDeferredTransfer(relayData.outputAmount);
+      const iHandlerBal = (await getAccount(connection, handlerATA)).amount;
+
+      // The fill only delivers tokens to the recipient and stores the message.
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+      assertSE(
+        (await getAccount(connection, handlerATA)).amount,
+        iHandlerBal + BigInt(relayAmount),
+        "Recipient should receive the relay amount"
+      );
+      assertSE((await getAccount(connection, finalRecipientATA)).amount, 0, "Message should not be executed yet");
+
+      // Anyone can execute the stored message.
+      await executeRelayMessage(relayHash, multicallHandlerCoder);
+      assertSE(
+        (await getAccount(connection, finalRecipientATA)).amount,
+        relayAmount,
+        "Final recipient's balance should be increased by the relay amount"
+      );
+      assert.isNull(await connection.getAccountInfo(pendingRelayMessage), "Pending relay message should be closed");
+    });
+
+    it("Completes the fill when the handler fails", async () => {
+      // Transferring more than received makes the handler fail.
+      const { multicallHandlerCoder, relayHash, pendingRelayMessage } = encodeDeferredTransfer(
+        relayData.outputAmount.muln(2)
+      );
+      const iHandlerBal = (await getAccount(connection, handlerATA)).amount;
+
+      const { approveIx, fillIx } = await createApproveAndFillIx(multicallHandlerCoder);
+      const fillTx = await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [relayer]);
+      assertSE(
+        (await getAccount(connection, handlerATA)).amount,
+        iHandlerBal + BigInt(relayAmount),
+        "Recipient should receive the relay amount"
+      );
+
+      // The fill reports that the message was deferred as the handler is allowed to fail.
+      const fillEvents = await readEventsUntilFound(connection, fillTx, [program]);
+      const deferredEvent = fillEvents.find((event) => event.name === "deferredRelayMessage")?.data;
+      assert.isNotNull(deferredEvent, "DeferredRelayMessage event should be emitted");
+      assertSE(deferredEvent.relayHash, relayHash, "Relay hash should match");
+      assertSE(deferredEvent.relayer, relayer.publicKey, "Relayer should match");
+      assert.isTrue(deferredEvent.allowHandlerFailure, "Handler failure should be allowed");
+
+      try {
+        await executeRelayMessage(relayHash, multicallHandlerCoder);
+        assert.fail("Message execution should have failed");
+      } catch (err: any) {
+        assert.include(err.toString(), "custom program error: 0x1", "Expected insufficient funds error");
+      }
+
+      // The execution window is measured from the fill time, so it extends past the fill deadline.
+      const fillTime = (await program.account.state.fetch(state)).currentTime;
+      const { executionDeadline } = await program.account.pendingRelayMessage.fetch(pendingRelayMessage);
+      assertSE(executionDeadline, fillTime + executionWindow, "Execution deadline should be measured from fill time");
+
+      // Pending message can only be closed as expired after the execution deadline.
+      const closeAccounts = { state, signer: owner, relayer: relayer.publicKey, pendingRelayMessage };
+      for (const currentTime of [relayData.fillDeadline.addn(1), new BN(executionDeadline)]) {
+        await setCurrentTime(program, state, relayer, currentTime);
+        try {
+          await program.methods.closePendingRelayMessage(relayHash).accounts(closeAccounts).rpc();
+          assert.fail("Closing pending relay message should have failed before execution deadline");
+        } catch (err: any) {
+          assert.include(
+            err.toString(),
+            "CanOnlyClosePendingRelayMessageIfExecutionDeadlinePassed",
+            "Expected CanOnlyClosePendingRelayMessageIfExecutionDeadlinePassed error"
+          );
+        }
+      }
+
+      await setCurrentTime(program, state, relayer, new BN(executionDeadline + 1));
+      const iRelayerLamports = await connection.getBalance(relayer.publicKey);
+      const pendingRelayMessageRent = await connection.getBalance(pendingRelayMessage);
+      const tx = await program.methods.closePendingRelayMessage(relayHash).accounts(closeAccounts).rpc();
+
+      const events = await readEventsUntilFound(connection, tx, [program]);
+      const event = events.find((event) => event.name === "expiredRelayMessage")?.data;
+      assert.isNotNull(event, "ExpiredRelayMessage event should be emitted");
+      assertSE(event.relayHash, relayHash, "Relay hash should match");
+      assertSE(
+        await connection.getBalance(relayer.publicKey),
+        iRelayerLamports + pendingRelayMessageRent,
+        "Relayer should receive the pending relay message rent"
+      );
+    });
+  });
 });
@@ -22,12 +22,14 @@ import {
   sendTransactionWithLookupTable,
   hashNonEmptyMessage,
   intToU8Array32,
+  exclusiveRelayerHashFn,
 } from "../../src/svm/web3-v1";
+import { MerkleTree } from "../../utils";
 import { common } from "./SvmSpoke.common";
 import { testAcrossPlusMessage } from "./utils";
 import { FillDataValues, RelayData } from "../../src/types/svm";
 const { provider, connection, program, owner, chainId, seedBalance } = common;
-const { recipient, initializeState, setCurrentTime, assertSE, assert } = common;
+const { recipient, initializeState, setCurrentTime, getCurrentTime, fillDeadlineBuffer, assertSE, assert } = common;
 
 describe("svm_spoke.fill", () => {
   anchor.setProvider(provider);
@@ -55,6 +57,9 @@ describe("svm_spoke.fill", () => {
     relayerTokenAccount: PublicKey;
     recipientTokenAccount: PublicKey;
     fillStatus: PublicKey;
+    fillStatusBucket?: PublicKey;
+    fillStatusOverflow?: PublicKey;
+    recipient?: PublicKey;
     tokenProgram: PublicKey;
     associatedTokenProgram: PublicKey;
     systemProgram: PublicKey;
@@ -162,7 +167,7 @@ describe("svm_spoke.fill", () => {
     assertSE(relayerAccount.amount, seedBalance, "Relayer's balance should be equal to seed balance before the fill");
 
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
-    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey]);
+    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
 
     // Verify relayer's balance after the fill
     relayerAccount = await getAccount(connection, relayerTA);
@@ -179,7 +184,7 @@ describe("svm_spoke.fill", () => {
 
   it("Verifies FilledRelay event after filling a relay", async () => {
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
-    const tx = await approvedFillRelay([relayHash, relayData, new BN(420), otherRelayer.publicKey]);
+    const tx = await approvedFillRelay([relayHash, relayData, new BN(420), otherRelayer.publicKey, null]);
 
     // Fetch and verify the FilledRelay event
     const events = await readEventsUntilFound(connection, tx, [program]);
@@ -211,7 +216,7 @@ describe("svm_spoke.fill", () => {
 
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
     try {
-      await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey]);
+      await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
       assert.fail("Fill should have failed due to fill deadline passed");
     } catch (err: any) {
       assert.include(err.toString(), "ExpiredFillDeadline", "Expected ExpiredFillDeadline error");
@@ -224,7 +229,7 @@ describe("svm_spoke.fill", () => {
 
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
     try {
-      await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey], undefined, otherRelayer);
+      await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null], undefined, otherRelayer);
       assert.fail("Fill should have failed due to non-exclusive relayer before exclusivity deadline");
     } catch (err: any) {
       assert.include(err.toString(), "NotExclusiveRelayer", "Expected NotExclusiveRelayer error");
@@ -241,7 +246,7 @@ describe("svm_spoke.fill", () => {
     const relayerAccountBefore = await getAccount(connection, otherRelayerTA);
 
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
-    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey], undefined, otherRelayer);
+    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null], undefined, otherRelayer);
 
     // Verify relayer's balance after the fill
     const relayerAccountAfter = await getAccount(connection, otherRelayerTA);
@@ -260,15 +265,64 @@ describe("svm_spoke.fill", () => {
     );
   });
 
+  it("Allows fill by a member of the exclusive relayer set before exclusivity deadline", async () => {
+    const exclusiveRelayers = [relayer.publicKey, otherRelayer.publicKey, Keypair.generate().publicKey];
+    const merkleTree = new MerkleTree<PublicKey>(exclusiveRelayers, exclusiveRelayerHashFn);
+    updateRelayData({ ...relayData, exclusiveRelayer: new PublicKey(merkleTree.getRoot()) });
+
+    accounts.signer = otherRelayer.publicKey;
+    accounts.relayerTokenAccount = otherRelayerTA;
+
+    const recipientAccountBefore = await getAccount(connection, recipientTA);
+
+    const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
+    const exclusivityProof = merkleTree.getProof(otherRelayer.publicKey).map((p) => Array.from(p));
+    await approvedFillRelay(
+      [relayHash, relayData, new BN(1), relayer.publicKey, exclusivityProof],
+      undefined,
+      otherRelayer
+    );
+
+    const recipientAccountAfter = await getAccount(connection, recipientTA);
+    assertSE(
+      recipientAccountAfter.amount,
+      BigInt(recipientAccountBefore.amount) + BigInt(relayAmount),
+      "Recipient's balance should be increased by the relay amount"
+    );
+  });
+
+  it("Fails to fill by a relayer outside the exclusive relayer set before exclusivity deadline", async () => {
+    const exclusiveRelayers = [relayer.publicKey, Keypair.generate().publicKey];
+    const merkleTree = new MerkleTree<PublicKey>(exclusiveRelayers, exclusiveRelayerHashFn);
+    updateRelayData({ ...relayData, exclusiveRelayer: new PublicKey(merkleTree.getRoot()) });
+
+    accounts.signer = otherRelayer.publicKey;
+    accounts.relayerTokenAccount = otherRelayerTA;
+
+    // Reuse the proof of a valid member, but sign as a non-member.
+    const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
+    const exclusivityProof = merkleTree.getProof(relayer.publicKey).map((p) => Array.from(p));
+    try {
+      await approvedFillRelay(
+        [relayHash, relayData, new BN(1), relayer.publicKey, exclusivityProof],
+        undefined,
+        otherRelayer
+      );
+      assert.fail("Fill should have failed due to relayer not being in the exclusive relayer set");
+    } catch (err: any) {
+      assert.include(err.toString(), "NotExclusiveRelayer", "Expected NotExclusiveRelayer error");
+    }
+  });
+
   it("Fails to fill a V3 relay with the same deposit data multiple times", async () => {
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
 
     // First fill attempt
-    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey]);
+    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
 
     // Second fill attempt with the same data
     try {
-      await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey]);
+      await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
       assert.fail("Fill should have failed due to RelayFilled error");
     } catch (err: any) {
       assert.include(err.toString(), "RelayFilled", "Expected RelayFilled error");
@@ -286,7 +340,7 @@ describe("svm_spoke.fill", () => {
     };
 
     // Execute the fill_v3_relay call
-    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey]);
+    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
 
     // Verify the fill PDA exists before closing
     const fillStatusAccountBefore = await connection.getAccountInfo(accounts.fillStatus);
@@ -315,6 +369,158 @@ describe("svm_spoke.fill", () => {
     assert.isNull(fillStatusAccountAfter, "Fill PDA should be closed after closing");
   });
 
+  it("Batch closes expired fill PDAs to their relayers", async () => {
+    // Fill one relay that expires first by relayer and another relay with a later deadline by otherRelayer.
+    const expiringFill = { ...accounts };
+    await approvedFillRelay([
+      Array.from(calculateRelayHashUint8Array(relayData, chainId)),
+      relayData,
+      new BN(1),
+      relayer.publicKey,
+      null,
+    ]);
+    const expiringFillDeadline = relayData.fillDeadline;
+
+    updateRelayData({
+      ...relayData,
+      fillDeadline: relayData.fillDeadline + 60,
+      exclusiveRelayer: otherRelayer.publicKey,
+    });
+    const activeFill = { ...accounts, signer: otherRelayer.publicKey, relayerTokenAccount: otherRelayerTA };
+    const activeRelayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
+    const activeFillDataValues: FillDataValues = [activeRelayHash, relayData, new BN(1), otherRelayer.publicKey, null];
+    await approvedFillRelay(activeFillDataValues, activeFill, otherRelayer);
+
+    // Close is permissionless, but the rent must be sent to the recorded relayer.
+    const closeFillPdas = (remainingAccounts: { pubkey: PublicKey }[]) =>
+      program.methods
+        .closeFillPdas()
+        .accounts({ state })
+        .remainingAccounts(remainingAccounts.map(({ pubkey }) => ({ pubkey, isWritable: true, isSigner: false })))
+        .rpc();
+    try {
+      await closeFillPdas([{ pubkey: expiringFill.fillStatus }, { pubkey: otherRelayer.publicKey }]);
+      assert.fail("Closing fill PDA to a wrong relayer should have failed");
+    } catch (err: any) {
+      assert.include(err.toString(), "NotRelayer", "Expected NotRelayer error");
+    }
+
+    await setCurrentTime(program, state, relayer, new BN(expiringFillDeadline + 1));
+
+    const relayerBalanceBefore = await connection.getBalance(relayer.publicKey);
+    const fillStatusRent = await connection.getBalance(expiringFill.fillStatus);
+    await closeFillPdas([
+      { pubkey: expiringFill.fillStatus },
+      { pubkey: relayer.publicKey },
+      { pubkey: activeFill.fillStatus },
+      { pubkey: otherRelayer.publicKey },
+    ]);
+
+    // Only the expired fill PDA is closed and its rent is returned to the relayer.
+    assert.isNull(await connection.getAccountInfo(expiringFill.fillStatus), "Expired fill PDA should be closed");
+    assert.isNotNull(await connection.getAccountInfo(activeFill.fillStatus), "Active fill PDA should be skipped");
+    assertSE(
+      await connection.getBalance(relayer.publicKey),
+      relayerBalanceBefore + fillStatusRent,
+      "Relayer should receive the fill PDA rent"
+    );
+  });
+
+  it("Views the fill status of filled and closed relays", async () => {
+    // Tombstones are sharded by the first byte of the fill PDA address.
+    const { seed } = await program.account.state.fetch(state);
+    const getFillTombstones = (fillStatus: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("fill_tombstones"), seed.toArrayLike(Buffer, "le", 8), fillStatus.toBuffer().subarray(0, 1)],
+        program.programId
+      )[0];
+    const fillTombstones = getFillTombstones(accounts.fillStatus);
+    const getFillStatus = (viewedRelayData: RelayData = relayData, fillStatus: PublicKey = accounts.fillStatus) =>
+      program.methods
+        .getFillStatus(viewedRelayData)
+        .accounts({ state, fillStatus, fillTombstones: getFillTombstones(fillStatus) })
+        .view();
+
+    await program.methods
+      .initializeFillTombstones(accounts.fillStatus.toBuffer()[0])
+      .accounts({ state, signer: owner })
+      .rpc();
+
+    let fillStatus = await getFillStatus();
+    assert.equal(JSON.stringify(fillStatus.status), `{"unfilled":{}}`, "FillStatus should be Unfilled");
+    assert.isFalse(fillStatus.closed, "Unfilled relay should not be closed");
+
+    const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
+    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
+
+    fillStatus = await getFillStatus();
+    assert.equal(JSON.stringify(fillStatus.status), `{"filled":{}}`, "FillStatus should be Filled");
+    assertSE(fillStatus.relayer, relayer.publicKey, "Relayer should match");
+    assertSE(fillStatus.fillDeadline, relayData.fillDeadline, "Fill deadline should match");
+    assert.isFalse(fillStatus.closed, "Open fill PDA should not be closed");
+
+    // Closing the fill PDA requires recording its tombstone once fill tombstones are initialized.
+    await setCurrentTime(program, state, relayer, new BN(relayData.fillDeadline + 1));
+    const closeFillPdaAccounts = { state, signer: relayer.publicKey, fillStatus: accounts.fillStatus };
+    try {
+      await program.methods.closeFillPda().accounts(closeFillPdaAccounts).signers([relayer]).rpc();
+      assert.fail("Closing fill PDA without fill tombstones should have failed");
+    } catch (err: any) {
+      assert.include(err.toString(), "MissingFillTombstones", "Expected MissingFillTombstones error");
+    }
+    await program.methods
+      .closeFillPda()
+      .accounts({ ...closeFillPdaAccounts, fillTombstones })
+      .signers([relayer])
+      .rpc();
+
+    // Closed fill PDA is reported from its tombstone.
+    assert.isNull(await connection.getAccountInfo(accounts.fillStatus), "Fill PDA should be closed");
+    fillStatus = await getFillStatus();
+    assert.equal(JSON.stringify(fillStatus.status), `{"filled":{}}`, "FillStatus should be Filled");
+    assertSE(fillStatus.relayer, relayer.publicKey, "Relayer should match");
+    assert.isTrue(fillStatus.closed, "Fill PDA should be reported as closed");
+
+    // Tombstones are never overwritten, so later closes in the same shard keep the earlier tombstone.
+    const closedRelayData = relayData;
+    const closedFillStatus = accounts.fillStatus;
+    let nextDepositId = 1000;
+    const nextShardRelayData = () => {
+      for (;;) {
+        const shardRelayData = {
+          ...closedRelayData,
+          depositId: intToU8Array32(nextDepositId++),
+          fillDeadline: closedRelayData.fillDeadline + 60,
+        };
+        const [fillStatus] = PublicKey.findProgramAddressSync(
+          [Buffer.from("fills"), calculateRelayHashUint8Array(shardRelayData, chainId)],
+          program.programId
+        );
+        if (getFillTombstones(fillStatus).equals(fillTombstones)) return shardRelayData;
+      }
+    };
+    const remainingAccounts: { pubkey: PublicKey; isWritable: boolean; isSigner: boolean }[] = [];
+    for (let i = 0; i < 3; i++) {
+      updateRelayData(nextShardRelayData());
+      const shardRelayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
+      await approvedFillRelay([shardRelayHash, relayData, new BN(1), relayer.publicKey, null]);
+      [accounts.fillStatus, relayer.publicKey, fillTombstones].forEach((pubkey) =>
+        remainingAccounts.push({ pubkey, isWritable: true, isSigner: false })
+      );
+    }
+    await setCurrentTime(program, state, relayer, new BN(relayData.fillDeadline + 1));
+    await program.methods.closeFillPdas().accounts({ state }).remainingAccounts(remainingAccounts).rpc();
+
+    for (const { pubkey } of remainingAccounts.filter((_, i) => i % 3 === 0)) {
+      assert.isNull(await connection.getAccountInfo(pubkey), "Fill PDA should be closed");
+    }
+    fillStatus = await getFillStatus(relayData, accounts.fillStatus);
+    assert.isTrue(fillStatus.closed, "Last closed fill PDA should be reported as closed");
+    fillStatus = await getFillStatus(closedRelayData, closedFillStatus);
+    assert.equal(JSON.stringify(fillStatus.status), `{"filled":{}}`, "FillStatus should be Filled");
+    assert.isTrue(fillStatus.closed, "First closed fill PDA should still be reported as closed");
+  });
+
   it("Fetches FillStatusAccount before and after fillV3Relay", async () => {
     const relayHash = calculateRelayHashUint8Array(relayData, chainId);
     const [fillStatusPDA] = PublicKey.findProgramAddressSync([Buffer.from("fills"), relayHash], program.programId);
@@ -324,7 +530,7 @@ describe("svm_spoke.fill", () => {
     assert.isNull(fillStatusAccount, "FillStatusAccount should be uninitialized before fillV3Relay");
 
     // Fill the relay
-    await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey]);
+    await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null]);
 
     // Fetch FillStatusAccount after fillV3Relay
     fillStatusAccount = await program.account.fillStatusAccount.fetch(fillStatusPDA);
@@ -343,7 +549,7 @@ describe("svm_spoke.fill", () => {
     // Try to fill the relay. This should fail because fills are paused.
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
     try {
-      await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey]);
+      await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
       assert.fail("Should not be able to fill relay when fills are paused");
     } catch (err: any) {
       assert.include(err.toString(), "Fills are currently paused!", "Expected fills paused error");
@@ -359,7 +565,7 @@ describe("svm_spoke.fill", () => {
     const [wrongFillStatus] = PublicKey.findProgramAddressSync([Buffer.from("fills"), relayHash], program.programId);
 
     try {
-      await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey], {
+      await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null], {
         ...accounts,
         recipientTokenAccount: wrongRecipientTA,
         fillStatus: wrongFillStatus,
@@ -381,7 +587,7 @@ describe("svm_spoke.fill", () => {
     await mintTo(connection, payer, wrongMint, wrongRelayerTA, owner, seedBalance);
 
     try {
-      await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey], {
+      await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null], {
         ...accounts,
         mint: wrongMint,
         relayerTokenAccount: wrongRelayerTA,
@@ -406,7 +612,7 @@ describe("svm_spoke.fill", () => {
     // Fill relay from custom relayer token account
     accounts.relayerTokenAccount = customRelayerTA;
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
-    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey]);
+    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
 
     // Verify balances after the fill
     const fRelayerBal = (await getAccount(connection, customRelayerTA)).amount;
@@ -434,7 +640,7 @@ describe("svm_spoke.fill", () => {
     const relayHash = Array.from(calculateRelayHashUint8Array(newRelayData, chainId));
 
     try {
-      await approvedFillRelay([relayHash, newRelayData, new BN(1), relayer.publicKey]);
+      await approvedFillRelay([relayHash, newRelayData, new BN(1), relayer.publicKey, null]);
       assert.fail("Fill should have failed due to missing ATA");
     } catch (err: any) {
       assert.include(err.toString(), "AccountNotInitialized", "Expected AccountNotInitialized error");
@@ -462,7 +668,7 @@ describe("svm_spoke.fill", () => {
       tokenProgram
     );
     const fillInstruction = await program.methods
-      .fillRelay(relayHash, newRelayData, new BN(1), relayer.publicKey)
+      .fillRelay(relayHash, newRelayData, new BN(1), relayer.publicKey, null)
       .accounts(accounts)
       .remainingAccounts(fillRemainingAccounts)
       .instruction();
@@ -475,6 +681,45 @@ describe("svm_spoke.fill", () => {
     const recipientAccount = await getAccount(connection, newRecipientATA);
     assertSE(recipientAccount.amount, relayAmount, "Recipient's balance should be increased by the relay amount");
   });
+  it("Creates the recipient ATA within the fill", async () => {
+    const newRecipient = Keypair.generate().publicKey;
+    const newRecipientATA = getAssociatedTokenAddressSync(mint, newRecipient);
+    updateRelayData({ ...relayData, recipient: newRecipient });
+    const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
+
+    // Passing the recipient lets the relayer create the missing ATA and pay for its rent.
+    const iRelayerLamports = await connection.getBalance(relayer.publicKey);
+    const tx = await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null], {
+      ...accounts,
+      recipientTokenAccount: newRecipientATA,
+      recipient: newRecipient,
+    });
+
+    const recipientAccount = await getAccount(connection, newRecipientATA);
+    assertSE(recipientAccount.amount, relayAmount, "Recipient's balance should be increased by the relay amount");
+
+    // The rent paid for the ATA is reported in the FilledRelay event.
+    const ataRent = await connection.getBalance(newRecipientATA);
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    const event = events.find((event) => event.name === "filledRelay")?.data;
+    assertSE(event.recipientTokenAccountRent, ataRent, "Recipient token account rent should match");
+    const fillStatusRent = await connection.getBalance(accounts.fillStatus);
+    assertSE(
+      await connection.getBalance(relayer.publicKey),
+      iRelayerLamports - ataRent - fillStatusRent,
+      "Relayer should pay the recipient ATA and fill PDA rent"
+    );
+
+    // Fills to existing ATAs report no rent.
+    updateRelayData({ ...relayData, depositId: intToU8Array32(Math.floor(Math.random() * 1000000)) });
+    const secondTx = await approvedFillRelay(
+      [Array.from(calculateRelayHashUint8Array(relayData, chainId)), relayData, new BN(1), relayer.publicKey, null],
+      { ...accounts, recipientTokenAccount: newRecipientATA, recipient: newRecipient }
+    );
+    const secondEvents = await readEventsUntilFound(connection, secondTx, [program]);
+    const secondEvent = secondEvents.find((event) => event.name === "filledRelay")?.data;
+    assertSE(secondEvent.recipientTokenAccountRent, 0, "Recipient token account rent should be 0 for existing ATA");
+  });
   it("Max fills in one transaction with account creation", async () => {
     // Save relayer balance before the the fills
     const iRelayerBal = (await getAccount(connection, relayerTA)).amount;
@@ -511,7 +756,7 @@ describe("svm_spoke.fill", () => {
       accounts.recipientTokenAccount = recipientAssociatedTokens[i];
       const relayHash = Array.from(calculateRelayHashUint8Array(newRelayData, chainId));
       const fillInstruction = await program.methods
-        .fillRelay(relayHash, newRelayData, new BN(1), relayer.publicKey)
+        .fillRelay(relayHash, newRelayData, new BN(1), relayer.publicKey, null)
         .accounts(accounts)
         .remainingAccounts(fillRemainingAccounts)
         .instruction();
@@ -602,7 +847,7 @@ describe("svm_spoke.fill", () => {
     assertSE(relayerAccount.amount, seedBalance, "Relayer's balance should be equal to seed balance before the fill");
 
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
-    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey]);
+    await approvedFillRelay([relayHash, relayData, new BN(1), relayer.publicKey, null]);
 
     // Verify relayer's balance after the fill
     relayerAccount = await getAccount(connection, relayerTA, undefined, tokenProgram);
@@ -620,7 +865,7 @@ describe("svm_spoke.fill", () => {
   it("Emits zeroed hash for empty message", async () => {
     updateRelayData({ ...relayData, message: Buffer.alloc(0) });
     const relayHash = Array.from(calculateRelayHashUint8Array(relayData, chainId));
-    const tx = await approvedFillRelay([relayHash, relayData, new BN(420), otherRelayer.publicKey]);
+    const tx = await approvedFillRelay([relayHash, relayData, new BN(420), otherRelayer.publicKey, null]);
 
     // Fetch and verify the FilledRelay event
     const events = await readEventsUntilFound(connection, tx, [program]);
@@ -631,4 +876,157 @@ describe("svm_spoke.fill", () => {
     assertSE(event.messageHash, new Uint8Array(32), `MessageHash should be zeroed`);
     assertSE(event.relayExecutionInfo.updatedMessageHash, new Uint8Array(32), `UpdatedMessageHash should be zeroed`);
   });
+
+  it("Fills a relay tracked in a fill status bucket", async () => {
+    const relayHash = calculateRelayHashUint8Array(relayData, chainId);
+
+    // Switch the state to the bucketed fill status backend and create the bucket tracking this relay.
+    await program.methods.setFillStatusBackend({ bucketed: {} }).accounts({ state, signer: owner }).rpc();
+    const { seed } = await program.account.state.fetch(state);
+    const [fillStatusBucket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fill_status_bucket"), seed.toArrayLike(Buffer, "le", 8), Buffer.from([relayHash[0]])],
+      program.programId
+    );
+    await program.methods.initializeFillStatusBucket(relayHash[0]).accounts({ state, signer: owner }).rpc();
+
+    // Fills with the fills PDA should fail when the state uses buckets.
+    try {
+      await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null]);
+      assert.fail("Fill with the fills PDA should have failed");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidFillStatusBackendAccount", "Expected InvalidFillStatusBackendAccount");
+    }
+
+    const bucketAccounts = {
+      ...accounts,
+      fillStatus: program.programId,
+      fillStatusBucket,
+      fillStatusOverflow: accounts.fillStatus,
+    };
+    await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null], bucketAccounts);
+
+    // No fills PDA is created and the bucket reports the relay as filled.
+    assert.isNull(await connection.getAccountInfo(accounts.fillStatus), "Fill PDA should not be created");
+    const status = await program.methods
+      .getBucketedFillStatus(relayData)
+      .accounts({ state, fillStatusBucket, fillStatus: accounts.fillStatus })
+      .view();
+    assert.equal(JSON.stringify(status), `{"filled":{}}`, "FillStatus should be Filled");
+    const fillStatusView = await program.methods
+      .getFillStatus(relayData)
+      .accounts({ state, fillStatus: accounts.fillStatus, fillStatusBucket })
+      .view();
+    assert.equal(JSON.stringify(fillStatusView.status), `{"filled":{}}`, "FillStatus view should be Filled");
+    assertSE(fillStatusView.fillDeadline, relayData.fillDeadline, "Fill deadline should match");
+
+    // Filling the same relay again should fail.
+    try {
+      await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null], bucketAccounts);
+      assert.fail("Second fill should have failed");
+    } catch (err: any) {
+      assert.include(err.toString(), "RelayFilled", "Expected RelayFilled error");
+    }
+  });
+
+  it("Relays overflow a full fill status bucket to their fills PDA", async () => {
+    await program.methods.setFillStatusBackend({ bucketed: {} }).accounts({ state, signer: owner }).rpc();
+    const { seed } = await program.account.state.fetch(state);
+    const bucketIndex = calculateRelayHashUint8Array(relayData, chainId)[0];
+    const [fillStatusBucket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fill_status_bucket"), seed.toArrayLike(Buffer, "le", 8), Buffer.from([bucketIndex])],
+      program.programId
+    );
+    await program.methods.initializeFillStatusBucket(bucketIndex).accounts({ state, signer: owner }).rpc();
+
+    // Anyone can fill zero amount relays, so grind deposit IDs for relays that are all tracked in the same bucket.
+    const farFillDeadline = (await getCurrentTime(program, state)) + 10 * fillDeadlineBuffer.toNumber();
+    let nextDepositId = 0;
+    const nextBucketRelay = () => {
+      for (;;) {
+        const bucketRelayData = {
+          ...relayData,
+          outputAmount: new BN(0),
+          depositId: intToU8Array32(nextDepositId++),
+          fillDeadline: farFillDeadline,
+          exclusivityDeadline: 0,
+          message: Buffer.alloc(0),
+        };
+        const relayHash = calculateRelayHashUint8Array(bucketRelayData, chainId);
+        const [fillStatusOverflow] = PublicKey.findProgramAddressSync(
+          [Buffer.from("fills"), relayHash],
+          program.programId
+        );
+        if (relayHash[0] === bucketIndex) {
+          return { relayHash: Array.from(relayHash), bucketRelayData, fillStatusOverflow };
+        }
+      }
+    };
+    const fillBucketRelay = async () => {
+      const { relayHash, bucketRelayData, fillStatusOverflow } = nextBucketRelay();
+      const bucketAccounts = { ...accounts, fillStatus: program.programId, fillStatusBucket, fillStatusOverflow };
+      await approvedFillRelay([relayHash, bucketRelayData, new BN(1), relayer.publicKey, null], bucketAccounts);
+      return { relayHash, bucketRelayData, bucketAccounts };
+    };
+
+    // Fill all bucket entries with far future fill deadlines, so none of them can be reused.
+    const bucketCapacity = (await program.account.fillStatusBucket.fetch(fillStatusBucket)).entries.length;
+    for (let i = 0; i < bucketCapacity; i++) {
+      const { bucketAccounts } = await fillBucketRelay();
+      assert.isNull(await connection.getAccountInfo(bucketAccounts.fillStatusOverflow), "Fill PDA should not exist");
+    }
+
+    // The next relay is still filled, tracked in its fills PDA paid by the relayer.
+    const { relayHash, bucketRelayData, bucketAccounts } = await fillBucketRelay();
+    const fillStatusAccount = await program.account.fillStatusAccount.fetch(bucketAccounts.fillStatusOverflow);
+    assert.equal(JSON.stringify(fillStatusAccount.status), `{"filled":{}}`, "FillStatus should be Filled");
+    assertSE(fillStatusAccount.relayer, relayer.publicKey, "Relayer should match");
+    const status = await program.methods
+      .getBucketedFillStatus(bucketRelayData)
+      .accounts({ state, fillStatusBucket, fillStatus: bucketAccounts.fillStatusOverflow })
+      .view();
+    assert.equal(JSON.stringify(status), `{"filled":{}}`, "FillStatus should be Filled");
+
+    // Overflowed relays cannot be filled again.
+    try {
+      await approvedFillRelay([relayHash, bucketRelayData, new BN(1), relayer.publicKey, null], bucketAccounts);
+      assert.fail("Second fill should have failed");
+    } catch (err: any) {
+      assert.include(err.toString(), "RelayFilled", "Expected RelayFilled error");
+    }
+  });
+
+  it("Bucketed backend keeps tracking relays filled with the Pda backend", async () => {
+    const relayHash = calculateRelayHashUint8Array(relayData, chainId);
+    await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null]);
+
+    await program.methods.setFillStatusBackend({ bucketed: {} }).accounts({ state, signer: owner }).rpc();
+    const { seed } = await program.account.state.fetch(state);
+    const [fillStatusBucket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fill_status_bucket"), seed.toArrayLike(Buffer, "le", 8), Buffer.from([relayHash[0]])],
+      program.programId
+    );
+    await program.methods.initializeFillStatusBucket(relayHash[0]).accounts({ state, signer: owner }).rpc();
+
+    // The relay filled before the switch is read from its fills PDA.
+    const bucketAccounts = {
+      ...accounts,
+      fillStatus: program.programId,
+      fillStatusBucket,
+      fillStatusOverflow: accounts.fillStatus,
+    };
+    try {
+      await approvedFillRelay([Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null], bucketAccounts);
+      assert.fail("Fill of a relay filled before the backend switch should have failed");
+    } catch (err: any) {
+      assert.include(err.toString(), "RelayFilled", "Expected RelayFilled error");
+    }
+
+    // Switching back would ignore relays tracked in buckets.
+    try {
+      await program.methods.setFillStatusBackend({ pda: {} }).accounts({ state, signer: owner }).rpc();
+      assert.fail("Switching back to the Pda backend should have failed");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidFillStatusBackendSwitch", "Expected InvalidFillStatusBackendSwitch error");
+    }
+  });
 });
@@ -11,6 +11,7 @@ import { SvmSpoke } from "../../target/types/svm_spoke";
 import { common } from "./SvmSpoke.common";
 
 const { createRoutePda, getVaultAta, initializeState, crossDomainAdmin, remoteDomain, localDomain } = common;
+const { relayerRefundLeafCapacity } = common;
 
 describe("svm_spoke.handle_receive_message", () => {
   anchor.setProvider(AnchorProvider.env());
@@ -507,7 +508,7 @@ describe("svm_spoke.handle_receive_message", () => {
     const [rootBundle] = web3.PublicKey.findProgramAddressSync(seeds, program.programId);
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
     await program.methods
-      .relayRootBundle(Array.from(relayerRefundRoot), Array.from(slowRelayRoot))
+      .relayRootBundle(Array.from(relayerRefundRoot), Array.from(slowRelayRoot), relayerRefundLeafCapacity)
       .accounts(relayRootBundleAccounts)
       .rpc();
 
@@ -0,0 +1,127 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { readEventsUntilFound } from "../../src/svm/web3-v1";
+import { common } from "./SvmSpoke.common";
+
+const { provider, program, owner, initializeState, assert, assertSE } = common;
+
+describe("svm_spoke.integrator", () => {
+  anchor.setProvider(provider);
+
+  const nonAuthority = Keypair.generate();
+  const newAuthority = Keypair.generate();
+  const integratorId = 7;
+  const name = "Test Integrator";
+  const metadataUri = "https://example.com/integrator.json";
+
+  let state: PublicKey, seed: BN, integrator: PublicKey;
+
+  const getIntegratorPda = (id: number) => {
+    const integratorIdBuffer = Buffer.alloc(2);
+    integratorIdBuffer.writeUInt16LE(id);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("integrator"), seed.toArrayLike(Buffer, "le", 8), integratorIdBuffer],
+      program.programId
+    )[0];
+  };
+
+  before("Funds non-authority wallet", async () => {
+    await provider.connection.requestAirdrop(nonAuthority.publicKey, 10_000_000_000); // 10 SOL
+  });
+
+  beforeEach(async () => {
+    ({ state, seed } = await initializeState());
+    integrator = getIntegratorPda(integratorId);
+  });
+
+  it("Registers an integrator and emits event", async () => {
+    const registerAccounts = { signer: owner, state, integrator, program: program.programId };
+    const tx = await program.methods
+      .registerIntegrator(integratorId, name, metadataUri)
+      .accounts(registerAccounts)
+      .rpc();
+
+    const integratorData = await program.account.integrator.fetch(integrator);
+    assertSE(integratorData.authority, owner, "Authority should be the registering signer");
+    assertSE(integratorData.name, name, "Name should match");
+    assertSE(integratorData.metadataUri, metadataUri, "Metadata URI should match");
+
+    const events = await readEventsUntilFound(provider.connection, tx, [program]);
+    const event = events.find((event) => event.name === "registeredIntegrator")?.data;
+    assertSE(event.integratorId, integratorId, "Integrator ID should match");
+    assertSE(event.authority, owner, "Authority should match");
+    assertSE(event.name, name, "Name should match");
+    assertSE(event.metadataUri, metadataUri, "Metadata URI should match");
+  });
+
+  it("Fails to register an already registered integrator ID", async () => {
+    const registerAccounts = { signer: owner, state, integrator, program: program.programId };
+    await program.methods.registerIntegrator(integratorId, name, metadataUri).accounts(registerAccounts).rpc();
+
+    try {
+      const otherRegisterAccounts = { signer: nonAuthority.publicKey, state, integrator, program: program.programId };
+      await program.methods
+        .registerIntegrator(integratorId, "Squatter", metadataUri)
+        .accounts(otherRegisterAccounts)
+        .signers([nonAuthority])
+        .rpc();
+      assert.fail("Registering an already registered integrator ID should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "already in use", "Expected account already in use error");
+    }
+  });
+
+  it("Fails to register an integrator with too long metadata", async () => {
+    const registerAccounts = { signer: owner, state, integrator, program: program.programId };
+    try {
+      await program.methods
+        .registerIntegrator(integratorId, "x".repeat(33), metadataUri)
+        .accounts(registerAccounts)
+        .rpc();
+      assert.fail("Registering with too long name should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "IntegratorMetadataTooLong", "Expected IntegratorMetadataTooLong error");
+    }
+  });
+
+  it("Updates integrator metadata and authority", async () => {
+    const registerAccounts = { signer: owner, state, integrator, program: program.programId };
+    await program.methods.registerIntegrator(integratorId, name, metadataUri).accounts(registerAccounts).rpc();
+
+    const newName = "Renamed Integrator";
+    const newMetadataUri = "https://example.com/renamed.json";
+    const updateAccounts = { signer: owner, state, integrator, program: program.programId };
+    const tx = await program.methods
+      .updateIntegrator(integratorId, newAuthority.publicKey, newName, newMetadataUri)
+      .accounts(updateAccounts)
+      .rpc();
+
+    const integratorData = await program.account.integrator.fetch(integrator);
+    assertSE(integratorData.authority, newAuthority.publicKey, "Authority should be updated");
+    assertSE(integratorData.name, newName, "Name should be updated");
+    assertSE(integratorData.metadataUri, newMetadataUri, "Metadata URI should be updated");
+
+    const events = await readEventsUntilFound(provider.connection, tx, [program]);
+    const event = events.find((event) => event.name === "updatedIntegrator")?.data;
+    assertSE(event.authority, newAuthority.publicKey, "Authority should match");
+    assertSE(event.name, newName, "Name should match");
+  });
+
+  it("Fails to update integrator by non-authority", async () => {
+    const registerAccounts = { signer: owner, state, integrator, program: program.programId };
+    await program.methods.registerIntegrator(integratorId, name, metadataUri).accounts(registerAccounts).rpc();
+
+    try {
+      const updateAccounts = { signer: nonAuthority.publicKey, state, integrator, program: program.programId };
+      await program.methods
+        .updateIntegrator(integratorId, nonAuthority.publicKey, name, metadataUri)
+        .accounts(updateAccounts)
+        .signers([nonAuthority])
+        .rpc();
+      assert.fail("Updating integrator by non-authority should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "NotIntegratorAuthority", "Expected NotIntegratorAuthority error");
+    }
+  });
+});
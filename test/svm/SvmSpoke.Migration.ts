@@ -0,0 +1,175 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { common } from "./SvmSpoke.common";
+
+const { provider, connection, program, owner, initializeState, createRoutePda, assert, assertSE } = common;
+
+// Legacy accounts are loaded from test/svm/accounts fixtures in the layout used before the stored bumps were added.
+const legacySeed = new BN(20240101);
+const legacyRouteOriginToken = new PublicKey("HUNSWGCa3VxTAz8SNMT3RYTKcQCWVyR9WpDWqKrQfJ7g");
+const legacyClaimMint = new PublicKey("BFUkjSJ6fLpqL6X7XCZ7HBtnLqyL1QtQMkksTJV7fZcP");
+const legacyClaimRefundAddress = new PublicKey("64nx67TumTc3JqLxYMxkytQ7QzLQmdf13jiFGrDZjPVC");
+
+describe("svm_spoke.migration", () => {
+  anchor.setProvider(provider);
+
+  const [legacyState, legacyStateBump] = PublicKey.findProgramAddressSync(
+    [Buffer.from("state"), legacySeed.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  );
+
+  it("Legacy state cannot be used before migration", async () => {
+    try {
+      await program.account.state.fetch(legacyState);
+      assert.fail("Legacy state should not deserialize before migration");
+    } catch (err: any) {
+      assert.instanceOf(err, Error);
+    }
+  });
+
+  it("Migrates legacy state", async () => {
+    const legacySize = (await connection.getAccountInfo(legacyState))!.data.length;
+
+    await program.methods.migrateState().accounts({ signer: owner, state: legacyState }).rpc();
+
+    const stateAccountInfo = await connection.getAccountInfo(legacyState);
+    assert.isAbove(stateAccountInfo!.data.length, legacySize, "State should be grown to the current layout");
+    const rentExempt = await connection.getMinimumBalanceForRentExemption(stateAccountInfo!.data.length);
+    assert.isAtLeast(stateAccountInfo!.lamports, rentExempt, "State should remain rent exempt");
+
+    // Legacy fields are preserved and the appended fields are set to their defaults and canonical bumps.
+    const stateData = await program.account.state.fetch(legacyState);
+    assertSE(stateData.owner, owner, "Owner should be preserved");
+    assertSE(stateData.seed, legacySeed, "Seed should be preserved");
+    assertSE(stateData.numberOfDeposits, 5, "Number of deposits should be preserved");
+    assertSE(stateData.chainId, new BN(420), "Chain ID should be preserved");
+    assertSE(stateData.rootBundleId, 1, "Root bundle ID should be preserved");
+    assertSE(stateData.fillDeadlineBuffer, 14400, "Fill deadline buffer should be preserved");
+    assertSE(stateData.bump, legacyStateBump, "State bump should be canonical");
+    const [, selfAuthorityBump] = PublicKey.findProgramAddressSync([Buffer.from("self_authority")], program.programId);
+    assertSE(stateData.selfAuthorityBump, selfAuthorityBump, "Self authority bump should be canonical");
+    assert.deepEqual(stateData.fillStatusBackend, { pda: {} }, "Legacy fills should be tracked in fills PDAs");
+    assert.isFalse(stateData.fillTombstonesEnabled, "Fill tombstones should not be required before initialization");
+
+    // Migrated state can be used with the stored bump.
+    await program.methods.pauseDeposits(true).accounts({ signer: owner, state: legacyState }).rpc();
+    assert.isTrue((await program.account.state.fetch(legacyState)).pausedDeposits, "Deposits should be paused");
+  });
+
+  it("Cannot migrate state twice", async () => {
+    try {
+      await program.methods.migrateState().accounts({ signer: owner, state: legacyState }).rpc();
+      assert.fail("Migrating already migrated state should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "AccountAlreadyMigrated", "Expected AccountAlreadyMigrated error");
+    }
+
+    const { state } = await initializeState();
+    try {
+      await program.methods.migrateState().accounts({ signer: owner, state }).rpc();
+      assert.fail("Migrating newly initialized state should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "AccountAlreadyMigrated", "Expected AccountAlreadyMigrated error");
+    }
+  });
+
+  it("Migrates legacy route", async () => {
+    const routeChainId = new BN(1);
+    const route = createRoutePda(legacyRouteOriginToken, legacySeed, routeChainId);
+    const [, routeBump] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("route"),
+        legacyRouteOriginToken.toBytes(),
+        legacySeed.toArrayLike(Buffer, "le", 8),
+        routeChainId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .migrateRoute(legacyRouteOriginToken, routeChainId)
+      .accounts({ signer: owner, state: legacyState, route })
+      .rpc();
+
+    const routeData = await program.account.route.fetch(route);
+    assert.isTrue(routeData.enabled, "Route should remain enabled");
+    assertSE(routeData.bump, routeBump, "Route bump should be canonical");
+  });
+
+  it("Migrates legacy claim account", async () => {
+    const [claimAccount, claimAccountBump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim_account"), legacyClaimMint.toBuffer(), legacyClaimRefundAddress.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .migrateClaimAccount()
+      .accounts({ signer: owner, mint: legacyClaimMint, refundAddress: legacyClaimRefundAddress })
+      .rpc();
+
+    const claimAccountData = await program.account.claimAccount.fetch(claimAccount);
+    assertSE(claimAccountData.amount, new BN(1000000), "Claim amount should be preserved");
+    assertSE(claimAccountData.initializer, owner, "Initializer should be preserved");
+    assertSE(claimAccountData.bump, claimAccountBump, "Claim account bump should be canonical");
+  });
+
+  it("Migrates legacy root bundle", async () => {
+    // Legacy root bundle 0 has leaves 0, 2 and 8 claimed in its two byte claimed bitmap.
+    const rootBundleId = 0;
+    const rootBundleIdBuffer = Buffer.alloc(4);
+    rootBundleIdBuffer.writeUInt32LE(rootBundleId);
+    const [rootBundle, rootBundleBump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("root_bundle"), legacySeed.toArrayLike(Buffer, "le", 8), rootBundleIdBuffer],
+      program.programId
+    );
+    const legacyData = (await connection.getAccountInfo(rootBundle))!.data;
+    const legacyClaimedBitmap = legacyData.subarray(76);
+
+    const nonOwner = Keypair.generate();
+    try {
+      await program.methods
+        .migrateRootBundle(rootBundleId, 3)
+        .accounts({ signer: nonOwner.publicKey, payer: owner, state: legacyState, rootBundle })
+        .signers([nonOwner])
+        .rpc();
+      assert.fail("Non-owner should not migrate root bundles");
+    } catch (err: any) {
+      assert.include(err.toString(), "NotOwner", "Expected NotOwner error");
+    }
+
+    await program.methods
+      .migrateRootBundle(rootBundleId, 3)
+      .accounts({ signer: owner, payer: owner, state: legacyState, rootBundle })
+      .rpc();
+
+    const rootBundleData = await program.account.rootBundle.fetch(rootBundle);
+    assert.deepEqual(rootBundleData.relayerRefundRoot, Array.from(legacyData.subarray(8, 40)), "Refund root");
+    assert.deepEqual(rootBundleData.slowRelayRoot, Array.from(legacyData.subarray(40, 72)), "Slow relay root");
+    assertSE(rootBundleData.payer, owner, "Payer should be set to the migration payer");
+    assertSE(rootBundleData.claimedLeafCount, 3, "Claimed leaves should be counted from the legacy bitmap");
+    assertSE(rootBundleData.relayerRefundLeafCount, 3, "Relayer refund leaf count should be recorded");
+    assertSE(rootBundleData.completionTargetSet, 1, "Completion target should be set");
+    const stateData = await program.account.state.fetch(legacyState);
+    assertSE(
+      rootBundleData.slowFillDeadline,
+      stateData.currentTime + stateData.fillDeadlineBuffer,
+      "Slow fill deadline should be bounded from the migration time"
+    );
+    assertSE(rootBundleData.bump, rootBundleBump, "Root bundle bump should be canonical");
+
+    // The claimed bitmap follows the 142 byte packed root bundle fields.
+    const migratedData = (await connection.getAccountInfo(rootBundle))!.data;
+    assert.deepEqual(migratedData.subarray(8 + 142), legacyClaimedBitmap, "Claimed bitmap should be preserved");
+
+    try {
+      await program.methods
+        .migrateRootBundle(rootBundleId, 3)
+        .accounts({ signer: owner, payer: owner, state: legacyState, rootBundle })
+        .rpc();
+      assert.fail("Migrating already migrated root bundle should fail");
+    } catch (err: any) {
+      assert.include(err.toString(), "AccountAlreadyMigrated", "Expected AccountAlreadyMigrated error");
+    }
+  });
+});
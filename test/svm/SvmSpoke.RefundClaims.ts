@@ -5,6 +5,7 @@ import { assert } from "chai";
 import { common } from "./SvmSpoke.common";
 import { MerkleTree } from "@uma/common/dist/MerkleTree";
 import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   AuthorityType,
   createMint,
   getOrCreateAssociatedTokenAccount,
@@ -12,10 +13,17 @@ import {
   setAuthority,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
-import { RelayerRefundLeafSolana, RelayerRefundLeafType } from "../../src/types/svm";
-import { loadExecuteRelayerRefundLeafParams, readEventsUntilFound, relayerRefundHashFn } from "../../src/svm/web3-v1";
+import { RelayData, RelayerRefundLeafSolana, RelayerRefundLeafType } from "../../src/types/svm";
+import {
+  calculateRelayHashUint8Array,
+  intToU8Array32,
+  loadExecuteRelayerRefundLeafParams,
+  readEventsUntilFound,
+  relayerRefundHashFn,
+} from "../../src/svm/web3-v1";
 
 const { provider, program, owner, initializeState, connection, chainId, assertSE } = common;
+const { relayerRefundLeafCapacity } = common;
 
 describe("svm_spoke.refund_claims", () => {
   anchor.setProvider(provider);
@@ -95,7 +103,10 @@ describe("svm_spoke.refund_claims", () => {
 
     // Relay root bundle
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
-    await program.methods.relayRootBundle(Array.from(root), Array.from(root)).accounts(relayRootBundleAccounts).rpc();
+    await program.methods
+      .relayRootBundle(Array.from(root), Array.from(root), relayerRefundLeafCapacity)
+      .accounts(relayRootBundleAccounts)
+      .rpc();
 
     // Pass claim account as relayer refund address.
     const remainingAccounts = [{ pubkey: claimAccount, isWritable: true, isSigner: false }];
@@ -433,4 +444,160 @@ describe("svm_spoke.refund_claims", () => {
       assert.strictEqual(error.error.origin, "claim_account", "Expected error on claim_account");
     }
   });
+
+  const fillRelayFromClaimAccount = async (outputAmount: BN) => {
+    const recipient = Keypair.generate().publicKey;
+    const recipientTA = (await getOrCreateAssociatedTokenAccount(connection, payer, mint, recipient)).address;
+    const relayData: RelayData = {
+      depositor: recipient,
+      recipient,
+      exclusiveRelayer: PublicKey.default,
+      inputToken: mint,
+      outputToken: mint,
+      inputAmount: outputAmount,
+      outputAmount,
+      originChainId: new BN(1),
+      depositId: intToU8Array32(Math.floor(Math.random() * 1000000)),
+      fillDeadline: Math.floor(Date.now() / 1000) + 60,
+      exclusivityDeadline: 0,
+      message: Buffer.alloc(0),
+    };
+    const relayHash = calculateRelayHashUint8Array(relayData, chainId);
+    const [fillStatus] = PublicKey.findProgramAddressSync([Buffer.from("fills"), relayHash], program.programId);
+    const fillAccounts = {
+      fill: {
+        signer: relayer.publicKey,
+        instructionParams: program.programId,
+        state,
+        mint,
+        relayerTokenAccount: tokenAccount,
+        recipientTokenAccount: recipientTA,
+        fillStatus,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        program: program.programId,
+      },
+      vault,
+      claimAccount,
+    };
+    const tx = await program.methods
+      .fillRelayFromClaimAccount(Array.from(relayHash), relayData, new BN(1), relayer.publicKey, null)
+      .accounts(fillAccounts)
+      .signers([relayer])
+      .rpc();
+    return { tx, recipientTA };
+  };
+
+  it("Relayer fills from claim account", async () => {
+    const relayerRefund = new BN(500000);
+    const outputAmount = new BN(300000);
+    await executeRelayerRefundToClaim(relayerRefund);
+    await connection.confirmTransaction(await connection.requestAirdrop(relayer.publicKey, 10_000_000_000));
+
+    const iVaultBal = (await connection.getTokenAccountBalance(vault)).value.amount;
+    const iRelayerBal = (await connection.getTokenAccountBalance(tokenAccount)).value.amount;
+
+    const { tx, recipientTA } = await fillRelayFromClaimAccount(outputAmount);
+
+    // The recipient should be paid from the vault without touching the relayer token account.
+    const fVaultBal = (await connection.getTokenAccountBalance(vault)).value.amount;
+    const fRelayerBal = (await connection.getTokenAccountBalance(tokenAccount)).value.amount;
+    const fRecipientBal = (await connection.getTokenAccountBalance(recipientTA)).value.amount;
+    assertSE(BigInt(iVaultBal) - BigInt(fVaultBal), outputAmount, "Vault balance");
+    assertSE(fRelayerBal, iRelayerBal, "Relayer balance");
+    assertSE(fRecipientBal, outputAmount, "Recipient balance");
+    const claimAccountData = await program.account.claimAccount.fetch(claimAccount);
+    assertSE(claimAccountData.amount, relayerRefund.sub(outputAmount), "Claim account should be debited");
+
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    const event = events.find((event) => event.name === "claimedRelayerRefund")?.data;
+    assertSE(event.claimAmount, outputAmount, "Claimed amount should match the output amount");
+    assertSE(event.refundAddress, relayer.publicKey, "Relayer refund address should match");
+    assert.isDefined(events.find((event) => event.name === "filledRelay"), "FilledRelay should be emitted");
+  });
+
+  it("Cannot fill more than the claim account balance", async () => {
+    const relayerRefund = new BN(500000);
+    await executeRelayerRefundToClaim(relayerRefund);
+    await connection.confirmTransaction(await connection.requestAirdrop(relayer.publicKey, 10_000_000_000));
+
+    try {
+      await fillRelayFromClaimAccount(relayerRefund.addn(1));
+      assert.fail("Fill should fail when claim account balance is insufficient");
+    } catch (error: any) {
+      assert.include(
+        error.toString(),
+        "InsufficientClaimAccountBalance",
+        "Expected InsufficientClaimAccountBalance error"
+      );
+    }
+  });
+
+  it("Claims relayer refunds in multiple tokens at once", async () => {
+    // Accrue refunds in the first token.
+    const relayerRefunds = [new BN(500000), new BN(700000)];
+    await executeRelayerRefundToClaim(relayerRefunds[0]);
+    const claims = [{ mint, vault, claimAccount, tokenAccount }];
+
+    // Accrue refunds in the second token, reusing the test helpers on the new mint.
+    mint = await createMint(connection, payer, owner, owner, 6);
+    vault = (await getOrCreateAssociatedTokenAccount(connection, payer, mint, state, true)).address;
+    await mintTo(connection, payer, mint, vault, provider.publicKey, initialMintAmount);
+    tokenAccount = (await getOrCreateAssociatedTokenAccount(connection, payer, mint, relayer.publicKey)).address;
+    [claimAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim_account"), mint.toBuffer(), relayer.publicKey.toBuffer()],
+      program.programId
+    );
+    [transferLiability] = PublicKey.findProgramAddressSync(
+      [Buffer.from("transfer_liability"), mint.toBuffer()],
+      program.programId
+    );
+    await executeRelayerRefundToClaim(relayerRefunds[1]);
+    claims.push({ mint, vault, claimAccount, tokenAccount });
+
+    const claimRelayerRefundsAccounts = {
+      signer: owner,
+      refundAddress: relayer.publicKey,
+      state,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      program: program.programId,
+    };
+    const remainingAccounts = claims.flatMap((claim) => [
+      { pubkey: claim.mint, isWritable: false, isSigner: false },
+      { pubkey: claim.vault, isWritable: true, isSigner: false },
+      { pubkey: claim.claimAccount, isWritable: true, isSigner: false },
+      { pubkey: claim.tokenAccount, isWritable: true, isSigner: false },
+    ]);
+    const tx = await program.methods
+      .claimRelayerRefunds()
+      .accounts(claimRelayerRefundsAccounts)
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    const claimEvents = events.filter((event) => event.name === "claimedRelayerRefund");
+    assertSE(claimEvents.length, claims.length, "One event should be emitted per mint");
+    for (const [i, claim] of claims.entries()) {
+      const relayerBal = (await connection.getTokenAccountBalance(claim.tokenAccount)).value.amount;
+      assertSE(relayerBal, relayerRefunds[i], `Relayer balance for mint ${i}`);
+      const claimAccountData = await program.account.claimAccount.fetch(claim.claimAccount);
+      assertSE(claimAccountData.amount, 0, `Claim account for mint ${i} should be reset`);
+      assertSE(claimEvents[i].data.l2TokenAddress, claim.mint, `l2TokenAddress for mint ${i} should match`);
+      assertSE(claimEvents[i].data.claimAmount, relayerRefunds[i], `Claim amount for mint ${i} should match`);
+      assertSE(claimEvents[i].data.refundAddress, relayer.publicKey, "Relayer refund address should match");
+    }
+
+    // Claims are reset, so claiming again fails.
+    try {
+      await program.methods
+        .claimRelayerRefunds()
+        .accounts(claimRelayerRefundsAccounts)
+        .remainingAccounts(remainingAccounts)
+        .rpc();
+      assert.fail("Claiming reset claim accounts should fail");
+    } catch (error: any) {
+      assert.include(error.toString(), "ZeroRefundClaim", "Expected ZeroRefundClaim error");
+    }
+  });
 });
@@ -0,0 +1,306 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN } from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createApproveCheckedInstruction,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Keypair, PublicKey, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import { ethers } from "ethers";
+import { calculateRelayHashUint8Array, readEventsUntilFound } from "../../src/svm/web3-v1";
+import { RelayData } from "../../src/types/svm";
+import { common } from "./SvmSpoke.common";
+
+const { provider, connection, program, owner, chainId, seedBalance, initializeState, depositData } = common;
+const { createRoutePda, getVaultAta, setCurrentTime, assert, assertSE } = common;
+
+describe("svm_spoke.same_chain", () => {
+  anchor.setProvider(provider);
+
+  const payer = (anchor.AnchorProvider.env().wallet as anchor.Wallet).payer;
+  const depositor = Keypair.generate();
+  const relayer = Keypair.generate();
+  const recipient = Keypair.generate().publicKey;
+  const tokenDecimals = 6;
+  const inputAmount = new BN(500000);
+  const outputAmount = new BN(490000);
+
+  let state: PublicKey, seed: BN, route: PublicKey, vault: PublicKey, eventAuthority: PublicKey;
+  let inputToken: PublicKey, outputToken: PublicKey;
+  let depositorTA: PublicKey, relayerInputTA: PublicKey, relayerOutputTA: PublicKey, recipientTA: PublicKey;
+  let depositNonce: BN;
+
+  const getSameChainDepositPda = (depositId: Uint8Array) => {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("same_chain_deposit"), seed.toArrayLike(Buffer, "le", 8), Buffer.from(depositId)],
+      program.programId
+    )[0];
+  };
+
+  const getUnsafeDepositId = (signer: PublicKey, nonce: BN) => {
+    const nonceBytes = nonce.toArrayLike(Buffer, "le", 8);
+    const data = ethers.utils.concat([signer.toBytes(), depositor.publicKey.toBytes(), nonceBytes]);
+    return ethers.utils.arrayify(ethers.utils.keccak256(data));
+  };
+
+  const depositSameChain = async (destinationChainId: BN = chainId) => {
+    const depositAccounts = {
+      deposit: {
+        signer: depositor.publicKey,
+        state,
+        route,
+        depositorTokenAccount: depositorTA,
+        vault,
+        mint: inputToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        eventAuthority,
+        program: program.programId,
+      },
+      sameChainDeposit: getSameChainDepositPda(getUnsafeDepositId(depositor.publicKey, depositNonce)),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+    const approveIx = createApproveCheckedInstruction(
+      depositorTA,
+      inputToken,
+      state,
+      depositor.publicKey,
+      BigInt(inputAmount.toString()),
+      tokenDecimals
+    );
+    const depositIx = await program.methods
+      .depositSameChain({
+        depositor: depositor.publicKey,
+        recipient,
+        inputToken,
+        outputToken,
+        inputAmount,
+        outputAmount,
+        destinationChainId,
+        exclusiveRelayer: PublicKey.default,
+        depositNonce,
+        quoteTimestamp: depositData.quoteTimestamp,
+        fillDeadline: depositData.fillDeadline,
+        exclusivityParameter: 0,
+        message: Buffer.from(""),
+      })
+      .accounts(depositAccounts)
+      .instruction();
+    return sendAndConfirmTransaction(connection, new Transaction().add(approveIx, depositIx), [payer, depositor]);
+  };
+
+  // Reconstructs the relay data from the FundsDeposited event as a relayer would do.
+  const getRelayData = async (depositTx: string): Promise<RelayData> => {
+    const events = await readEventsUntilFound(connection, depositTx, [program]);
+    const event = events.find((event) => event.name === "fundsDeposited")?.data;
+    return {
+      depositor: event.depositor,
+      recipient: event.recipient,
+      exclusiveRelayer: event.exclusiveRelayer,
+      inputToken: event.inputToken,
+      outputToken: event.outputToken,
+      inputAmount: event.inputAmount,
+      outputAmount: event.outputAmount,
+      originChainId: chainId,
+      depositId: event.depositId,
+      fillDeadline: event.fillDeadline,
+      exclusivityDeadline: event.exclusivityDeadline,
+      message: event.message,
+    };
+  };
+
+  const getFillAccounts = (relayHash: Uint8Array) => {
+    const [fillStatus] = PublicKey.findProgramAddressSync([Buffer.from("fills"), relayHash], program.programId);
+    return {
+      signer: relayer.publicKey,
+      instructionParams: program.programId,
+      state,
+      mint: outputToken,
+      relayerTokenAccount: relayerOutputTA,
+      recipientTokenAccount: recipientTA,
+      fillStatus,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      eventAuthority,
+      program: program.programId,
+    };
+  };
+
+  const fillSameChainRelay = async (relayData: RelayData, sameChainDeposit: PublicKey) => {
+    const relayHash = calculateRelayHashUint8Array(relayData, chainId);
+    const fillAccounts = {
+      fill: getFillAccounts(relayHash),
+      sameChainDeposit,
+      depositPayer: depositor.publicKey,
+      inputMint: inputToken,
+      vault,
+      relayerInputTokenAccount: relayerInputTA,
+      inputTokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const approveIx = createApproveCheckedInstruction(
+      relayerOutputTA,
+      outputToken,
+      state,
+      relayer.publicKey,
+      BigInt(relayData.outputAmount.toString()),
+      tokenDecimals
+    );
+    const fillIx = await program.methods
+      .fillSameChainRelay(Array.from(relayHash), relayData, chainId, relayer.publicKey, null)
+      .accounts(fillAccounts)
+      .instruction();
+    return sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [payer, relayer]);
+  };
+
+  before("Funds depositor and relayer wallets", async () => {
+    await connection.requestAirdrop(depositor.publicKey, 10_000_000_000); // 10 SOL
+    await connection.requestAirdrop(relayer.publicKey, 10_000_000_000); // 10 SOL
+  });
+
+  beforeEach(async () => {
+    ({ state, seed } = await initializeState());
+    [eventAuthority] = PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], program.programId);
+    depositNonce = new BN(Math.floor(Math.random() * 1000000));
+
+    inputToken = await createMint(connection, payer, owner, owner, tokenDecimals);
+    outputToken = await createMint(connection, payer, owner, owner, tokenDecimals);
+
+    // Enable the route from the input token to this chain.
+    route = createRoutePda(inputToken, seed, chainId);
+    vault = await getVaultAta(inputToken, state);
+    await program.methods
+      .setEnableRoute(inputToken, chainId, true)
+      .accounts({
+        signer: owner,
+        payer: owner,
+        state,
+        route,
+        vault,
+        originTokenMint: inputToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    depositorTA = (await getOrCreateAssociatedTokenAccount(connection, payer, inputToken, depositor.publicKey)).address;
+    relayerInputTA = (await getOrCreateAssociatedTokenAccount(connection, payer, inputToken, relayer.publicKey))
+      .address;
+    relayerOutputTA = (await getOrCreateAssociatedTokenAccount(connection, payer, outputToken, relayer.publicKey))
+      .address;
+    recipientTA = (await getOrCreateAssociatedTokenAccount(connection, payer, outputToken, recipient)).address;
+    await mintTo(connection, payer, inputToken, depositorTA, owner, seedBalance);
+    await mintTo(connection, payer, outputToken, relayerOutputTA, owner, seedBalance);
+  });
+
+  it("Fills a same chain deposit and releases the escrowed input to the relayer", async () => {
+    const depositTx = await depositSameChain();
+    const relayData = await getRelayData(depositTx);
+    const sameChainDeposit = getSameChainDepositPda(Uint8Array.from(relayData.depositId));
+
+    let vaultAccount = await getAccount(connection, vault);
+    assertSE(vaultAccount.amount, inputAmount, "Vault should hold the escrowed deposit");
+    const sameChainDepositData = await program.account.sameChainDeposit.fetch(sameChainDeposit);
+    assertSE(
+      Buffer.from(sameChainDepositData.relayHash).toString("hex"),
+      Buffer.from(calculateRelayHashUint8Array(relayData, chainId)).toString("hex"),
+      "Escrow should record the relay hash"
+    );
+    assertSE(sameChainDepositData.payer, depositor.publicKey, "Escrow payer should be the deposit signer");
+
+    const fillTx = await fillSameChainRelay(relayData, sameChainDeposit);
+
+    const recipientAccount = await getAccount(connection, recipientTA);
+    assertSE(recipientAccount.amount, outputAmount, "Recipient should receive the output amount");
+    const relayerInputAccount = await getAccount(connection, relayerInputTA);
+    assertSE(relayerInputAccount.amount, inputAmount, "Relayer should receive the escrowed input amount");
+    vaultAccount = await getAccount(connection, vault);
+    assertSE(vaultAccount.amount, "0", "Vault should release the escrowed deposit");
+    assert.isNull(await connection.getAccountInfo(sameChainDeposit), "Escrow record should be closed");
+
+    const events = await readEventsUntilFound(connection, fillTx, [program]);
+    const settledEvent = events.find((event) => event.name === "settledSameChainRelay")?.data;
+    assertSE(settledEvent.relayer, relayer.publicKey, "Relayer should match");
+    assertSE(settledEvent.inputToken, inputToken, "Input token should match");
+    assertSE(settledEvent.inputAmount, inputAmount, "Input amount should match");
+    assert.isDefined(
+      events.find((event) => event.name === "filledRelay"),
+      "FilledRelay should be emitted next to the settlement"
+    );
+  });
+
+  it("Fails to fill a same chain deposit with modified relay data", async () => {
+    const depositTx = await depositSameChain();
+    const relayData = await getRelayData(depositTx);
+    const sameChainDeposit = getSameChainDepositPda(Uint8Array.from(relayData.depositId));
+
+    try {
+      await fillSameChainRelay({ ...relayData, inputAmount: inputAmount.muln(2) }, sameChainDeposit);
+      assert.fail("Fill should have failed due to relay hash mismatch");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidRelayHash", "Expected InvalidRelayHash error");
+    }
+  });
+
+  it("Fails to fill a same chain deposit without releasing the escrow", async () => {
+    const depositTx = await depositSameChain();
+    const relayData = await getRelayData(depositTx);
+    const relayHash = calculateRelayHashUint8Array(relayData, chainId);
+
+    const approveIx = createApproveCheckedInstruction(
+      relayerOutputTA,
+      outputToken,
+      state,
+      relayer.publicKey,
+      BigInt(relayData.outputAmount.toString()),
+      tokenDecimals
+    );
+    const fillIx = await program.methods
+      .fillRelay(Array.from(relayHash), relayData, chainId, relayer.publicKey, null)
+      .accounts(getFillAccounts(relayHash))
+      .instruction();
+    try {
+      await sendAndConfirmTransaction(connection, new Transaction().add(approveIx, fillIx), [payer, relayer]);
+      assert.fail("Fill should have failed as same chain relays require the escrow settlement");
+    } catch (err: any) {
+      assert.include(err.toString(), "SameChainRelayRequiresEscrow", "Expected SameChainRelayRequiresEscrow error");
+    }
+    const recipientAccount = await getAccount(connection, recipientTA);
+    assertSE(recipientAccount.amount, "0", "Recipient should not receive the output amount");
+  });
+
+  it("Fails to make a same chain deposit to another chain", async () => {
+    try {
+      await depositSameChain(chainId.addn(1));
+      assert.fail("Deposit should have failed due to invalid destination");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidSameChainDestination", "Expected InvalidSameChainDestination error");
+    }
+  });
+
+  it("Closes unfilled same chain deposit only after fill deadline", async () => {
+    const depositTx = await depositSameChain();
+    const relayData = await getRelayData(depositTx);
+    const sameChainDeposit = getSameChainDepositPda(Uint8Array.from(relayData.depositId));
+    const closeAccounts = { signer: relayer.publicKey, state, sameChainDeposit, depositPayer: depositor.publicKey };
+
+    try {
+      await program.methods.closeSameChainDeposit().accounts(closeAccounts).signers([relayer]).rpc();
+      assert.fail("Closing should have failed before the fill deadline");
+    } catch (err: any) {
+      assert.include(
+        err.toString(),
+        "CanOnlyCloseSameChainDepositIfFillDeadlinePassed",
+        "Expected CanOnlyCloseSameChainDepositIfFillDeadlinePassed error"
+      );
+    }
+
+    await setCurrentTime(program, state, relayer, new BN(relayData.fillDeadline + 1));
+    await program.methods.closeSameChainDeposit().accounts(closeAccounts).signers([relayer]).rpc();
+    assert.isNull(await connection.getAccountInfo(sameChainDeposit), "Escrow record should be closed");
+  });
+});
@@ -41,7 +41,7 @@ import {
   SlowFillLeaf,
 } from "../../src/types/svm";
 const { provider, connection, program, owner, chainId, setCurrentTime } = common;
-const { initializeState, assertSE, assert } = common;
+const { initializeState, assertSE, assert, relayerRefundLeafCapacity } = common;
 
 describe("svm_spoke.slow_fill.across_plus", () => {
   anchor.setProvider(provider);
@@ -122,7 +122,7 @@ describe("svm_spoke.slow_fill.across_plus", () => {
     // Relay root bundle
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
     await program.methods
-      .relayRootBundle(Array.from(relayerRefundRoot), Array.from(slowRelayRoot))
+      .relayRootBundle(Array.from(relayerRefundRoot), Array.from(slowRelayRoot), relayerRefundLeafCapacity)
       .accounts(relayRootBundleAccounts)
       .rpc();
 
@@ -8,6 +8,7 @@ import {
   getOrCreateAssociatedTokenAccount,
   mintTo,
   createApproveCheckedInstruction,
+  closeAccount,
 } from "@solana/spl-token";
 import { PublicKey, Keypair, Transaction, sendAndConfirmTransaction, ComputeBudgetProgram } from "@solana/web3.js";
 import { common } from "./SvmSpoke.common";
@@ -23,7 +24,7 @@ import {
 import { testAcrossPlusMessage } from "./utils";
 
 const { provider, connection, program, owner, chainId, seedBalance, initializeState } = common;
-const { recipient, setCurrentTime, assertSE, assert } = common;
+const { recipient, setCurrentTime, assertSE, assert, relayerRefundLeafCapacity } = common;
 
 describe("svm_spoke.slow_fill", () => {
   anchor.setProvider(provider);
@@ -129,7 +130,7 @@ describe("svm_spoke.slow_fill", () => {
     // Relay root bundle
     const relayRootBundleAccounts = { state, rootBundle, signer: owner, payer: owner, program: program.programId };
     await program.methods
-      .relayRootBundle(Array.from(relayerRefundRoot), Array.from(slowRelayRoot))
+      .relayRootBundle(Array.from(relayerRefundRoot), Array.from(slowRelayRoot), relayerRefundLeafCapacity)
       .accounts(relayRootBundleAccounts)
       .rpc();
 
@@ -229,7 +230,7 @@ describe("svm_spoke.slow_fill", () => {
       tokenDecimals
     );
     const fillIx = await program.methods
-      .fillRelay(relayHash, relayData, new BN(1), relayer.publicKey)
+      .fillRelay(relayHash, relayData, new BN(1), relayer.publicKey, null)
       .accounts(fillAccounts)
       .remainingAccounts(fillRemainingAccounts)
       .instruction();
@@ -400,6 +401,64 @@ describe("svm_spoke.slow_fill", () => {
     assertSE(event.relayer, PublicKey.default, "Repayment address should be 0");
   });
 
+  it("Creates the recipient ATA within the slow fill when the payer is appended", async () => {
+    // Close the recipient ATA created when relaying the root bundle so that the slow fill has to create it.
+    const newRecipient = Keypair.generate();
+    const { relayHash, leaf, rootBundleId, proofAsNumbers, rootBundle } = await relaySlowFillRootBundle(
+      newRecipient.publicKey,
+      chainId,
+      Buffer.alloc(0)
+    );
+    await closeAccount(connection, payer, recipientTA, payer.publicKey, newRecipient);
+    await program.methods
+      .requestSlowFill(Array.from(relayHash), leaf.relayData)
+      .accounts(requestAccounts)
+      .signers([relayer])
+      .rpc();
+
+    // The executor signer is not writable, so the recipient ATA cannot be created without the appended payer.
+    const executeSlowRelayLeafAccounts = {
+      state,
+      rootBundle,
+      signer: otherRelayer.publicKey,
+      instructionParams: program.programId,
+      fillStatus: requestAccounts.fillStatus,
+      vault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      mint,
+      recipientTokenAccount: recipientTA,
+      recipient: newRecipient.publicKey,
+      program: program.programId,
+    };
+    try {
+      await program.methods
+        .executeSlowRelayLeaf(Array.from(relayHash), leaf, rootBundleId, proofAsNumbers)
+        .accounts(executeSlowRelayLeafAccounts)
+        .signers([otherRelayer])
+        .rpc();
+      assert.fail("Execution should have failed due to the missing recipient ATA payer");
+    } catch (err: any) {
+      assert.include(err.toString(), "AccountNotInitialized", "Expected AccountNotInitialized error");
+    }
+
+    const iPayerLamports = await connection.getBalance(owner);
+    const tx = await program.methods
+      .executeSlowRelayLeaf(Array.from(relayHash), leaf, rootBundleId, proofAsNumbers)
+      .accounts({ ...executeSlowRelayLeafAccounts, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, payer: owner })
+      .signers([otherRelayer])
+      .rpc();
+
+    const fRecipientBal = (await connection.getTokenAccountBalance(recipientTA)).value.amount;
+    assertSE(fRecipientBal, leaf.updatedOutputAmount, "Recipient balance should be increased by relay amount");
+
+    // The rent paid by the payer for the ATA is reported in the FilledRelay event.
+    const ataRent = await connection.getBalance(recipientTA);
+    const events = await readEventsUntilFound(connection, tx, [program]);
+    const event = events.find((event) => event.name === "filledRelay")?.data;
+    assertSE(event.recipientTokenAccountRent, ataRent, "Recipient token account rent should match");
+    assert.isAtMost(await connection.getBalance(owner), iPayerLamports - ataRent, "Payer should pay the ATA rent");
+  });
+
   it("Fails to request a V3 slow fill when fills are paused", async () => {
     // Pause fills
     const pauseFillsAccounts = {
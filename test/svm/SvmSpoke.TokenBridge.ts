@@ -17,6 +17,7 @@ import { TokenMessengerMinter } from "../../target/types/token_messenger_minter"
 import { common } from "./SvmSpoke.common";
 
 const { provider, program, owner, initializeState, connection, remoteDomain, chainId, crossDomainAdmin } = common;
+const { relayerRefundLeafCapacity } = common;
 
 describe("svm_spoke.token_bridge", () => {
   anchor.setProvider(provider);
@@ -166,7 +167,7 @@ describe("svm_spoke.token_bridge", () => {
       program: program.programId,
     };
     await program.methods
-      .relayRootBundle(Array.from(root), Array.from(Buffer.alloc(32)))
+      .relayRootBundle(Array.from(root), Array.from(Buffer.alloc(32)), relayerRefundLeafCapacity)
       .accounts(relayRootBundleAccounts)
       .rpc();
 
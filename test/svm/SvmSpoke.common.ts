@@ -30,6 +30,7 @@ const exclusivityParameter = new BN(0); // 0 means no exclusivity and disables t
 const message = Buffer.from("Test message");
 const depositQuoteTimeBuffer = new BN(3600); // 1 hour.
 const fillDeadlineBuffer = new BN(3600 * 4); // 4 hours.
+const relayerRefundLeafCapacity = 256; // Covers the relayer refund leaf IDs used in tests.
 
 const initializeState = async (
   seed?: BN,
@@ -128,6 +129,7 @@ export const common = {
   message,
   depositQuoteTimeBuffer,
   fillDeadlineBuffer,
+  relayerRefundLeafCapacity,
   initializeState,
   createRoutePda,
   getVaultAta,